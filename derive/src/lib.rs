@@ -0,0 +1,173 @@
+//! Derive macro for the [`user_dirs`](https://docs.rs/user_dirs) crate. Use it through the `derive` feature of
+//! `user_dirs` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/// The directory kinds accepted in `#[user_dir(...)]`, with their `user_dirs::DirKind` variants.
+const KINDS: [(&str, &str); 6] = [
+    ("home", "Home"),
+    ("cache", "Cache"),
+    ("config", "Config"),
+    ("data", "Data"),
+    ("runtime", "Runtime"),
+    ("state", "State"),
+];
+
+/// Generates a `fn resolve() -> Result<Self, user_dirs::UserDirsError>` constructor populating the annotated fields.
+///
+/// Fields are annotated with `#[user_dir(<kind>)]`, where `<kind>` is one of `home`, `cache`, `config`, `data`,
+/// `runtime` or `state`, and optionally `app_name = "..."` to append an application subdirectory. An `app_name` on
+/// the struct applies to every field that doesn't set its own. `PathBuf` fields fail with
+/// `UserDirsError::Unavailable` if the directory doesn't exist on the platform, while `Option<PathBuf>` fields are set
+/// to `None`. Fields without an annotation are initialized with [`Default::default`].
+///
+/// ```rust,ignore
+/// #[derive(user_dirs::UserDir)]
+/// #[user_dir(app_name = "myapp")]
+/// struct Paths {
+///     #[user_dir(config)]
+///     config: PathBuf,
+///     #[user_dir(state)]
+///     state: Option<PathBuf>,
+///     #[user_dir(cache, app_name = "myapp-thumbnails")]
+///     thumbnails: PathBuf,
+/// }
+///
+/// let paths = Paths::resolve()?;
+/// ```
+#[proc_macro_derive(UserDir, attributes(user_dir))]
+pub fn derive_user_dir(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Annotation {
+    kind: Option<Ident>,
+    app_name: Option<LitStr>,
+}
+
+fn parse_annotation(attrs: &[syn::Attribute]) -> syn::Result<Option<Annotation>> {
+    let mut annotation = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("user_dir")) {
+        let annotation = annotation.get_or_insert_with(Annotation::default);
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("app_name") {
+                annotation.app_name = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+
+            let Some(ident) = meta.path.get_ident() else {
+                return Err(meta.error("expected a directory kind or `app_name`"));
+            };
+            if !KINDS.iter().any(|(name, _)| ident == name) {
+                return Err(meta.error(format!(
+                    "unknown directory kind `{ident}`, expected one of {}",
+                    KINDS.map(|(name, _)| format!("`{name}`")).join(", ")
+                )));
+            }
+            if annotation.kind.replace(ident.clone()).is_some() {
+                return Err(meta.error("only one directory kind can be given"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(annotation)
+}
+
+/// Returns whether `ty` is spelled as an `Option<...>`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`UserDir` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`UserDir` can only be derived for structs with named fields",
+        ));
+    };
+
+    let default_app_name = match parse_annotation(&input.attrs)? {
+        Some(Annotation {
+            kind: Some(kind), ..
+        }) => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "directory kinds can only be given on fields",
+            ))
+        }
+        Some(annotation) => annotation.app_name,
+        None => None,
+    };
+
+    let initializers = fields
+        .named
+        .iter()
+        .map(|field| {
+            let name = field.ident.as_ref().expect("fields are named");
+            let Some(annotation) = parse_annotation(&field.attrs)? else {
+                return Ok(quote! { #name: ::core::default::Default::default() });
+            };
+            let Some(kind) = annotation.kind else {
+                return Err(syn::Error::new(field.span(), "missing directory kind"));
+            };
+
+            let variant = KINDS
+                .iter()
+                .find(|(name, _)| kind == name)
+                .map(|(_, variant)| Ident::new(variant, kind.span()))
+                .expect("kinds are validated while parsing");
+            let app_name = annotation
+                .app_name
+                .or_else(|| default_app_name.clone())
+                .map_or_else(
+                    || quote! { ::core::option::Option::None },
+                    |app_name| quote! { ::core::option::Option::Some(#app_name) },
+                );
+            let resolve = if is_option(&field.ty) {
+                quote! { optional }
+            } else {
+                quote! { required }
+            };
+
+            Ok(quote! {
+                #name: ::user_dirs::__private::#resolve(::user_dirs::DirKind::#variant, #app_name)?
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Resolves every field annotated with `#[user_dir(...)]`.
+            pub fn resolve() -> ::core::result::Result<Self, ::user_dirs::UserDirsError> {
+                ::core::result::Result::Ok(Self {
+                    #(#initializers,)*
+                })
+            }
+        }
+    })
+}
@@ -1,8 +1,14 @@
+use user_dirs::test_util::EnvOverride;
+
 fn main() {
-    std::env::set_var("XDG_CONFIG_HOME", "foo");
-    println!("With XDG_CONFIG_HOME set: {:?}", user_dirs::config_dir());
+    {
+        let config = std::env::temp_dir().join("usage-config");
+        let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(&config))]);
+        println!("With XDG_CONFIG_HOME set: {:?}", user_dirs::config_dir());
+        assert_eq!(user_dirs::config_dir().unwrap(), config);
+    }
 
-    std::env::remove_var("XDG_CONFIG_HOME");
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", None::<&str>)]);
     println!("With XDG_CONFIG_HOME unset: {:?}", user_dirs::config_dir());
     assert_eq!(
         user_dirs::config_dir().unwrap(),
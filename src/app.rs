@@ -0,0 +1,556 @@
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config_dir,
+    env::{self, Env, ProcessEnv, TargetOs},
+    explain, runtime, DirKind, DirSource, Explanation, PortableDirs, PortableOptions, UserDirs,
+    UserDirsError,
+};
+
+/// The default name of the directory profiles are kept in, see [`AppDirs::with_profile`].
+const PROFILES_DIR: &str = "profiles";
+
+/// The directories of a single application, each a subdirectory of the corresponding [`UserDirs`] directory.
+///
+/// The subdirectory is named after the application, normalized with [`app_name_normalize`] to lowercase with
+/// whitespace replaced by hyphens and anything other than ASCII letters, digits, `-`, `_` and `.` removed, so
+/// `"My App"` becomes `my-app`.
+///
+/// Applications that keep several profiles apart, like browsers do, can select one with [`AppDirs::with_profile`].
+/// Users can be allowed to move each directory on its own with [`AppDirs::with_env_overrides`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirs {
+    base: UserDirs,
+    name: String,
+    env_prefix: String,
+    env_overrides: bool,
+    env_dirs: Vec<(DirKind, PathBuf)>,
+    profile: Option<String>,
+    profiles_dir: String,
+    portable: Option<PortableDirs>,
+    cache: PathBuf,
+    config: PathBuf,
+    data: PathBuf,
+    runtime: Option<PathBuf>,
+    state: Option<PathBuf>,
+}
+
+impl AppDirs {
+    /// Resolves every directory for the application `app_name`.
+    ///
+    /// This fails with [`UserDirsError::InvalidAppName`] if nothing is left of the name after normalization.
+    pub fn new(app_name: &str) -> Result<AppDirs, UserDirsError> {
+        UserDirs::new()?.into_app_dirs(app_name)
+    }
+
+    fn from_base(base: UserDirs, app_name: &str) -> Result<AppDirs, UserDirsError> {
+        let name = app_dir_name(app_name)?;
+        let mut dirs = AppDirs {
+            base,
+            env_prefix: env_prefix(&name),
+            name,
+            env_overrides: false,
+            env_dirs: Vec::new(),
+            profile: None,
+            profiles_dir: PROFILES_DIR.to_owned(),
+            portable: None,
+            cache: PathBuf::new(),
+            config: PathBuf::new(),
+            data: PathBuf::new(),
+            runtime: None,
+            state: None,
+        };
+        dirs.locate();
+        Ok(dirs)
+    }
+
+    /// Resolves every directory from the base directories, the application's name and the profile.
+    fn locate(&mut self) {
+        self.env_dirs = if self.env_overrides {
+            OVERRIDABLE
+                .into_iter()
+                .filter_map(|kind| {
+                    let dir = env_dir(&self.override_var(kind)?, &ProcessEnv)?;
+                    Some((kind, dir))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let locate = |kind| {
+            let mut dir = self.root(kind)?;
+            if let Some(profile) = &self.profile {
+                dir.push(&self.profiles_dir);
+                dir.push(profile);
+            }
+            Some(dir)
+        };
+
+        let [cache, config, data, runtime, state] = [
+            DirKind::Cache,
+            DirKind::Config,
+            DirKind::Data,
+            DirKind::Runtime,
+            DirKind::State,
+        ]
+        .map(locate);
+
+        // The cache, config and data directories are always available.
+        self.cache = cache.unwrap_or_default();
+        self.config = config.unwrap_or_default();
+        self.data = data.unwrap_or_default();
+        self.runtime = runtime;
+        self.state = state;
+    }
+
+    /// Returns the application's directory of the given kind, without the profile.
+    fn root(&self, kind: DirKind) -> Option<PathBuf> {
+        if let Some(dir) = self.env_dir(kind) {
+            return Some(dir.to_path_buf());
+        }
+        match (&self.portable, kind) {
+            (_, DirKind::Home) => None,
+            (Some(portable), DirKind::Cache) => Some(portable.cache()),
+            (Some(portable), DirKind::Config) => Some(portable.config()),
+            (Some(portable), DirKind::Data) => Some(portable.data()),
+            (Some(portable), DirKind::State) => Some(portable.state()),
+            _ => Some(self.base.get(kind)?.join(&self.name)),
+        }
+    }
+
+    /// Switches to the directories next to the executable if portable mode is enabled, see [`PortableOptions`].
+    ///
+    /// In portable mode, the cache, config, data and state directories are those of [`PortableDirs`], such as
+    /// `<exe_dir>/config`; the runtime directory stays in the user's runtime directory. Profiles apply inside them as
+    /// usual. Nothing changes unless a rule of `options` holds, so portable mode can't be enabled by accident.
+    ///
+    /// This fails with [`UserDirsError::CurrentExe`] if `<APP>_PORTABLE=1` is set but the executable can't be located;
+    /// without the variable, an executable that can't be located simply means portable mode is off.
+    pub fn with_portable_mode(
+        mut self,
+        options: &PortableOptions,
+    ) -> Result<AppDirs, UserDirsError> {
+        self.portable = options.detect(&self.env_prefix)?;
+        self.locate();
+        Ok(self)
+    }
+
+    /// Returns the portable directories in use, if portable mode is enabled.
+    #[must_use]
+    pub fn portable(&self) -> Option<&PortableDirs> {
+        self.portable.as_ref()
+    }
+
+    /// Selects the profile `profile`, whose directories are `profiles/<profile>` inside each of the application's.
+    ///
+    /// The profile's name is normalized like the application's. An empty name or `default` selects no profile, so the
+    /// directories are exactly those without profile support. This fails with [`UserDirsError::InvalidProfileName`]
+    /// if nothing is left of any other name after normalization.
+    ///
+    /// ```rust
+    /// use user_dirs::AppDirs;
+    ///
+    /// let dirs = AppDirs::new("My App")?;
+    /// let work = dirs.clone().with_profile("Work")?;
+    ///
+    /// assert_eq!(work.config(), dirs.config().join("profiles").join("work"));
+    /// # Ok::<(), user_dirs::UserDirsError>(())
+    /// ```
+    pub fn with_profile(self, profile: &str) -> Result<AppDirs, UserDirsError> {
+        self.with_profile_in(PROFILES_DIR, profile)
+    }
+
+    /// Selects the profile `profile` like [`AppDirs::with_profile`], but keeps profiles in `profiles_dir` instead of
+    /// `profiles`.
+    ///
+    /// This fails with [`UserDirsError::InvalidFileName`] if `profiles_dir` is not a single path component.
+    pub fn with_profile_in(
+        mut self,
+        profiles_dir: &str,
+        profile: &str,
+    ) -> Result<AppDirs, UserDirsError> {
+        if !runtime::is_valid_component(profiles_dir) {
+            return Err(UserDirsError::InvalidFileName(profiles_dir.to_owned()));
+        }
+        let name = app_name_normalize(profile);
+        self.profile = if profile.trim().is_empty() || name == "default" {
+            None
+        } else if name.is_empty() {
+            return Err(UserDirsError::InvalidProfileName(profile.to_owned()));
+        } else {
+            Some(name)
+        };
+        profiles_dir.clone_into(&mut self.profiles_dir);
+
+        self.locate();
+        Ok(self)
+    }
+
+    /// Selects the profile named by the `<APP>_PROFILE` environment variable, if it is set.
+    ///
+    /// `<APP>` is the application's normalized name in uppercase with `-` and `.` replaced by `_`, so the variable
+    /// of `"My App"` is `MY_APP_PROFILE`. Without the variable, the directories are left unchanged.
+    pub fn with_profile_from_env(self) -> Result<AppDirs, UserDirsError> {
+        let var = format!("{}_PROFILE", self.env_prefix);
+        match ProcessEnv.var_os(&var) {
+            Some(profile) => {
+                let profile = profile.into_string().map_err(|profile| {
+                    UserDirsError::InvalidProfileName(profile.to_string_lossy().into_owned())
+                })?;
+                let profiles_dir = self.profiles_dir.clone();
+                self.with_profile_in(&profiles_dir, &profile)
+            }
+            None => Ok(self),
+        }
+    }
+
+    /// Returns the selected profile's normalized name, if any.
+    #[must_use]
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Returns the names of the profiles that have a config or data directory, sorted.
+    ///
+    /// Profiles are found whether or not one is selected. Missing directories are not an error.
+    pub fn list_profiles(&self) -> io::Result<Vec<String>> {
+        let mut profiles = BTreeSet::new();
+        for kind in [DirKind::Config, DirKind::Data] {
+            let Some(dir) = self.root(kind) else {
+                continue;
+            };
+            let dir = dir.join(&self.profiles_dir);
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    profiles.extend(entry.file_name().into_string().ok());
+                }
+            }
+        }
+        Ok(profiles.into_iter().collect())
+    }
+
+    /// Lets users move each of the application's directories with its own environment variable.
+    ///
+    /// The variables are `<APP>_CACHE_DIR`, `<APP>_CONFIG_DIR`, `<APP>_DATA_DIR` and `<APP>_STATE_DIR`, where `<APP>`
+    /// is [`AppDirs::env_prefix`], so `MY_APP_CONFIG_DIR` for `"My App"`. They are checked before the XDG variables
+    /// and portable mode, and like the XDG variables, a value that is empty or not an absolute path is ignored. The
+    /// directory set by a variable is the application's own, so the name is not appended, but a profile still is.
+    ///
+    /// ```rust
+    /// let dirs = user_dirs::AppDirs::new("My App")?.with_env_overrides();
+    /// # Ok::<(), user_dirs::UserDirsError>(())
+    /// ```
+    #[must_use]
+    pub fn with_env_overrides(mut self) -> AppDirs {
+        self.env_overrides = true;
+        self.locate();
+        self
+    }
+
+    /// Uses `prefix` instead of the one derived from the application's name for its environment variables.
+    ///
+    /// This applies to the variables of [`AppDirs::with_env_overrides`], [`AppDirs::with_profile_from_env`] and
+    /// [`AppDirs::with_portable_mode`]. It fails with [`UserDirsError::InvalidEnvPrefix`] unless `prefix` is
+    /// non-empty and made of ASCII letters, digits and `_` only.
+    pub fn with_env_prefix(mut self, prefix: &str) -> Result<AppDirs, UserDirsError> {
+        if prefix.is_empty()
+            || !prefix
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'_')
+        {
+            return Err(UserDirsError::InvalidEnvPrefix(prefix.to_owned()));
+        }
+        prefix.clone_into(&mut self.env_prefix);
+        self.locate();
+        Ok(self)
+    }
+
+    /// Returns the prefix of the application's environment variables, such as `MY_APP` for `"My App"`.
+    ///
+    /// Unless set with [`AppDirs::with_env_prefix`], this is [`app_env_prefix`] of the application's name.
+    #[must_use]
+    pub fn env_prefix(&self) -> &str {
+        &self.env_prefix
+    }
+
+    /// Returns where the application's directory of the given kind came from, if it is available.
+    ///
+    /// A directory set by one of the variables of [`AppDirs::with_env_overrides`] is [`DirSource::AppEnv`]; any other
+    /// is reported like the base directory it is in, except that directories in portable mode are `None`.
+    #[must_use]
+    pub fn source(&self, kind: DirKind) -> Option<DirSource> {
+        if self.env_dir(kind).is_some() {
+            return Some(DirSource::AppEnv);
+        }
+        match &self.portable {
+            Some(_) if OVERRIDABLE.contains(&kind) => None,
+            _ => self
+                .base
+                .source(kind)
+                .filter(|_| self.dir_in_base(kind).is_some()),
+        }
+    }
+
+    /// Resolves the application's directory of the given kind again, recording every step taken, see [`explain`].
+    ///
+    /// The steps start with the application's own variable, if [`AppDirs::with_env_overrides`] is enabled, followed
+    /// by those of the base directory unless the variable was used. The result is the application's directory.
+    #[must_use]
+    pub fn explain(&self, kind: DirKind) -> Explanation {
+        let dir = match kind {
+            DirKind::Home => Some(self.base.home().to_path_buf()),
+            _ => self.dir_in_base(kind).map(|(dir, _)| dir.to_path_buf()),
+        };
+        explain::explain_app(kind, self.override_var(kind).as_deref(), dir)
+    }
+
+    /// Returns the directory set by the application's variable for the given kind, if any.
+    fn env_dir(&self, kind: DirKind) -> Option<&Path> {
+        self.env_dirs
+            .iter()
+            .find(|(dir_kind, _)| *dir_kind == kind)
+            .map(|(_, dir)| dir.as_path())
+    }
+
+    /// Returns the name of the application's variable for the given kind, if overrides are enabled.
+    fn override_var(&self, kind: DirKind) -> Option<String> {
+        let suffix = match kind {
+            _ if !self.env_overrides => return None,
+            DirKind::Cache => "CACHE_DIR",
+            DirKind::Config => "CONFIG_DIR",
+            DirKind::Data => "DATA_DIR",
+            DirKind::State => "STATE_DIR",
+            DirKind::Home | DirKind::Runtime => return None,
+        };
+        Some(format!("{}_{suffix}", self.env_prefix))
+    }
+
+    /// Returns the path to the application's cache directory.
+    #[must_use]
+    pub fn cache(&self) -> &Path {
+        &self.cache
+    }
+
+    /// Returns the path to the application's config directory.
+    #[must_use]
+    pub fn config(&self) -> &Path {
+        &self.config
+    }
+
+    /// Returns the path to the application's data directory.
+    #[must_use]
+    pub fn data(&self) -> &Path {
+        &self.data
+    }
+
+    /// Returns the path to the application's runtime directory, if available.
+    #[must_use]
+    pub fn runtime(&self) -> Option<&Path> {
+        self.runtime.as_deref()
+    }
+
+    /// Returns the path to the application's state directory, if available.
+    #[must_use]
+    pub fn state(&self) -> Option<&Path> {
+        self.state.as_deref()
+    }
+
+    /// Returns the application's normalized name, which its subdirectories are named after, such as `my-app`.
+    #[must_use]
+    pub fn app_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the snapshot the application's directories were resolved from, for the base directories without the
+    /// application's subdirectory.
+    #[must_use]
+    pub fn base_dirs(&self) -> &UserDirs {
+        &self.base
+    }
+
+    /// Returns the application's directory of the given kind along with the base directory it is in, if available.
+    pub(crate) fn dir_in_base(&self, kind: DirKind) -> Option<(&Path, &Path)> {
+        let dir = match kind {
+            DirKind::Home => return None,
+            DirKind::Cache => &self.cache,
+            DirKind::Config => &self.config,
+            DirKind::Data => &self.data,
+            DirKind::Runtime => self.runtime.as_ref()?,
+            DirKind::State => self.state.as_ref()?,
+        };
+        let base = match &self.portable {
+            Some(portable) if kind != DirKind::Runtime => portable.base(),
+            _ => self.base.get(kind)?,
+        };
+        Some((dir, base))
+    }
+}
+
+/// The directories of a specific version of an application, for applications that keep each version's files apart.
+///
+/// The subdirectories are named after the application followed by `-<major>.<minor>`, so version `(2, 0)` of
+/// `"My App"` uses `my-app-2.0`. It dereferences to the underlying [`AppDirs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirsVersioned {
+    dirs: AppDirs,
+    version: (u32, u32),
+}
+
+impl AppDirsVersioned {
+    /// Resolves every directory for the given `version` of the application `app_name`.
+    ///
+    /// This fails with [`UserDirsError::InvalidAppName`] if nothing is left of the name after normalization.
+    pub fn new(app_name: &str, version: (u32, u32)) -> Result<AppDirsVersioned, UserDirsError> {
+        let dirs = AppDirs::from_base(UserDirs::new()?, &versioned_app_name(app_name, version)?)?;
+        Ok(AppDirsVersioned { dirs, version })
+    }
+
+    /// Returns the version as `(major, minor)`.
+    #[must_use]
+    pub fn version(&self) -> (u32, u32) {
+        self.version
+    }
+
+    /// Returns the underlying application directories.
+    #[must_use]
+    pub fn inner(&self) -> &AppDirs {
+        &self.dirs
+    }
+}
+
+impl Deref for AppDirsVersioned {
+    type Target = AppDirs;
+
+    fn deref(&self) -> &AppDirs {
+        &self.dirs
+    }
+}
+
+/// Returns the path to the config directory of the given `version` of the application `app_name`.
+///
+/// `config_dir_for_app_versioned("My App", (2, 0))` is `config_dir()?.join("my-app-2.0")`, see [`AppDirsVersioned`].
+pub fn config_dir_for_app_versioned(
+    app_name: &str,
+    version: (u32, u32),
+) -> Result<PathBuf, UserDirsError> {
+    Ok(config_dir()?.join(versioned_app_name(app_name, version)?))
+}
+
+/// Returns the path to the config directory of the application `app` published by the organization `org`.
+///
+/// Both names are normalized with [`app_name_normalize`], so `config_dir_for_app_with_org("My Org", "My App")` is
+/// `config_dir()?.join("my-org").join("my-app")`. This is in the config directory on every platform, including macOS
+/// where preferences would otherwise be named like `com.myorg.myapp`. This fails with
+/// [`UserDirsError::InvalidAppName`] if nothing is left of either name after normalization.
+pub fn config_dir_for_app_with_org(org: &str, app: &str) -> Result<PathBuf, UserDirsError> {
+    let (org_dir, app_dir) = (app_dir_name(org)?, app_dir_name(app)?);
+    Ok(config_dir()?.join(org_dir).join(app_dir))
+}
+
+/// Returns the normalized directory name of the given `version` of the application `app_name`.
+fn versioned_app_name(app_name: &str, (major, minor): (u32, u32)) -> Result<String, UserDirsError> {
+    let name = app_dir_name(app_name)?;
+    Ok(format!("{name}-{major}.{minor}"))
+}
+
+impl UserDirs {
+    /// Returns the directories of the application `app_name`, see [`AppDirs`].
+    pub fn with_app(&self, app_name: &str) -> Result<AppDirs, UserDirsError> {
+        AppDirs::from_base(self.clone(), app_name)
+    }
+
+    /// Returns the directories of the application `app_name`, consuming this snapshot.
+    ///
+    /// This is the same as [`UserDirs::with_app`], but avoids cloning the snapshot when it is no longer needed.
+    pub fn into_app_dirs(self, app_name: &str) -> Result<AppDirs, UserDirsError> {
+        AppDirs::from_base(self, app_name)
+    }
+}
+
+/// Returns the prefix of the environment variables of the application `app_name`, see [`AppDirs::env_prefix`].
+///
+/// The prefix is the normalized name in uppercase with `-` and `.` replaced by `_`, so different names can share a
+/// prefix, such as `"my-app"` and `"My App"`. Applications can compare prefixes to detect this, and pick another with
+/// [`AppDirs::with_env_prefix`]. This returns `None` if nothing is left of the name after normalization.
+///
+/// ```rust
+/// assert_eq!(user_dirs::app_env_prefix("My App").as_deref(), Some("MY_APP"));
+/// assert_eq!(user_dirs::app_env_prefix("my.app"), user_dirs::app_env_prefix("my-app"));
+/// ```
+#[must_use]
+pub fn app_env_prefix(app_name: &str) -> Option<String> {
+    let name = app_name_normalize(app_name);
+    (!name.is_empty()).then(|| env_prefix(&name))
+}
+
+/// The kinds of directories that can be moved with the application's variables.
+const OVERRIDABLE: [DirKind; 4] = [
+    DirKind::Cache,
+    DirKind::Config,
+    DirKind::Data,
+    DirKind::State,
+];
+
+/// Returns the environment variable prefix of a normalized application name.
+fn env_prefix(name: &str) -> String {
+    name.to_ascii_uppercase().replace(['-', '.'], "_")
+}
+
+/// Returns the directory set by the variable `var` in `env`, ignoring values that are not absolute paths.
+pub(crate) fn env_dir(var: &str, env: &impl Env) -> Option<PathBuf> {
+    let os = TargetOs::current();
+    env::var(os, env, var)
+        .filter(|value| env::is_absolute(os, value))
+        .map(PathBuf::from)
+}
+
+/// Normalizes an application name into a directory name, as every application-scoped function of this crate does.
+///
+/// The name is trimmed and lowercased, whitespace is replaced by `-`, and anything other than ASCII letters, digits,
+/// `-`, `_` and `.` is removed, as are leading dots. Paths derived from an application name outside this crate
+/// should use it too, so they match the directories of [`AppDirs`]. The result may be empty, which this crate's
+/// functions reject with [`UserDirsError::InvalidAppName`].
+///
+/// ```rust
+/// assert_eq!(user_dirs::app_name_normalize(" My App! "), "my-app");
+/// assert_eq!(user_dirs::app_name_normalize("..hidden"), "hidden");
+/// ```
+#[must_use]
+pub fn app_name_normalize(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() {
+                '-'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .filter(|&c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+
+    // A leading dot would hide the directory on Unix, and "." or ".." would escape it.
+    name.trim_start_matches('.').to_owned()
+}
+
+/// Normalizes `app_name` with [`app_name_normalize`], failing with [`UserDirsError::InvalidAppName`] if nothing is left.
+pub(crate) fn app_dir_name(app_name: &str) -> Result<String, UserDirsError> {
+    let name = app_name_normalize(app_name);
+    if name.is_empty() {
+        return Err(UserDirsError::InvalidAppName(app_name.to_owned()));
+    }
+    Ok(name)
+}
@@ -0,0 +1,193 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::DirKind;
+
+/// A problem found by [`audit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFinding {
+    severity: Severity,
+    code: AuditCode,
+    path: PathBuf,
+}
+
+impl AuditFinding {
+    /// Returns how serious the problem is.
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns what the problem is.
+    #[must_use]
+    pub fn code(&self) -> AuditCode {
+        self.code
+    }
+
+    /// Returns the path with the problem, which is the directory or one of its ancestors.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.severity,
+            self.path.display(),
+            self.code
+        )
+    }
+}
+
+/// How serious an [`AuditFinding`] is, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, but not a problem.
+    Info,
+    /// Possibly a problem, depending on the setup.
+    Warning,
+    /// A problem that should be fixed.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// What an [`AuditFinding`] is about.
+///
+/// Its [`Display`](fmt::Display) implementation prints a stable, machine-readable code such as `world-writable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AuditCode {
+    /// The directory does not exist yet.
+    Missing,
+    /// Something other than a directory exists at the path.
+    NotADirectory,
+    /// The path cannot be inspected or listed.
+    Unreadable,
+    /// The directory is owned by another user.
+    WrongOwner,
+    /// The directory can be written by its group.
+    GroupWritable,
+    /// The directory can be written by anyone.
+    WorldWritable,
+    /// The runtime directory can be accessed by other users, which the XDG Base Directory Specification forbids.
+    NotPrivate,
+    /// The path or one of its ancestors is a symbolic link owned by another user, who could redirect it.
+    ForeignSymlink,
+}
+
+impl fmt::Display for AuditCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Missing => "missing",
+            Self::NotADirectory => "not-a-directory",
+            Self::Unreadable => "unreadable",
+            Self::WrongOwner => "wrong-owner",
+            Self::GroupWritable => "group-writable",
+            Self::WorldWritable => "world-writable",
+            Self::NotPrivate => "not-private",
+            Self::ForeignSymlink => "foreign-symlink",
+        })
+    }
+}
+
+/// Checks the ownership and permissions of the directory of the given kind, returning every problem found.
+///
+/// On Unix, the directory must be owned by the current user and not writable by others, and the runtime directory
+/// must not be accessible by others at all. Every symbolic link on the way to the directory must be owned by the
+/// current user or `root`. On other platforms, only the existence and readability of the directory are checked; ACLs
+/// are not inspected.
+///
+/// Nothing is modified, and paths that can't be inspected are reported as [`AuditCode::Unreadable`] rather than
+/// failing. A directory that can't be resolved, or is not available on this platform, has no findings.
+#[must_use]
+pub fn audit(kind: DirKind) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    if let Ok(Some(dir)) = kind.resolve() {
+        audit_dir(kind, &dir, &mut |severity, code, path: &Path| {
+            findings.push(AuditFinding {
+                severity,
+                code,
+                path: path.to_path_buf(),
+            });
+        });
+    }
+    findings
+}
+
+/// Audits the directory of every kind, see [`audit`].
+#[must_use]
+pub fn audit_all() -> Vec<AuditFinding> {
+    DirKind::ALL.into_iter().flat_map(audit).collect()
+}
+
+fn audit_dir(kind: DirKind, dir: &Path, report: &mut impl FnMut(Severity, AuditCode, &Path)) {
+    #[cfg(unix)]
+    for ancestor in dir.ancestors() {
+        use std::os::unix::fs::MetadataExt;
+
+        if let Ok(metadata) = fs::symlink_metadata(ancestor) {
+            if metadata.file_type().is_symlink() && metadata.uid() != 0 && metadata.uid() != uid() {
+                report(Severity::Error, AuditCode::ForeignSymlink, ancestor);
+            }
+        }
+    }
+
+    let metadata = match fs::metadata(dir) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return report(Severity::Info, AuditCode::Missing, dir);
+        }
+        Err(_) => return report(Severity::Warning, AuditCode::Unreadable, dir),
+    };
+    if !metadata.is_dir() {
+        return report(Severity::Error, AuditCode::NotADirectory, dir);
+    }
+    if fs::read_dir(dir).is_err() {
+        report(Severity::Warning, AuditCode::Unreadable, dir);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let private = kind == DirKind::Runtime;
+        if metadata.uid() != uid() {
+            let severity = if private {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            report(severity, AuditCode::WrongOwner, dir);
+        }
+        if private && metadata.mode() & 0o077 != 0 {
+            report(Severity::Error, AuditCode::NotPrivate, dir);
+        } else if metadata.mode() & 0o002 != 0 {
+            report(Severity::Error, AuditCode::WorldWritable, dir);
+        } else if metadata.mode() & 0o020 != 0 {
+            report(Severity::Warning, AuditCode::GroupWritable, dir);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = kind;
+}
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    // SAFETY: `getuid` has no preconditions and cannot fail.
+    unsafe { libc::getuid() }
+}
@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    env::{self, candidates_for, ProcessEnv, TargetOs},
+    home_dir, DirKind, HomeDirError,
+};
+
+/// A place a directory could be, see [`config_dir_candidates`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    path: PathBuf,
+    source: CandidateSource,
+}
+
+impl Candidate {
+    pub(crate) fn new(path: PathBuf, source: CandidateSource) -> Candidate {
+        Candidate { path, source }
+    }
+
+    /// Returns the path to the candidate directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns where the candidate comes from.
+    #[must_use]
+    pub fn source(&self) -> CandidateSource {
+        self.source
+    }
+}
+
+/// Where a [`Candidate`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CandidateSource {
+    /// The directory overridden on the current thread with [`set_override`](crate::env::set_override).
+    Override,
+    /// The XDG environment variable, such as `$XDG_CONFIG_HOME`.
+    Xdg,
+    /// The platform's default, which this crate and the `dirs` crate use when the XDG variable is unset.
+    PlatformDefault,
+    /// The XDG Base Directory Specification's default under the home directory, such as `~/.config`, used by
+    /// applications that follow the specification on every platform.
+    XdgDefault,
+    /// Another location applications commonly used on this platform, such as `~/Library/Application Support` for
+    /// config on macOS.
+    Alternative,
+    /// The Windows environment variable of the given name, such as `%LOCALAPPDATA%`.
+    EnvVar(&'static str),
+}
+
+/// Returns every place the config directory could be, with the one to write to first.
+///
+/// The first candidate is always [`config_dir`](crate::config_dir). The others are where the directory would be under
+/// different policies, such as the platform default when `$XDG_CONFIG_HOME` is set, so an application that changed
+/// policies can still read data from where it actually is. Duplicates are removed, and nothing is checked on disk.
+pub fn config_dir_candidates() -> Result<Vec<Candidate>, HomeDirError> {
+    candidates(DirKind::Config)
+}
+
+/// Returns every place the data directory could be, with the one to write to first, see [`config_dir_candidates`].
+pub fn data_dir_candidates() -> Result<Vec<Candidate>, HomeDirError> {
+    candidates(DirKind::Data)
+}
+
+fn candidates(kind: DirKind) -> Result<Vec<Candidate>, HomeDirError> {
+    let mut candidates = candidates_for(kind, TargetOs::current(), &home_dir()?, &ProcessEnv);
+    if let Some(dir) = env::overridden(kind) {
+        candidates.retain(|candidate| candidate.path() != dir);
+        candidates.insert(0, Candidate::new(dir, CandidateSource::Override));
+    }
+    Ok(candidates)
+}
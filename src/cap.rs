@@ -0,0 +1,90 @@
+//! [`cap_std`] capability handles for the base directories.
+//!
+//! Each function resolves its directory with the usual rules, creates it if `create` is set, and opens it as a
+//! [`Dir`], so the rest of a sandboxed program can work relative to the handle without ever holding a path. Opening a
+//! directory uses ambient authority once; everything done through the handle afterwards is confined to it.
+//!
+//! ```rust,no_run
+//! let config = user_dirs::cap::open_config_dir(true)?;
+//! let settings = config.read_to_string("settings.toml")?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{error::Error, fmt, fs, io};
+
+use cap_std::{ambient_authority, fs::Dir};
+
+use crate::{DirKind, HomeDirError};
+
+/// Opens the cache directory, creating it first if `create` is set.
+pub fn open_cache_dir(create: bool) -> Result<Dir, OpenDirError> {
+    open(DirKind::Cache, create)
+}
+
+/// Opens the config directory, creating it first if `create` is set.
+pub fn open_config_dir(create: bool) -> Result<Dir, OpenDirError> {
+    open(DirKind::Config, create)
+}
+
+/// Opens the data directory, creating it first if `create` is set.
+pub fn open_data_dir(create: bool) -> Result<Dir, OpenDirError> {
+    open(DirKind::Data, create)
+}
+
+/// Opens the state directory, creating it first if `create` is set.
+///
+/// Fails with [`OpenDirError::Unavailable`] if there is no state directory on this platform.
+pub fn open_state_dir(create: bool) -> Result<Dir, OpenDirError> {
+    open(DirKind::State, create)
+}
+
+fn open(kind: DirKind, create: bool) -> Result<Dir, OpenDirError> {
+    let dir = kind.resolve()?.ok_or(OpenDirError::Unavailable(kind))?;
+    if create {
+        fs::create_dir_all(&dir)?;
+    }
+    Ok(Dir::open_ambient_dir(&dir, ambient_authority())?)
+}
+
+/// This error occurs when a directory cannot be opened as a capability handle.
+#[derive(Debug)]
+pub enum OpenDirError {
+    /// The home directory could not be located.
+    HomeDir(HomeDirError),
+    /// There is no directory of this kind on this platform.
+    Unavailable(DirKind),
+    /// The directory could not be created or opened, such as when it does not exist or permission is denied.
+    Io(io::Error),
+}
+
+impl fmt::Display for OpenDirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HomeDir(err) => err.fmt(f),
+            Self::Unavailable(kind) => write!(f, "no {kind} directory on this platform"),
+            Self::Io(_) => write!(f, "could not open directory"),
+        }
+    }
+}
+
+impl Error for OpenDirError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::HomeDir(err) => Some(err),
+            Self::Unavailable(_) => None,
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<HomeDirError> for OpenDirError {
+    fn from(err: HomeDirError) -> Self {
+        Self::HomeDir(err)
+    }
+}
+
+impl From<io::Error> for OpenDirError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{unicode_path, DirKind, UserDirsError};
+
+/// Returns whether the home, data, config and cache directories all exist as directories on disk.
+///
+/// This is a quick health check for startup; if it returns `false`, create the directories with functions such as
+/// [`ensure_config_dir`](crate::ensure_config_dir), or report a setup error. [`integrity_check`] explains what is
+/// wrong instead.
+#[must_use]
+pub fn all_dirs_exist() -> bool {
+    [
+        DirKind::Home,
+        DirKind::Data,
+        DirKind::Config,
+        DirKind::Cache,
+    ]
+    .into_iter()
+    .all(|kind| matches!(kind.resolve(), Ok(Some(dir)) if dir.is_dir()))
+}
+
+/// Checks every resolved directory on disk, returning all the problems found.
+///
+/// A directory that does not exist yet is fine, since it will be created when needed. Otherwise it must be a
+/// directory (following symbolic links) whose contents can be listed. On Unix, the runtime directory must also be
+/// owned by the current user and inaccessible to anyone else, as the XDG Base Directory Specification requires.
+///
+/// Unlike most functions in this crate, this does not stop at the first error, so the result describes everything
+/// that needs fixing.
+pub fn integrity_check() -> Result<(), Vec<UserDirsError>> {
+    let mut errors = Vec::new();
+    let mut home_error = false;
+
+    for kind in DirKind::ALL {
+        let result = match kind.resolve() {
+            Ok(Some(path)) => check_dir(kind, path),
+            Ok(None) => Ok(()),
+            // Every directory derived from home fails the same way, so only report it once.
+            Err(_) if home_error => Ok(()),
+            Err(err) => {
+                home_error = true;
+                Err(err.into())
+            }
+        };
+        errors.extend(result.err());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn check_dir(kind: DirKind, path: PathBuf) -> Result<(), UserDirsError> {
+    let Some(metadata) = existing_dir(kind, &path)? else {
+        return Ok(());
+    };
+    if let Err(err) = fs::read_dir(&path) {
+        return Err(UserDirsError::Inaccessible(kind, path, err));
+    }
+
+    #[cfg(unix)]
+    if kind == DirKind::Runtime {
+        use std::os::unix::fs::MetadataExt;
+
+        // SAFETY: `getuid` has no preconditions and cannot fail.
+        if metadata.uid() != unsafe { libc::getuid() } || metadata.mode() & 0o077 != 0 {
+            return Err(UserDirsError::InsecurePermissions(kind, path));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    Ok(())
+}
+
+/// Returns the metadata of the directory at `path`, or `None` if nothing exists there.
+fn existing_dir(kind: DirKind, path: &Path) -> Result<Option<fs::Metadata>, UserDirsError> {
+    match fs::metadata(path) {
+        // A broken symbolic link is not missing, it points somewhere that is.
+        Err(err) if err.kind() == io::ErrorKind::NotFound && !is_symlink(path) => Ok(None),
+        Err(err) => Err(UserDirsError::Inaccessible(kind, path.to_owned(), err)),
+        Ok(metadata) if !metadata.is_dir() => {
+            Err(UserDirsError::NotADirectory(kind, path.to_owned()))
+        }
+        Ok(metadata) => Ok(Some(metadata)),
+    }
+}
+
+fn checked(kind: DirKind) -> Result<Option<PathBuf>, UserDirsError> {
+    match kind.resolve()? {
+        Some(path) => Ok(existing_dir(kind, &path)?
+            .map(|_| unicode_path(path))
+            .transpose()?),
+        None => Ok(None),
+    }
+}
+
+/// Returns the path to the cache directory if it exists, see [`config_dir_checked`].
+pub fn cache_dir_checked() -> Result<Option<PathBuf>, UserDirsError> {
+    checked(DirKind::Cache)
+}
+
+/// Returns the path to the config directory if it exists.
+///
+/// Nothing is created. This returns `Ok(None)` if nothing exists at the path, and fails with
+/// [`UserDirsError::NotADirectory`] if something other than a directory does, or with
+/// [`UserDirsError::Inaccessible`] if the path is a broken symbolic link or cannot be inspected. This tells a fresh
+/// install apart from a misconfigured one.
+pub fn config_dir_checked() -> Result<Option<PathBuf>, UserDirsError> {
+    checked(DirKind::Config)
+}
+
+/// Returns the path to the data directory if it exists, see [`config_dir_checked`].
+pub fn data_dir_checked() -> Result<Option<PathBuf>, UserDirsError> {
+    checked(DirKind::Data)
+}
+
+/// Returns the path to the runtime directory if it is available and exists, see [`config_dir_checked`].
+pub fn runtime_dir_checked() -> Result<Option<PathBuf>, UserDirsError> {
+    checked(DirKind::Runtime)
+}
+
+/// Returns the path to the state directory if it is available and exists, see [`config_dir_checked`].
+pub fn state_dir_checked() -> Result<Option<PathBuf>, UserDirsError> {
+    checked(DirKind::State)
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
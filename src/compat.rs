@@ -0,0 +1,13 @@
+//! Drop-in replacements for the APIs of other directory crates, mapped onto this crate's resolution.
+//!
+//! Each module is enabled by its own feature, and lets code written against another crate switch to `user_dirs` by
+//! changing its imports.
+
+#[cfg(feature = "compat-app-dirs")]
+pub mod app_dirs;
+#[cfg(feature = "compat-electron")]
+pub mod electron;
+#[cfg(feature = "compat-platformdirs")]
+pub mod platformdirs;
+#[cfg(feature = "compat-qt")]
+pub mod qt;
@@ -0,0 +1,121 @@
+//! The API of the [`app_dirs2`](https://docs.rs/app_dirs2) crate.
+//!
+//! User directories are resolved like [`AppDirs`], so the XDG variables are respected and the application's name is
+//! normalized into its directory name; the author is ignored. Shared directories are those of the [`system`]
+//! module. The `app_*` functions create the directory, while the `get_*` functions only return its path.
+//!
+//! ```rust,no_run
+//! use user_dirs::compat::app_dirs::{get_app_root, AppDataType, AppInfo};
+//!
+//! const APP_INFO: AppInfo = AppInfo { name: "My App", author: "Leah" };
+//!
+//! let config = get_app_root(AppDataType::UserConfig, &APP_INFO)?;
+//! # Ok::<(), user_dirs::compat::app_dirs::AppDirsError>(())
+//! ```
+
+use std::{fmt, fs, io, path::PathBuf};
+
+use crate::{app::app_dir_name, system, AppDirs, UserDirsError};
+
+/// Information about an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AppInfo {
+    /// The name of the application.
+    pub name: &'static str,
+    /// The author of the application, which is not part of any path.
+    pub author: &'static str,
+}
+
+/// The kinds of directories an application can store its files in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppDataType {
+    /// The user's config directory, see [`config_dir`](crate::config_dir).
+    UserConfig,
+    /// The user's data directory, see [`data_dir`](crate::data_dir).
+    UserData,
+    /// The user's cache directory, see [`cache_dir`](crate::cache_dir).
+    UserCache,
+    /// The system-wide data directory, see [`system::state_dir`].
+    SharedData,
+    /// The system-wide config directory, see [`system::config_dir`].
+    SharedConfig,
+}
+
+impl AppDataType {
+    /// Returns whether the directory is shared by every user.
+    #[must_use]
+    pub fn is_shared(self) -> bool {
+        matches!(self, Self::SharedData | Self::SharedConfig)
+    }
+}
+
+/// The errors that can occur when resolving or creating a directory.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AppDirsError {
+    /// The directory could not be resolved.
+    UserDirs(UserDirsError),
+    /// The directory could not be created.
+    Io(io::Error),
+}
+
+impl fmt::Display for AppDirsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UserDirs(err) => err.fmt(f),
+            Self::Io(err) => write!(f, "failed to create directory: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AppDirsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UserDirs(err) => Some(err),
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<UserDirsError> for AppDirsError {
+    fn from(err: UserDirsError) -> Self {
+        Self::UserDirs(err)
+    }
+}
+
+impl From<io::Error> for AppDirsError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Returns the path to the application's directory of type `t`, creating it if it does not exist.
+pub fn app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
+    create(get_app_root(t, app)?)
+}
+
+/// Returns the path to the application's directory of type `t`.
+pub fn get_app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
+    Ok(match t {
+        AppDataType::SharedData => system::state_dir(&app_dir_name(app.name)?),
+        AppDataType::SharedConfig => system::config_dir(&app_dir_name(app.name)?),
+        AppDataType::UserConfig => AppDirs::new(app.name)?.config().to_path_buf(),
+        AppDataType::UserData => AppDirs::new(app.name)?.data().to_path_buf(),
+        AppDataType::UserCache => AppDirs::new(app.name)?.cache().to_path_buf(),
+    })
+}
+
+/// Returns the path to `path` within the application's directory of type `t`, creating it if it does not exist.
+pub fn app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf, AppDirsError> {
+    create(get_app_dir(t, app, path)?)
+}
+
+/// Returns the path to `path` within the application's directory of type `t`.
+pub fn get_app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf, AppDirsError> {
+    Ok(get_app_root(t, app)?.join(path))
+}
+
+fn create(dir: PathBuf) -> Result<PathBuf, AppDirsError> {
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
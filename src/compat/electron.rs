@@ -0,0 +1,151 @@
+//! The paths of Electron's [`app.getPath()`](https://www.electronjs.org/docs/latest/api/app#appgetpathname).
+//!
+//! These reproduce Electron's defaults exactly, so that a port of an Electron app can find the files the old app wrote.
+//! Unlike the rest of this crate, they follow Chromium's rules rather than the XDG specification: `$XDG_CONFIG_HOME`
+//! is used as long as it is set and not empty, and the application's name is used as is rather than normalized. Paths
+//! changed at runtime with `app.setPath()` can't be known.
+//!
+//! | Name         | Linux                              | macOS                                       | Windows                   |
+//! | ------------ | ---------------------------------- | ------------------------------------------- | ------------------------- |
+//! | `appData`    | `$XDG_CONFIG_HOME` or `~/.config`  | `~/Library/Application Support`             | `%APPDATA%`               |
+//! | `userData`   | `<appData>/<name>`                 | `<appData>/<name>`                          | `<appData>\<name>`        |
+//! | `logs`       | `<userData>/logs`                  | `~/Library/Logs/<name>`                     | `<userData>\logs`         |
+//! | `crashDumps` | `<userData>/Crashpad`              | `<userData>/Crashpad`                       | `<userData>\Crashpad`     |
+//! | `temp`       | `$TMPDIR` or `/tmp`                | the per-user temporary directory            | `%TMP%` or `%TEMP%`       |
+//!
+//! ```rust,no_run
+//! use user_dirs::compat::electron::{get_path, ElectronPath};
+//!
+//! let user_data = get_path(ElectronPath::UserData, "My App")?;
+//! # Ok::<(), user_dirs::UserDirsError>(())
+//! ```
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    env::{self, Env, ProcessEnv, TargetOs},
+    home_dir, runtime, UserDirsError,
+};
+
+/// The names accepted by Electron's `app.getPath()` that depend only on the platform and the application's name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ElectronPath {
+    /// `home`, the user's home directory.
+    Home,
+    /// `appData`, the per-user application data directory.
+    AppData,
+    /// `userData`, the application's own directory in `appData`, named after the application.
+    UserData,
+    /// `sessionData`, which is `userData` unless changed at runtime.
+    SessionData,
+    /// `temp`, the temporary directory.
+    Temp,
+    /// `logs`, the application's log directory.
+    Logs,
+    /// `crashDumps`, where Crashpad stores crash dumps.
+    CrashDumps,
+}
+
+impl ElectronPath {
+    /// Returns the name of the path as passed to `app.getPath()`, such as `userData`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Home => "home",
+            Self::AppData => "appData",
+            Self::UserData => "userData",
+            Self::SessionData => "sessionData",
+            Self::Temp => "temp",
+            Self::Logs => "logs",
+            Self::CrashDumps => "crashDumps",
+        }
+    }
+}
+
+/// Returns the path Electron's `app.getPath(name)` returns for the application `app_name` on this machine.
+///
+/// `app_name` is the application's name as Electron knows it, which is the `productName` (or else the `name`) of its
+/// `package.json`. This fails with [`UserDirsError::InvalidAppName`] if it can't be a directory name.
+pub fn get_path(name: ElectronPath, app_name: &str) -> Result<PathBuf, UserDirsError> {
+    // Chromium uses `NSTemporaryDirectory()`, which is the per-user temporary directory.
+    #[cfg(target_os = "macos")]
+    if name == ElectronPath::Temp {
+        if let Some(temp) = crate::os::macos::darwin_user_temp_dir() {
+            return Ok(temp);
+        }
+    }
+
+    let home = home_dir()?;
+    path_for(name, app_name, TargetOs::current(), &home, &ProcessEnv)
+}
+
+/// Returns the path Electron's `app.getPath(name)` returns for the application `app_name` on `os`.
+///
+/// Like [`env::dir_for`], this reads variables from `env` instead of the process environment and joins paths with the
+/// separator of `os`, so it can compute the paths of another machine.
+pub fn path_for(
+    name: ElectronPath,
+    app_name: &str,
+    os: TargetOs,
+    home: &Path,
+    env: &impl Env,
+) -> Result<PathBuf, UserDirsError> {
+    if !runtime::is_valid_component(app_name) {
+        return Err(UserDirsError::InvalidAppName(app_name.to_owned()));
+    }
+
+    let user_data = || env::join(os, &app_data(os, home, env), &[app_name]);
+    Ok(match name {
+        ElectronPath::Home => home.to_path_buf(),
+        ElectronPath::AppData => app_data(os, home, env),
+        ElectronPath::UserData | ElectronPath::SessionData => user_data(),
+        ElectronPath::Temp => temp(os, env),
+        ElectronPath::Logs if os == TargetOs::MacOs => {
+            env::join(os, home, &["Library", "Logs", app_name])
+        }
+        ElectronPath::Logs => env::join(os, &user_data(), &["logs"]),
+        ElectronPath::CrashDumps => env::join(os, &user_data(), &["Crashpad"]),
+    })
+}
+
+fn app_data(os: TargetOs, home: &Path, env: &impl Env) -> PathBuf {
+    match os {
+        TargetOs::MacOs => env::join(os, home, &["Library", "Application Support"]),
+        TargetOs::Windows => env::roaming_app_data(home, env),
+        // Chromium takes the variable as is, without requiring an absolute path.
+        _ => non_empty_var(env, "XDG_CONFIG_HOME")
+            .map_or_else(|| env::join(os, home, &[".config"]), PathBuf::from),
+    }
+}
+
+fn temp(os: TargetOs, env: &impl Env) -> PathBuf {
+    let var = match os {
+        // The order of `GetTempPath`, whose result Chromium strips of its trailing separator.
+        TargetOs::Windows => non_empty_var(env, "TMP")
+            .or_else(|| non_empty_var(env, "TEMP"))
+            .or_else(|| non_empty_var(env, "USERPROFILE"))
+            .or_else(|| non_empty_var(env, "SystemRoot")),
+        _ => non_empty_var(env, "TMPDIR"),
+    };
+    let Some(var) = var else {
+        return PathBuf::from(match os {
+            TargetOs::Windows => r"C:\Windows",
+            _ => "/tmp",
+        });
+    };
+
+    match var.to_str() {
+        Some(var) if os == TargetOs::Windows && var.len() > 3 => {
+            PathBuf::from(var.trim_end_matches(['\\', '/']))
+        }
+        _ => PathBuf::from(var),
+    }
+}
+
+fn non_empty_var(env: &impl Env, key: &str) -> Option<OsString> {
+    env.var_os(key).filter(|value| !value.is_empty())
+}
@@ -0,0 +1,319 @@
+//! The API of Python's [`platformdirs`](https://platformdirs.readthedocs.io), for applications shared with Python code.
+//!
+//! The functions take the same parameters as their Python counterparts and follow its layout: the application's name
+//! and version become trailing path components, and on Windows the author comes first and `roaming` chooses between
+//! `%APPDATA%` and `%LOCALAPPDATA%`. The cache and log directories get their own `Cache` and `Logs` (or `log`)
+//! folders where `platformdirs` puts them, as with its default `opinion=True`.
+//!
+//! Unlike `platformdirs`, an XDG variable set to an absolute path is respected on every platform, as everywhere in this
+//! crate. The directory then follows the Linux layout, `<XDG dir>/<app>/<version>`.
+//!
+//! | Function            | Linux                                | macOS                                 | Windows                                         |
+//! | ------------------- | ------------------------------------ | ------------------------------------- | ----------------------------------------------- |
+//! | [`user_data_dir`]   | `~/.local/share/<app>/<version>`     | `~/Library/Application Support/<app>` | `%APPDATA%\<author>\<app>\<version>`            |
+//! | [`user_config_dir`] | `~/.config/<app>/<version>`          | `~/Library/Application Support/<app>` | `%APPDATA%\<author>\<app>\<version>`            |
+//! | [`user_cache_dir`]  | `~/.cache/<app>/<version>`           | `~/Library/Caches/<app>`              | `%LOCALAPPDATA%\<author>\<app>\Cache\<version>` |
+//! | [`user_state_dir`]  | `~/.local/state/<app>/<version>`     | `~/Library/Application Support/<app>` | `%APPDATA%\<author>\<app>\<version>`            |
+//! | [`user_log_dir`]    | `~/.local/state/<app>/<version>/log` | `~/Library/Logs/<app>`                | `%APPDATA%\<author>\<app>\<version>\Logs`       |
+//! | [`site_data_dir`]   | `/usr/local/share/<app>/<version>`   | `/Library/Application Support/<app>`  | `%PROGRAMDATA%\<author>\<app>\<version>`        |
+//! | [`site_config_dir`] | `/etc/xdg/<app>/<version>`           | `/Library/Application Support/<app>`  | `%PROGRAMDATA%\<author>\<app>\<version>`        |
+//! | [`site_cache_dir`]  | `/var/cache/<app>/<version>`         | `/Library/Caches/<app>`               | `%PROGRAMDATA%\<author>\<app>\Cache\<version>`  |
+//!
+//! On macOS, `<version>` follows `<app>` as well. On Windows, `%LOCALAPPDATA%` replaces `%APPDATA%` unless `roaming` is set.
+//!
+//! ```rust,no_run
+//! use user_dirs::compat::platformdirs::{user_config_dir, AppAuthor};
+//!
+//! let config = user_config_dir(Some("SuperApp"), AppAuthor::Name("Acme"), Some("1.0"), false)?;
+//! # Ok::<(), user_dirs::UserDirsError>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    env::{self, Env, ProcessEnv, TargetOs},
+    home_dir, DirKind, UserDirsError,
+};
+
+/// The `appauthor` parameter of `platformdirs`, which is only part of the path on Windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppAuthor<'a> {
+    /// `None`: the application's name is used as the author too.
+    #[default]
+    Default,
+    /// The author's or company's name.
+    Name(&'a str),
+    /// `False`: there is no author component.
+    Omit,
+}
+
+/// The directories of `platformdirs`, see the [module documentation](self) for their locations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PlatformDir {
+    /// `user_data_dir`.
+    UserData,
+    /// `user_config_dir`.
+    UserConfig,
+    /// `user_cache_dir`.
+    UserCache,
+    /// `user_state_dir`.
+    UserState,
+    /// `user_log_dir`.
+    UserLog,
+    /// `site_data_dir`.
+    SiteData,
+    /// `site_config_dir`.
+    SiteConfig,
+    /// `site_cache_dir`.
+    SiteCache,
+}
+
+/// The parameters shared by every `platformdirs` function, like its `PlatformDirs` class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PlatformDirs<'a> {
+    /// The application's name, or `None` for the base directory itself.
+    pub appname: Option<&'a str>,
+    /// The application's author, see [`AppAuthor`].
+    pub appauthor: AppAuthor<'a>,
+    /// The application's version, appended after its name if both are set.
+    pub version: Option<&'a str>,
+    /// Whether to use the roaming `%APPDATA%` instead of `%LOCALAPPDATA%` on Windows.
+    pub roaming: bool,
+}
+
+impl PlatformDirs<'_> {
+    /// Returns the path to the directory `dir` on this machine.
+    pub fn dir(&self, dir: PlatformDir) -> Result<PathBuf, UserDirsError> {
+        let home = home_dir()?;
+        Ok(self.dir_for(dir, TargetOs::current(), &home, &ProcessEnv))
+    }
+
+    /// Returns the path to the directory `dir` on `os`.
+    ///
+    /// Like [`env::dir_for`], this reads variables from `env` instead of the process environment and joins paths with
+    /// the separator of `os`, so it can compute the paths of another machine.
+    #[must_use]
+    pub fn dir_for(&self, dir: PlatformDir, os: TargetOs, home: &Path, env: &impl Env) -> PathBuf {
+        let xdg_kind = match dir {
+            PlatformDir::UserData => Some(DirKind::Data),
+            PlatformDir::UserConfig => Some(DirKind::Config),
+            PlatformDir::UserCache => Some(DirKind::Cache),
+            PlatformDir::UserState | PlatformDir::UserLog => Some(DirKind::State),
+            _ => None,
+        };
+        if let Some(xdg) = xdg_kind.and_then(|kind| env::xdg_dir(kind, os, env)) {
+            let path = self.join(TargetOs::Linux, os, &xdg, None);
+            return if dir == PlatformDir::UserLog {
+                env::join(os, &path, &["log"])
+            } else {
+                path
+            };
+        }
+
+        match os {
+            TargetOs::Windows => self.windows_dir(dir, home, env),
+            TargetOs::MacOs => {
+                let (base, parts): (&Path, &[&str]) = match dir {
+                    PlatformDir::UserCache => (home, &["Library", "Caches"]),
+                    PlatformDir::UserLog => (home, &["Library", "Logs"]),
+                    PlatformDir::SiteData | PlatformDir::SiteConfig => {
+                        (Path::new("/Library/Application Support"), &[])
+                    }
+                    PlatformDir::SiteCache => (Path::new("/Library/Caches"), &[]),
+                    _ => (home, &["Library", "Application Support"]),
+                };
+                self.join(os, os, &env::join(os, base, parts), None)
+            }
+            _ => {
+                let base = match dir {
+                    PlatformDir::UserData => env::join(os, home, &[".local", "share"]),
+                    PlatformDir::UserConfig => env::join(os, home, &[".config"]),
+                    PlatformDir::UserCache => env::join(os, home, &[".cache"]),
+                    PlatformDir::UserState | PlatformDir::UserLog => {
+                        env::join(os, home, &[".local", "state"])
+                    }
+                    PlatformDir::SiteData => first_entry(os, env, "XDG_DATA_DIRS")
+                        .unwrap_or_else(|| "/usr/local/share".into()),
+                    PlatformDir::SiteConfig => {
+                        first_entry(os, env, "XDG_CONFIG_DIRS").unwrap_or_else(|| "/etc/xdg".into())
+                    }
+                    PlatformDir::SiteCache => PathBuf::from("/var/cache"),
+                };
+                let path = self.join(os, os, &base, None);
+                if dir == PlatformDir::UserLog {
+                    env::join(os, &path, &["log"])
+                } else {
+                    path
+                }
+            }
+        }
+    }
+
+    fn windows_dir(&self, dir: PlatformDir, home: &Path, env: &impl Env) -> PathBuf {
+        let os = TargetOs::Windows;
+        let var = |key, default: &[&str]| {
+            env::var(os, env, key).map_or_else(|| env::join(os, home, default), PathBuf::from)
+        };
+        let app_data = || {
+            if self.roaming {
+                var("APPDATA", &["AppData", "Roaming"])
+            } else {
+                var("LOCALAPPDATA", &["AppData", "Local"])
+            }
+        };
+        let program_data = || {
+            env::var(os, env, "PROGRAMDATA")
+                .map_or_else(|| PathBuf::from(r"C:\ProgramData"), PathBuf::from)
+        };
+
+        match dir {
+            PlatformDir::UserCache => self.join(
+                os,
+                os,
+                &var("LOCALAPPDATA", &["AppData", "Local"]),
+                Some("Cache"),
+            ),
+            PlatformDir::UserLog => env::join(os, &self.join(os, os, &app_data(), None), &["Logs"]),
+            PlatformDir::SiteData | PlatformDir::SiteConfig => {
+                self.join(os, os, &program_data(), None)
+            }
+            PlatformDir::SiteCache => self.join(os, os, &program_data(), Some("Cache")),
+            _ => self.join(os, os, &app_data(), None),
+        }
+    }
+
+    /// Appends the application's components to `base` following the layout of `layout`, like `platformdirs` does.
+    fn join(&self, layout: TargetOs, os: TargetOs, base: &Path, opinion: Option<&str>) -> PathBuf {
+        let Some(appname) = self.appname.filter(|name| !name.is_empty()) else {
+            return base.to_path_buf();
+        };
+
+        let mut parts = Vec::new();
+        if layout == TargetOs::Windows {
+            match self.appauthor {
+                AppAuthor::Default => parts.push(appname),
+                AppAuthor::Name(author) => parts.push(author),
+                AppAuthor::Omit => {}
+            }
+        }
+        parts.push(appname);
+        parts.extend(opinion);
+        parts.extend(self.version.filter(|version| !version.is_empty()));
+        env::join(os, base, &parts)
+    }
+}
+
+fn first_entry(os: TargetOs, env: &impl Env, key: &str) -> Option<PathBuf> {
+    let value = env.var_os(key)?;
+    let value = value.to_str()?;
+    value
+        .split(':')
+        .find(|entry| env::is_absolute(os, entry.as_ref()))
+        .map(PathBuf::from)
+}
+
+fn resolve(
+    dir: PlatformDir,
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+    roaming: bool,
+) -> Result<PathBuf, UserDirsError> {
+    PlatformDirs {
+        appname,
+        appauthor,
+        version,
+        roaming,
+    }
+    .dir(dir)
+}
+
+/// Returns the path to the user's data directory for the application, like `platformdirs.user_data_dir`.
+pub fn user_data_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+    roaming: bool,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::UserData, appname, appauthor, version, roaming)
+}
+
+/// Returns the path to the user's config directory for the application, like `platformdirs.user_config_dir`.
+pub fn user_config_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+    roaming: bool,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(
+        PlatformDir::UserConfig,
+        appname,
+        appauthor,
+        version,
+        roaming,
+    )
+}
+
+/// Returns the path to the user's cache directory for the application, like `platformdirs.user_cache_dir`.
+///
+/// On Windows, this is always in `%LOCALAPPDATA%`, with a `Cache` folder before the version.
+pub fn user_cache_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::UserCache, appname, appauthor, version, false)
+}
+
+/// Returns the path to the user's state directory for the application, like `platformdirs.user_state_dir`.
+pub fn user_state_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+    roaming: bool,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::UserState, appname, appauthor, version, roaming)
+}
+
+/// Returns the path to the user's log directory for the application, like `platformdirs.user_log_dir`.
+///
+/// This is a `log` folder in the state directory on Linux, and a `Logs` folder in the data directory on Windows.
+pub fn user_log_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::UserLog, appname, appauthor, version, false)
+}
+
+/// Returns the path to the system-wide data directory for the application, like `platformdirs.site_data_dir`.
+///
+/// On Linux, this is in the first entry of `$XDG_DATA_DIRS`.
+pub fn site_data_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::SiteData, appname, appauthor, version, false)
+}
+
+/// Returns the path to the system-wide config directory for the application, like `platformdirs.site_config_dir`.
+///
+/// On Linux, this is in the first entry of `$XDG_CONFIG_DIRS`.
+pub fn site_config_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::SiteConfig, appname, appauthor, version, false)
+}
+
+/// Returns the path to the system-wide cache directory for the application, like `platformdirs.site_cache_dir`.
+pub fn site_cache_dir(
+    appname: Option<&str>,
+    appauthor: AppAuthor<'_>,
+    version: Option<&str>,
+) -> Result<PathBuf, UserDirsError> {
+    resolve(PlatformDir::SiteCache, appname, appauthor, version, false)
+}
@@ -0,0 +1,261 @@
+//! The locations of Qt's [`QStandardPaths`](https://doc.qt.io/qt-6/qstandardpaths.html), for applications whose
+//! Rust and Qt parts must agree on where files live.
+//!
+//! [`writable_location`] and [`standard_locations`] reproduce `QStandardPaths::writableLocation` and
+//! `QStandardPaths::standardLocations` on Linux (and other Unix systems), macOS and Windows, including the system-wide
+//! entries of the latter. Qt's application-specific locations end with `<APPNAME>`, which is the organization's name
+//! followed by the application's name, leaving out whichever is empty; here both are passed explicitly instead of being
+//! read from `QCoreApplication`.
+//!
+//! Like Qt, the XDG variables are only respected on Linux, and only when set to absolute paths. The paths use the
+//! platform's separator, where Qt always uses `/`.
+//!
+//! ```rust,no_run
+//! use user_dirs::compat::qt::{standard_locations, writable_location, StandardLocation};
+//!
+//! let config = writable_location(StandardLocation::AppConfig, "Acme", "Editor");
+//! let search = standard_locations(StandardLocation::AppData, "Acme", "Editor");
+//! ```
+
+use std::{env, path::PathBuf};
+
+use crate::{
+    env::{ProcessEnv, TargetOs},
+    home_dir, media, UserDirKind,
+};
+
+/// The locations of `QStandardPaths::StandardLocation`, named without the `Location` suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StandardLocation {
+    /// `DesktopLocation`, the user's desktop.
+    Desktop,
+    /// `DocumentsLocation`, the user's documents.
+    Documents,
+    /// `FontsLocation`, the fonts.
+    Fonts,
+    /// `ApplicationsLocation`, the installed applications or their shortcuts.
+    Applications,
+    /// `MusicLocation`, the user's music.
+    Music,
+    /// `MoviesLocation`, the user's videos.
+    Movies,
+    /// `PicturesLocation`, the user's pictures.
+    Pictures,
+    /// `TempLocation`, the temporary directory.
+    Temp,
+    /// `HomeLocation`, the user's home directory.
+    Home,
+    /// `AppLocalDataLocation`, the application's local (non-roaming) data.
+    AppLocalData,
+    /// `CacheLocation`, the application's cache.
+    Cache,
+    /// `GenericDataLocation`, data shared between applications.
+    GenericData,
+    /// `RuntimeLocation`, runtime files such as sockets.
+    Runtime,
+    /// `ConfigLocation`, the user's config files.
+    Config,
+    /// `DownloadLocation`, the user's downloads.
+    Download,
+    /// `GenericCacheLocation`, cache shared between applications.
+    GenericCache,
+    /// `GenericConfigLocation`, config files shared between applications.
+    GenericConfig,
+    /// `AppDataLocation`, the application's data, which roams on Windows.
+    AppData,
+    /// `AppConfigLocation`, the application's config files.
+    AppConfig,
+    /// `PublicShareLocation`, files shared with other users.
+    PublicShare,
+    /// `TemplatesLocation`, the user's templates.
+    Templates,
+}
+
+/// Returns the directory files of type `location` should be written to, like `QStandardPaths::writableLocation`.
+///
+/// Returns `None` if the home directory can't be located or the location is unavailable, such as a disabled user
+/// directory. Like Qt, the fonts and applications directories are returned on macOS and Windows even though only the
+/// system can write to them.
+#[must_use]
+pub fn writable_location(location: StandardLocation, org: &str, app: &str) -> Option<PathBuf> {
+    use StandardLocation as L;
+
+    let os = TargetOs::current();
+    let home = home_dir().ok()?;
+    let user_dir = |kind| media::user_dir(kind).ok().flatten();
+
+    Some(match (os, location) {
+        (_, L::Home) | (TargetOs::Windows, L::Runtime) => home,
+        (_, L::Temp) => temp_dir(),
+        (_, L::Desktop) => user_dir(UserDirKind::Desktop)?,
+        (_, L::Documents) => user_dir(UserDirKind::Documents)?,
+        (_, L::Download) => user_dir(UserDirKind::Download)?,
+        (_, L::Music) => user_dir(UserDirKind::Music)?,
+        (_, L::Movies) => user_dir(UserDirKind::Videos)?,
+        (_, L::Pictures) => user_dir(UserDirKind::Pictures)?,
+        (_, L::PublicShare) => user_dir(UserDirKind::PublicShare)?,
+        (_, L::Templates) => user_dir(UserDirKind::Templates)?,
+
+        (TargetOs::MacOs, L::Fonts) => PathBuf::from("/System/Library/Fonts"),
+        (TargetOs::MacOs, L::Applications) => PathBuf::from("/Applications"),
+        (TargetOs::MacOs, L::GenericData | L::Runtime) => {
+            home.join("Library").join("Application Support")
+        }
+        (TargetOs::MacOs, L::AppData | L::AppLocalData) => {
+            with_app(home.join("Library").join("Application Support"), org, app)
+        }
+        (TargetOs::MacOs, L::GenericCache) => home.join("Library").join("Caches"),
+        (TargetOs::MacOs, L::Cache) => with_app(home.join("Library").join("Caches"), org, app),
+        (TargetOs::MacOs, L::Config | L::GenericConfig) => home.join("Library").join("Preferences"),
+        (TargetOs::MacOs, L::AppConfig) => {
+            with_app(home.join("Library").join("Preferences"), org, app)
+        }
+
+        (TargetOs::Windows, L::Fonts) => windows_dir().join("Fonts"),
+        (TargetOs::Windows, L::Applications) => crate::os::data_dir()
+            .ok()?
+            .join(r"Microsoft\Windows\Start Menu\Programs"),
+        (TargetOs::Windows, L::GenericData | L::GenericConfig) => crate::os::cache_dir().ok()?,
+        (TargetOs::Windows, L::AppLocalData | L::Config | L::AppConfig) => {
+            with_app(crate::os::cache_dir().ok()?, org, app)
+        }
+        (TargetOs::Windows, L::AppData) => with_app(crate::os::data_dir().ok()?, org, app),
+        (TargetOs::Windows, L::GenericCache) => crate::os::cache_dir().ok()?.join("cache"),
+        (TargetOs::Windows, L::Cache) => {
+            with_app(crate::os::cache_dir().ok()?, org, app).join("cache")
+        }
+
+        (_, L::Fonts) => crate::data_dir().ok()?.join("fonts"),
+        (_, L::Applications) => crate::data_dir().ok()?.join("applications"),
+        (_, L::Runtime) => {
+            #[cfg(unix)]
+            let dir = crate::runtime_dir().unwrap_or_else(run_user_dir);
+            #[cfg(not(unix))]
+            let dir = crate::runtime_dir()?;
+            dir
+        }
+        (_, L::GenericData) => crate::data_dir().ok()?,
+        (_, L::AppData | L::AppLocalData) => with_app(crate::data_dir().ok()?, org, app),
+        (_, L::Config | L::GenericConfig) => crate::config_dir().ok()?,
+        (_, L::AppConfig) => with_app(crate::config_dir().ok()?, org, app),
+        (_, L::GenericCache) => crate::cache_dir().ok()?,
+        (_, L::Cache) => with_app(crate::cache_dir().ok()?, org, app),
+    })
+}
+
+/// Returns every directory files of type `location` are looked for in, like `QStandardPaths::standardLocations`.
+///
+/// The [`writable_location`] comes first, followed by the system-wide directories: the entries of `$XDG_CONFIG_DIRS`
+/// and `$XDG_DATA_DIRS` on Linux, `/Library` on macOS, and `%PROGRAMDATA%` and the executable's directory on Windows.
+#[must_use]
+pub fn standard_locations(location: StandardLocation, org: &str, app: &str) -> Vec<PathBuf> {
+    use StandardLocation as L;
+
+    let mut dirs = Vec::new();
+    match (TargetOs::current(), location) {
+        (TargetOs::MacOs, L::GenericData | L::AppData | L::AppLocalData) => {
+            let support = PathBuf::from("/Library/Application Support");
+            dirs.push(if location == L::GenericData {
+                support
+            } else {
+                with_app(support, org, app)
+            });
+            if location != L::GenericData {
+                dirs.extend(exe_dir().map(|dir| match dir.file_name() {
+                    Some(name) if name == "MacOS" => dir.with_file_name("Resources"),
+                    _ => dir,
+                }));
+            }
+        }
+        (TargetOs::MacOs, L::GenericCache) => dirs.push(PathBuf::from("/Library/Caches")),
+        (TargetOs::MacOs, L::Cache) => dirs.push(with_app("/Library/Caches".into(), org, app)),
+        (
+            TargetOs::Windows,
+            L::Config
+            | L::AppConfig
+            | L::AppData
+            | L::AppLocalData
+            | L::GenericData
+            | L::GenericConfig,
+        ) => {
+            let generic = matches!(location, L::GenericData | L::GenericConfig);
+            let program_data = crate::env::var(TargetOs::Windows, &ProcessEnv, "PROGRAMDATA")
+                .map_or_else(|| PathBuf::from(r"C:\ProgramData"), PathBuf::from);
+            dirs.push(if generic {
+                program_data
+            } else {
+                with_app(program_data, org, app)
+            });
+            if let Some(exe_dir) = exe_dir() {
+                let data = exe_dir.join("data");
+                let app_data = with_app(data.clone(), org, app);
+                dirs.extend([exe_dir, data.clone()]);
+                if !generic && app_data != data {
+                    dirs.push(app_data);
+                }
+            }
+        }
+        (TargetOs::MacOs | TargetOs::Windows, _) => {}
+        (_, L::Config | L::GenericConfig) => dirs.extend(crate::system_config_dirs()),
+        (_, L::AppConfig) => {
+            dirs.extend(crate::system_config_dirs().map(|dir| with_app(dir, org, app)));
+        }
+        (_, L::GenericData) => dirs.extend(crate::system_data_dirs()),
+        (_, L::AppData | L::AppLocalData) => {
+            dirs.extend(crate::system_data_dirs().map(|dir| with_app(dir, org, app)));
+        }
+        (_, L::Fonts) => {
+            dirs.extend(home_dir().ok().map(|home| home.join(".fonts")));
+            dirs.extend(crate::system_data_dirs().map(|dir| dir.join("fonts")));
+        }
+        (_, L::Applications) => {
+            dirs.extend(crate::system_data_dirs().map(|dir| dir.join("applications")));
+        }
+        _ => {}
+    }
+
+    if let Some(writable) = writable_location(location, org, app) {
+        if !dirs.contains(&writable) {
+            dirs.insert(0, writable);
+        }
+    }
+    dirs
+}
+
+/// Appends Qt's `<APPNAME>`: the organization's name, then the application's name, leaving out whichever is empty.
+fn with_app(mut path: PathBuf, org: &str, app: &str) -> PathBuf {
+    for part in [org, app] {
+        if !part.is_empty() {
+            path.push(part);
+        }
+    }
+    path
+}
+
+/// Returns `QDir::tempPath()`, without a trailing separator.
+fn temp_dir() -> PathBuf {
+    // Qt uses `NSTemporaryDirectory()` on macOS, which is the per-user temporary directory.
+    #[cfg(target_os = "macos")]
+    if let Some(temp) = crate::os::macos::darwin_user_temp_dir() {
+        return temp;
+    }
+
+    env::temp_dir().components().collect()
+}
+
+fn windows_dir() -> PathBuf {
+    crate::env::var(TargetOs::Windows, &ProcessEnv, "WINDIR")
+        .map_or_else(|| PathBuf::from(r"C:\Windows"), PathBuf::from)
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    Some(env::current_exe().ok()?.parent()?.to_path_buf())
+}
+
+/// Returns `/run/user/<uid>`, which Qt uses when `$XDG_RUNTIME_DIR` is unset.
+#[cfg(unix)]
+fn run_user_dir() -> PathBuf {
+    // SAFETY: `getuid` has no preconditions and cannot fail.
+    PathBuf::from(format!("/run/user/{}", unsafe { libc::getuid() }))
+}
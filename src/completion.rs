@@ -0,0 +1,69 @@
+use std::{env, fmt, path::PathBuf};
+
+use crate::{
+    documents_dir,
+    env::{Env, ProcessEnv},
+    install::xdg_style,
+    DirKind, HomeDirError,
+};
+
+/// The shells [`completion_dir`] knows about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Shell {
+    /// Bash, with the `bash-completion` package.
+    Bash,
+    /// Zsh.
+    Zsh,
+    /// Fish.
+    Fish,
+    /// PowerShell 7 or later.
+    PowerShell,
+    /// Elvish.
+    Elvish,
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::PowerShell => "powershell",
+            Self::Elvish => "elvish",
+        })
+    }
+}
+
+/// Returns the per-user directory to install completion scripts for `shell` in, if there is one.
+///
+/// | Shell        | Directory                                               | Picked up automatically?    |
+/// | ------------ | ------------------------------------------------------- | --------------------------- |
+/// | Bash         | `$XDG_DATA_HOME/bash-completion/completions`            | yes                         |
+/// | Zsh          | `$XDG_DATA_HOME/zsh/site-functions`                     | no, add it to `fpath`       |
+/// | Fish         | `$XDG_CONFIG_HOME/fish/completions`                     | yes                         |
+/// | `PowerShell` | `Documents\PowerShell` or `$XDG_CONFIG_HOME/powershell` | no, source it in `$PROFILE` |
+/// | Elvish       | `$XDG_CONFIG_HOME/elvish/lib` or `%APPDATA%\elvish\lib` | no, `use` it in `rc.elv`    |
+///
+/// These shells follow the XDG conventions on macOS too, so `$XDG_DATA_HOME` and `$XDG_CONFIG_HOME` default to
+/// `~/.local/share` and `~/.config` there. Bash's directory is `$BASH_COMPLETION_USER_DIR/completions` if that is set.
+/// There is no directory for Bash and Zsh on Windows.
+pub fn completion_dir(shell: Shell) -> Result<Option<PathBuf>, HomeDirError> {
+    let windows = env::consts::OS == "windows";
+
+    Ok(match shell {
+        Shell::Bash | Shell::Zsh if windows => None,
+        Shell::Bash => Some(match ProcessEnv.var_os("BASH_COMPLETION_USER_DIR") {
+            Some(dir) => PathBuf::from(dir).join("completions"),
+            None => xdg_style(DirKind::Data)?
+                .join("bash-completion")
+                .join("completions"),
+        }),
+        Shell::Zsh => Some(xdg_style(DirKind::Data)?.join("zsh").join("site-functions")),
+        Shell::Fish => Some(xdg_style(DirKind::Config)?.join("fish").join("completions")),
+        Shell::PowerShell if windows => documents_dir()?.map(|dir| dir.join("PowerShell")),
+        Shell::PowerShell => Some(xdg_style(DirKind::Config)?.join("powershell")),
+        Shell::Elvish if windows => Some(crate::os::config_dir()?.join("elvish").join("lib")),
+        Shell::Elvish => Some(xdg_style(DirKind::Config)?.join("elvish").join("lib")),
+    })
+}
@@ -0,0 +1,189 @@
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process,
+    time::SystemTime,
+};
+
+use crate::{install::xdg_style, DirKind, HomeDirError};
+
+/// Returns the path to the directory for user-installed desktop entries, `$XDG_DATA_HOME/applications`, if available.
+///
+/// Desktop entries are how applications appear in the menus of Linux desktop environments. There is no such directory
+/// on macOS and Windows.
+pub fn application_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(match env::consts::OS {
+        "macos" | "windows" => None,
+        _ => Some(xdg_style(DirKind::Data)?.join("applications")),
+    })
+}
+
+/// Installs a desktop entry named `<file_stem>.desktop`, see [`DesktopEntryOptions::install`].
+pub fn install_desktop_entry(
+    file_stem: &str,
+    contents: &str,
+) -> Result<PathBuf, DesktopEntryError> {
+    DesktopEntryOptions::new().install(file_stem, contents)
+}
+
+/// Removes the desktop entry named `<file_stem>.desktop`, see [`DesktopEntryOptions::uninstall`].
+pub fn uninstall_desktop_entry(file_stem: &str) -> Result<bool, DesktopEntryError> {
+    DesktopEntryOptions::new().uninstall(file_stem)
+}
+
+/// Asynchronous version of [`install_desktop_entry`], running it on tokio's blocking thread pool.
+#[cfg(feature = "tokio")]
+pub async fn install_desktop_entry_async(
+    file_stem: &str,
+    contents: &str,
+) -> Result<PathBuf, DesktopEntryError> {
+    let (file_stem, contents) = (file_stem.to_owned(), contents.to_owned());
+    crate::ensure::unblock(move || install_desktop_entry(&file_stem, &contents)).await
+}
+
+/// Asynchronous version of [`uninstall_desktop_entry`], running it on tokio's blocking thread pool.
+#[cfg(feature = "tokio")]
+pub async fn uninstall_desktop_entry_async(file_stem: &str) -> Result<bool, DesktopEntryError> {
+    let file_stem = file_stem.to_owned();
+    crate::ensure::unblock(move || uninstall_desktop_entry(&file_stem)).await
+}
+
+/// Options for installing and removing desktop entries in the [`application_dir`].
+///
+/// Running `update-desktop-database` afterwards is left to the caller.
+#[derive(Debug, Clone, Default)]
+pub struct DesktopEntryOptions {
+    touch_dir: bool,
+}
+
+impl DesktopEntryOptions {
+    /// Creates the default options, which leave the directory's modification time alone.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the directory's modification time is updated afterwards, so desktop environments that only check
+    /// it notice the change.
+    pub fn touch_dir(&mut self, touch: bool) -> &mut Self {
+        self.touch_dir = touch;
+        self
+    }
+
+    /// Writes `contents` to `<file_stem>.desktop` in the [`application_dir`], creating the directory if needed, and
+    /// returns the file's path.
+    ///
+    /// The stem must follow the desktop entry naming rules: one or more elements separated by `.`, each made of ASCII
+    /// letters, digits, `-` and `_` and not starting with a digit. A reverse DNS name like `org.example.MyApp` is
+    /// recommended. The file is written next to its destination first and then renamed over it, so desktop
+    /// environments never read a partial entry.
+    ///
+    /// This fails with [`DesktopEntryError::InvalidName`] for an invalid stem, and with
+    /// [`DesktopEntryError::Unsupported`] on macOS and Windows, which have no desktop entries.
+    pub fn install(&self, file_stem: &str, contents: &str) -> Result<PathBuf, DesktopEntryError> {
+        let dir = entries_dir(file_stem)?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{file_stem}.desktop"));
+        let temp = dir.join(format!(".{file_stem}.desktop.{}.tmp", process::id()));
+
+        if let Err(err) = fs::write(&temp, contents).and_then(|()| fs::rename(&temp, &path)) {
+            let _ = fs::remove_file(&temp);
+            return Err(err.into());
+        }
+        self.touch(&dir)?;
+        Ok(path)
+    }
+
+    /// Removes `<file_stem>.desktop` from the [`application_dir`], returning whether it existed.
+    ///
+    /// The stem is checked like for [`DesktopEntryOptions::install`].
+    pub fn uninstall(&self, file_stem: &str) -> Result<bool, DesktopEntryError> {
+        let dir = entries_dir(file_stem)?;
+        match fs::remove_file(dir.join(format!("{file_stem}.desktop"))) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+        self.touch(&dir)?;
+        Ok(true)
+    }
+
+    /// Updates the modification time of `dir`, if enabled.
+    fn touch(&self, dir: &Path) -> io::Result<()> {
+        if self.touch_dir {
+            File::open(dir)?.set_modified(SystemTime::now())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the directory of the desktop entry named `file_stem`, checking the name.
+fn entries_dir(file_stem: &str) -> Result<PathBuf, DesktopEntryError> {
+    if !is_valid_stem(file_stem) {
+        return Err(DesktopEntryError::InvalidName(file_stem.to_owned()));
+    }
+    application_dir()?.ok_or(DesktopEntryError::Unsupported)
+}
+
+/// Returns whether `stem` follows the desktop entry naming rules.
+fn is_valid_stem(stem: &str) -> bool {
+    stem.split('.').all(|element| {
+        element
+            .chars()
+            .next()
+            .is_some_and(|first| !first.is_ascii_digit())
+            && element
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    })
+}
+
+/// This error occurs when a desktop entry cannot be installed or removed.
+#[derive(Debug)]
+pub enum DesktopEntryError {
+    /// The home directory could not be located.
+    HomeDir(HomeDirError),
+    /// The file stem does not follow the desktop entry naming rules.
+    InvalidName(String),
+    /// The platform has no desktop entries, like macOS and Windows.
+    Unsupported,
+    /// The desktop entry could not be written or removed.
+    Io(io::Error),
+}
+
+impl fmt::Display for DesktopEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HomeDir(err) => err.fmt(f),
+            Self::InvalidName(name) => write!(f, "invalid desktop entry name {name:?}"),
+            Self::Unsupported => write!(f, "desktop entries are not supported on this platform"),
+            Self::Io(_) => write!(f, "could not update desktop entry"),
+        }
+    }
+}
+
+impl Error for DesktopEntryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::HomeDir(err) => Some(err),
+            Self::InvalidName(_) | Self::Unsupported => None,
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<HomeDirError> for DesktopEntryError {
+    fn from(err: HomeDirError) -> Self {
+        Self::HomeDir(err)
+    }
+}
+
+impl From<io::Error> for DesktopEntryError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
@@ -0,0 +1,62 @@
+use std::{fmt, path::PathBuf};
+
+use crate::{DirKind, UserDirs};
+
+/// Compares two snapshots, such as before and after [`UserDirs::reload`].
+#[must_use]
+pub fn compare(old: &UserDirs, new: &UserDirs) -> UserDirsDiff {
+    let entries = DirKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let (old, new) = (old.get(kind), new.get(kind));
+            (old != new).then(|| (kind, old.map(PathBuf::from), new.map(PathBuf::from)))
+        })
+        .collect();
+    UserDirsDiff { entries }
+}
+
+/// The directories that differ between two snapshots, returned by [`compare`].
+///
+/// Its [`Display`](fmt::Display) output resembles a unified diff, with a line for each directory on either side:
+///
+/// ```text
+/// -config = /home/leah/.config
+/// +config = /home/leah/dotfiles
+/// +runtime = /run/user/1000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserDirsDiff {
+    entries: Vec<(DirKind, Option<PathBuf>, Option<PathBuf>)>,
+}
+
+impl UserDirsDiff {
+    /// Returns whether any directory differs.
+    #[must_use]
+    pub fn has_changes(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// Returns the directories available in both snapshots whose paths differ, as `(kind, old, new)`.
+    ///
+    /// Directories that only one snapshot has, such as a runtime directory that appeared, are left out here but shown
+    /// by the [`Display`](fmt::Display) output.
+    pub fn changed(&self) -> impl Iterator<Item = (DirKind, &PathBuf, &PathBuf)> {
+        self.entries
+            .iter()
+            .filter_map(|(kind, old, new)| Some((*kind, old.as_ref()?, new.as_ref()?)))
+    }
+}
+
+impl fmt::Display for UserDirsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, old, new) in &self.entries {
+            if let Some(old) = old {
+                writeln!(f, "-{kind} = {}", old.display())?;
+            }
+            if let Some(new) = new {
+                writeln!(f, "+{kind} = {}", new.display())?;
+            }
+        }
+        Ok(())
+    }
+}
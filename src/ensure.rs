@@ -0,0 +1,132 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{home_dir, media::user_dirs, DirKind, UserDirKind};
+
+/// Resolves the directory of `kind`, treating a directory unavailable on this platform as an error.
+fn target(kind: DirKind) -> io::Result<PathBuf> {
+    kind.resolve()?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no {kind} directory on this platform"),
+        )
+    })
+}
+
+fn ensure(kind: DirKind) -> io::Result<PathBuf> {
+    let dir = target(kind)?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Returns the path to the cache directory, creating it if it does not exist.
+pub fn ensure_cache_dir() -> io::Result<PathBuf> {
+    ensure(DirKind::Cache)
+}
+
+/// Returns the path to the config directory, creating it if it does not exist.
+pub fn ensure_config_dir() -> io::Result<PathBuf> {
+    ensure(DirKind::Config)
+}
+
+/// Returns the path to the data directory, creating it if it does not exist.
+///
+/// A home directory that can't be located is reported as an [`io::Error`] of kind [`io::ErrorKind::NotFound`], so it
+/// can be propagated with `?`:
+///
+/// ```rust,no_run
+/// fn open_database() -> std::io::Result<std::fs::File> {
+///     std::fs::File::create(user_dirs::ensure_data_dir()?.join("db.sqlite"))
+/// }
+/// ```
+pub fn ensure_data_dir() -> io::Result<PathBuf> {
+    ensure(DirKind::Data)
+}
+
+/// Returns the path to the state directory, creating it if it does not exist.
+///
+/// Fails with [`io::ErrorKind::NotFound`] if there is no state directory on this platform.
+pub fn ensure_state_dir() -> io::Result<PathBuf> {
+    ensure(DirKind::State)
+}
+
+/// Creates every missing user directory, such as `~/Downloads`, returning the ones created.
+///
+/// This mirrors what `xdg-user-dirs-update` does on first login, for systems where it never ran. The directories are
+/// resolved like [`user_dir`](crate::user_dir), so `user-dirs.dirs` and `user-dirs.defaults` are respected, and
+/// directories disabled by setting them to `$HOME` are left alone. Only directories inside the home directory are
+/// created, so shared locations such as `C:\Users\Public` are never touched. With `dry_run`, nothing is created and
+/// the directories that would have been are returned.
+pub fn ensure_user_dirs(dry_run: bool) -> io::Result<Vec<(UserDirKind, PathBuf)>> {
+    let home = home_dir()?;
+    let dirs = user_dirs()?;
+
+    let mut created = Vec::new();
+    for kind in UserDirKind::ALL {
+        let Some(Some(dir)) = dirs.get(&kind) else {
+            continue;
+        };
+        if dir == &home || !dir.starts_with(&home) || fs::symlink_metadata(dir).is_ok() {
+            continue;
+        }
+
+        if !dry_run {
+            fs::create_dir_all(dir)?;
+        }
+        created.push((kind, dir.clone()));
+    }
+    Ok(created)
+}
+
+/// Asynchronous version of [`ensure_user_dirs`], running it on tokio's blocking thread pool.
+#[cfg(feature = "tokio")]
+pub async fn ensure_user_dirs_async(dry_run: bool) -> io::Result<Vec<(UserDirKind, PathBuf)>> {
+    unblock(move || ensure_user_dirs(dry_run)).await
+}
+
+/// Runs `f` on tokio's blocking thread pool, so helpers doing several blocking steps share their sync version's code.
+///
+/// A panic in `f` is resumed in the caller, as if `f` had been called directly. If the caller holds the environment
+/// lock of a `test_util::EnvOverride`, `f` reads the environment on its behalf rather than waiting for the lock.
+#[cfg(feature = "tokio")]
+pub(crate) async fn unblock<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let holder = crate::env::holds_env_lock();
+    match tokio::task::spawn_blocking(move || crate::env::on_behalf_of(holder, f)).await {
+        Ok(value) => value,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+#[cfg(feature = "tokio")]
+async fn ensure_async(kind: DirKind) -> io::Result<PathBuf> {
+    let dir = target(kind)?;
+    tokio::fs::create_dir_all(&dir).await?;
+    Ok(dir)
+}
+
+/// Asynchronous version of [`ensure_cache_dir`] using [`tokio::fs`].
+#[cfg(feature = "tokio")]
+pub async fn ensure_cache_dir_async() -> io::Result<PathBuf> {
+    ensure_async(DirKind::Cache).await
+}
+
+/// Asynchronous version of [`ensure_config_dir`] using [`tokio::fs`].
+#[cfg(feature = "tokio")]
+pub async fn ensure_config_dir_async() -> io::Result<PathBuf> {
+    ensure_async(DirKind::Config).await
+}
+
+/// Asynchronous version of [`ensure_data_dir`] using [`tokio::fs`].
+#[cfg(feature = "tokio")]
+pub async fn ensure_data_dir_async() -> io::Result<PathBuf> {
+    ensure_async(DirKind::Data).await
+}
+
+/// Asynchronous version of [`ensure_state_dir`] using [`tokio::fs`].
+#[cfg(feature = "tokio")]
+pub async fn ensure_state_dir_async() -> io::Result<PathBuf> {
+    ensure_async(DirKind::State).await
+}
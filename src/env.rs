@@ -0,0 +1,458 @@
+//! Host-independent directory resolution.
+//!
+//! The top-level functions read the process environment and follow the conventions of the platform they are running
+//! on. [`dir_for`] performs the same resolution with every input explicit, so it can compute where a directory will be
+//! on another machine:
+//!
+//! ```rust
+//! use std::{collections::HashMap, path::Path};
+//!
+//! use user_dirs::{env::{dir_for, TargetOs}, DirKind};
+//!
+//! let env = HashMap::from([("APPDATA", r"C:\Users\Leah\AppData\Roaming")]);
+//! let config = dir_for(DirKind::Config, TargetOs::Windows, Path::new(r"C:\Users\Leah"), &env);
+//!
+//! assert_eq!(config.as_deref(), Some(Path::new(r"C:\Users\Leah\AppData\Roaming")));
+//! ```
+
+use std::{
+    borrow::Borrow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    env,
+    ffi::{OsStr, OsString},
+    hash::{BuildHasher, Hash},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{msys::MsysMounts, Candidate, CandidateSource, DirKind};
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<DirKind, PathBuf>> = RefCell::new(HashMap::new());
+    /// Whether this thread holds [`ENV_LOCK`], so its own reads don't wait for themselves.
+    static HOLDS_ENV_LOCK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Serializes reads of the process environment with the changes of `test_util::EnvOverride`, so no read observes
+/// another thread's override.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Holds [`ENV_LOCK`] for the current thread until dropped, see [`lock_env`].
+#[cfg(feature = "test-util")]
+#[derive(Debug)]
+pub(crate) struct EnvLock {
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(feature = "test-util")]
+impl Drop for EnvLock {
+    fn drop(&mut self) {
+        HOLDS_ENV_LOCK.with(|holds| holds.set(false));
+    }
+}
+
+/// Takes the environment lock, blocking reads on every other thread until the guard is dropped.
+#[cfg(feature = "test-util")]
+pub(crate) fn lock_env() -> EnvLock {
+    let lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    HOLDS_ENV_LOCK.with(|holds| holds.set(true));
+    EnvLock { _lock: lock }
+}
+
+/// Returns whether the current thread holds the environment lock, see [`on_behalf_of`].
+#[cfg(feature = "tokio")]
+pub(crate) fn holds_env_lock() -> bool {
+    HOLDS_ENV_LOCK.with(Cell::get)
+}
+
+/// Runs `f` as if the current thread held the environment lock if `holder` is set.
+///
+/// This is for work that a thread holding the lock hands to another thread and waits for, which would otherwise
+/// deadlock when reading the environment.
+#[cfg(feature = "tokio")]
+pub(crate) fn on_behalf_of<T>(holder: bool, f: impl FnOnce() -> T) -> T {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            HOLDS_ENV_LOCK.with(|holds| holds.set(false));
+        }
+    }
+
+    if !holder {
+        return f();
+    }
+    HOLDS_ENV_LOCK.with(|holds| holds.set(true));
+    let _reset = Reset;
+    f()
+}
+
+/// Runs `f`, which reads the process environment, with the environment lock, unless this thread already holds it.
+pub(crate) fn with_env_lock<T>(f: impl FnOnce() -> T) -> T {
+    if HOLDS_ENV_LOCK.with(Cell::get) {
+        return f();
+    }
+    let _lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    f()
+}
+
+/// Overrides the directory of the given kind for the current thread, without changing the process environment.
+///
+/// Until [`clear_override`] is called, the top-level functions such as [`config_dir`](crate::config_dir) and
+/// [`UserDirs`](crate::UserDirs) snapshots built on this thread return `path` as is. Overriding [`DirKind::Home`] also
+/// moves the default directories derived from it. This is meant for tests, which run on separate threads and can't
+/// safely call [`std::env::set_var`]; resolution with [`dir_for`] is not affected.
+pub fn set_override(kind: DirKind, path: PathBuf) {
+    OVERRIDES.with(|overrides| overrides.borrow_mut().insert(kind, path));
+}
+
+/// Removes the override set with [`set_override`] for the given kind on the current thread.
+pub fn clear_override(kind: DirKind) {
+    OVERRIDES.with(|overrides| overrides.borrow_mut().remove(&kind));
+}
+
+/// Returns every override set on the current thread, so they can be carried over to another thread.
+#[cfg(feature = "watch")]
+pub(crate) fn overrides() -> HashMap<DirKind, PathBuf> {
+    OVERRIDES.with(|overrides| overrides.borrow().clone())
+}
+
+/// Returns the directory of the given kind overridden on the current thread, if any.
+pub(crate) fn overridden(kind: DirKind) -> Option<PathBuf> {
+    OVERRIDES.with(|overrides| overrides.borrow().get(&kind).cloned())
+}
+
+/// A source of environment variables.
+pub trait Env {
+    /// Returns the value of the environment variable `key`, if it is set.
+    fn var_os(&self, key: &str) -> Option<OsString>;
+}
+
+/// The environment of the current process.
+///
+/// Reads wait while an `test_util::EnvOverride` is held on another thread.
+///
+/// With the `registry` feature on Windows, an unset `APPDATA` is read from the registry's `User Shell Folders` key,
+/// since services can run without a user environment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+impl Env for ProcessEnv {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        #[cfg(all(windows, feature = "registry"))]
+        if key == "APPDATA" {
+            return with_env_lock(|| env::var_os(key)).or_else(|| {
+                crate::registry::shell_folder("AppData").map(std::path::PathBuf::into_os_string)
+            });
+        }
+
+        with_env_lock(|| env::var_os(key))
+    }
+}
+
+impl<K, V, S> Env for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsRef<OsStr>,
+    S: BuildHasher,
+{
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self.get(key).map(|value| value.as_ref().to_owned())
+    }
+}
+
+impl<E: Env + ?Sized> Env for &E {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        (**self).var_os(key)
+    }
+}
+
+/// The operating systems whose conventions can be followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TargetOs {
+    /// Linux, following the XDG Base Directory specification.
+    Linux,
+    /// macOS.
+    MacOs,
+    /// Windows.
+    Windows,
+    /// Fuchsia, whose components have no home directory but `/data` and `/cache` in their namespace.
+    ///
+    /// The data directory is `/data`, the config directory `/data/config`, the state directory `/data/state` and the
+    /// cache directory `/cache`. XDG variables provided in the component's environment still take precedence.
+    Fuchsia,
+    /// Any other system, which follows the XDG Base Directory specification like Linux.
+    Other,
+}
+
+impl TargetOs {
+    /// Returns the operating system this program is running on.
+    #[must_use]
+    pub fn current() -> TargetOs {
+        match env::consts::OS {
+            "linux" => Self::Linux,
+            "macos" => Self::MacOs,
+            "windows" => Self::Windows,
+            "fuchsia" => Self::Fuchsia,
+            _ => Self::Other,
+        }
+    }
+
+    fn separator(self) -> char {
+        match self {
+            Self::Windows => '\\',
+            _ => '/',
+        }
+    }
+}
+
+/// Returns the path to the directory of the given kind on `os`, if available.
+///
+/// This follows the same rules as the top-level functions, but reads variables from `env` instead of the process
+/// environment and never inspects the host. Paths are joined with the separator of `os`, and XDG variables are only
+/// used if they are absolute paths on `os`.
+#[must_use]
+pub fn dir_for(kind: DirKind, os: TargetOs, home: &Path, env: &impl Env) -> Option<PathBuf> {
+    xdg_dir(kind, os, env).or_else(|| os_dir(kind, os, home, env))
+}
+
+/// Returns every place the directory of the given kind could be on `os`, with the one [`dir_for`] returns first.
+///
+/// This is the host-independent version of [`config_dir_candidates`](crate::config_dir_candidates).
+#[must_use]
+pub fn candidates_for(kind: DirKind, os: TargetOs, home: &Path, env: &impl Env) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    let mut push = |path: PathBuf, source| {
+        if !candidates
+            .iter()
+            .any(|candidate: &Candidate| candidate.path() == path)
+        {
+            candidates.push(Candidate::new(path, source));
+        }
+    };
+
+    if let Some(dir) = xdg_dir(kind, os, env) {
+        push(dir, CandidateSource::Xdg);
+    }
+    if let Some(dir) = os_dir(kind, os, home, env) {
+        push(dir, CandidateSource::PlatformDefault);
+    }
+
+    let xdg_default: &[&str] = match kind {
+        DirKind::Cache => &[".cache"],
+        DirKind::Config => &[".config"],
+        DirKind::Data => &[".local", "share"],
+        DirKind::State => &[".local", "state"],
+        DirKind::Home | DirKind::Runtime => &[],
+    };
+    if !xdg_default.is_empty() && os != TargetOs::Fuchsia {
+        push(join(os, home, xdg_default), CandidateSource::XdgDefault);
+    }
+
+    match (os, kind) {
+        (TargetOs::MacOs, DirKind::Config) => push(
+            join(os, home, &["Library", "Application Support"]),
+            CandidateSource::Alternative,
+        ),
+        (TargetOs::Windows, DirKind::Config | DirKind::Data) => {
+            if let Some(dir) = var(os, env, "LOCALAPPDATA") {
+                push(PathBuf::from(dir), CandidateSource::EnvVar("LOCALAPPDATA"));
+            }
+            push(
+                join(os, home, &["AppData", "Roaming"]),
+                CandidateSource::Alternative,
+            );
+        }
+        _ => {}
+    }
+
+    candidates
+}
+
+/// Returns the platform's default for the given kind, ignoring XDG variables.
+pub(crate) fn os_dir(kind: DirKind, os: TargetOs, home: &Path, env: &impl Env) -> Option<PathBuf> {
+    match kind {
+        DirKind::Home => Some(home.to_path_buf()),
+        DirKind::Cache => Some(os_cache_dir(os, home, env)),
+        DirKind::Config => Some(os_config_dir(os, home, env)),
+        DirKind::Data => Some(os_data_dir(os, home, env)),
+        DirKind::Runtime => None,
+        DirKind::State => os_state_dir(os, home),
+    }
+}
+
+/// Returns the directory set by the XDG variable for the given kind, if any.
+///
+/// As the XDG Base Directory specification requires, a value that is not an absolute path on `os` is ignored.
+pub(crate) fn xdg_dir(kind: DirKind, os: TargetOs, env: &impl Env) -> Option<PathBuf> {
+    var(os, env, xdg_var(kind)?)
+        .filter(|value| is_absolute(os, value))
+        .map(PathBuf::from)
+}
+
+/// Returns the name of the XDG variable for the given kind, if there is one.
+pub(crate) fn xdg_var(kind: DirKind) -> Option<&'static str> {
+    Some(match kind {
+        DirKind::Home => return None,
+        DirKind::Cache => "XDG_CACHE_HOME",
+        DirKind::Config => "XDG_CONFIG_HOME",
+        DirKind::Data => "XDG_DATA_HOME",
+        DirKind::Runtime => "XDG_RUNTIME_DIR",
+        DirKind::State => "XDG_STATE_HOME",
+    })
+}
+
+/// Returns whether `path` is absolute on `os`, regardless of the host.
+pub(crate) fn is_absolute(os: TargetOs, path: &OsStr) -> bool {
+    let path = path.as_encoded_bytes();
+    let is_separator = |c: &u8| *c == b'/' || *c == b'\\';
+
+    match os {
+        TargetOs::Windows => match path {
+            [drive, b':', separator, ..] => drive.is_ascii_alphabetic() && is_separator(separator),
+            [first, second, ..] => is_separator(first) && is_separator(second),
+            _ => false,
+        },
+        _ => path.starts_with(b"/"),
+    }
+}
+
+pub(crate) fn os_data_dir(os: TargetOs, home: &Path, env: &impl Env) -> PathBuf {
+    match os {
+        TargetOs::MacOs => join(os, home, &["Library", "Application Support"]),
+        TargetOs::Windows => roaming_app_data(home, env),
+        TargetOs::Fuchsia => PathBuf::from("/data"),
+        _ => join(os, home, &[".local", "share"]),
+    }
+}
+
+pub(crate) fn os_config_dir(os: TargetOs, home: &Path, env: &impl Env) -> PathBuf {
+    match os {
+        TargetOs::MacOs => join(os, home, &["Library", "Preferences"]),
+        TargetOs::Windows => roaming_app_data(home, env),
+        TargetOs::Fuchsia => PathBuf::from("/data/config"),
+        _ => join(os, home, &[".config"]),
+    }
+}
+
+pub(crate) fn os_cache_dir(os: TargetOs, home: &Path, env: &impl Env) -> PathBuf {
+    match os {
+        TargetOs::MacOs => join(os, home, &["Library", "Caches"]),
+        TargetOs::Windows => var(os, env, "LOCALAPPDATA")
+            .map_or_else(|| join(os, home, &["AppData", "Local"]), PathBuf::from),
+        TargetOs::Fuchsia => PathBuf::from("/cache"),
+        _ => join(os, home, &[".cache"]),
+    }
+}
+
+pub(crate) fn os_state_dir(os: TargetOs, home: &Path) -> Option<PathBuf> {
+    match os {
+        TargetOs::MacOs | TargetOs::Windows => None, // No state directory on macOS or Windows by default.
+        TargetOs::Fuchsia => Some(PathBuf::from("/data/state")),
+        _ => Some(join(os, home, &[".local", "state"])),
+    }
+}
+
+pub(crate) fn roaming_app_data(home: &Path, env: &impl Env) -> PathBuf {
+    var(TargetOs::Windows, env, "APPDATA").map_or_else(
+        || join(TargetOs::Windows, home, &["AppData", "Roaming"]),
+        PathBuf::from,
+    )
+}
+
+/// Expands `%VAR%` references in `value` from `env`, as Windows does for `REG_EXPAND_SZ` values.
+///
+/// Windows values often refer to other variables, such as `MYAPP_DATA_DIR=%LOCALAPPDATA%\MyApp`. Variables that are
+/// not set in `env` are left as written, and values are only expanded once, so `%` in an expanded value is kept.
+/// Values that are not valid Unicode are returned unchanged.
+///
+/// ```rust
+/// use std::{collections::HashMap, ffi::OsStr};
+///
+/// use user_dirs::env::expand_windows_vars;
+///
+/// let env = HashMap::from([("USERPROFILE", r"C:\Users\Leah")]);
+/// let expanded = expand_windows_vars(OsStr::new(r"%USERPROFILE%\AppData\%UNSET%"), &env);
+///
+/// assert_eq!(expanded, r"C:\Users\Leah\AppData\%UNSET%");
+/// ```
+#[must_use]
+pub fn expand_windows_vars(value: &OsStr, env: &impl Env) -> OsString {
+    let Some(mut rest) = value.to_str() else {
+        return value.to_owned();
+    };
+
+    let mut expanded = OsString::new();
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            break;
+        };
+
+        expanded.push(&rest[..start]);
+        let name = &after[..end];
+        match Some(name)
+            .filter(|name| !name.is_empty())
+            .and_then(|name| env.var_os(name))
+        {
+            Some(value) => expanded.push(value),
+            None => expanded.push(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push(rest);
+
+    expanded
+}
+
+/// Reads the variable `key` from `env`, in the path form of `os`.
+///
+/// On Windows, `%VAR%` references are expanded. Under MSYS2 or Cygwin, values in the other form are converted, see
+/// the [`msys`](crate::msys) module.
+pub(crate) fn var(os: TargetOs, env: &impl Env, key: &str) -> Option<OsString> {
+    let value = env.var_os(key)?;
+    let value = match os {
+        TargetOs::Windows => expand_windows_vars(&value, env),
+        _ => value,
+    };
+
+    // Only look for MSYS2 when the value is in the other form, so the environment is left alone otherwise.
+    let windows =
+        is_absolute(TargetOs::Windows, &value) && !value.as_encoded_bytes().starts_with(b"/");
+    let posix =
+        value.as_encoded_bytes().starts_with(b"/") && !is_absolute(TargetOs::Windows, &value);
+    if (os == TargetOs::Windows && posix) || (os != TargetOs::Windows && windows) {
+        let converted = MsysMounts::detect(env).and_then(|mounts| {
+            let value = value.to_str()?;
+            if posix {
+                mounts.to_windows_form(value)
+            } else {
+                mounts.to_posix_form(value)
+            }
+        });
+        if let Some(converted) = converted {
+            return Some(converted.into());
+        }
+    }
+    Some(value)
+}
+
+/// Joins `parts` onto `base` with the separator of `os`, regardless of the host's separator.
+pub(crate) fn join(os: TargetOs, base: &Path, parts: &[&str]) -> PathBuf {
+    let separator = os.separator();
+    let mut path = base.as_os_str().to_owned();
+
+    for part in parts {
+        let ends_with_separator = path
+            .as_encoded_bytes()
+            .last()
+            .is_some_and(|&last| last == b'/' || last == separator as u8);
+        if !ends_with_separator {
+            path.push(separator.encode_utf8(&mut [0; 1]));
+        }
+        path.push(part);
+    }
+
+    PathBuf::from(path)
+}
@@ -0,0 +1,302 @@
+use std::{
+    cell::RefCell,
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app,
+    env::{self, Env, ProcessEnv, TargetOs},
+    home_dir_with, DirKind, HomeDirError, HOME_VAR,
+};
+
+/// A trace of how a directory was resolved, see [`explain`].
+///
+/// Its [`Display`](fmt::Display) implementation prints a multi-line report suitable for bug reports. With the
+/// alternate flag (`{:#}`), the home directory is replaced by `~` in every path to avoid leaking the user's name.
+#[derive(Debug)]
+pub struct Explanation {
+    kind: DirKind,
+    steps: Vec<Step>,
+    result: Result<Option<PathBuf>, HomeDirError>,
+}
+
+impl Explanation {
+    /// Returns the kind of directory that was resolved.
+    #[must_use]
+    pub fn kind(&self) -> DirKind {
+        self.kind
+    }
+
+    /// Returns every step taken, in order.
+    #[must_use]
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the resolved directory, as the corresponding function would.
+    pub fn result(&self) -> Result<Option<&Path>, HomeDirError> {
+        match &self.result {
+            Ok(path) => Ok(path.as_deref()),
+            Err(err) => Err(*err),
+        }
+    }
+
+    fn home(&self) -> Option<&Path> {
+        self.steps.iter().find_map(|step| match step {
+            Step::Home { path } => path.as_deref(),
+            _ => None,
+        })
+    }
+}
+
+/// A single step in resolving a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Step {
+    /// An environment variable was consulted.
+    Var {
+        /// The name of the variable.
+        name: String,
+        /// The value of the variable, if set.
+        value: Option<OsString>,
+        /// What was made of the value.
+        outcome: VarOutcome,
+    },
+    /// The directory was overridden on the current thread with [`set_override`](crate::env::set_override).
+    Override {
+        /// The kind of directory that was overridden, which is the home directory when a default was derived from it.
+        kind: DirKind,
+        /// The overriding path.
+        path: PathBuf,
+    },
+    /// The home directory was located, or could not be.
+    Home {
+        /// The path to the home directory, if found.
+        path: Option<PathBuf>,
+    },
+    /// The platform's default location was used.
+    Default {
+        /// The default location.
+        path: PathBuf,
+    },
+}
+
+/// What was made of an environment variable while resolving a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VarOutcome {
+    /// The variable is not set.
+    Unset,
+    /// The variable's value was used.
+    Used,
+    /// The variable is set to an empty value, so it was ignored.
+    Empty,
+    /// The variable is set to a relative path, so it was ignored.
+    Relative,
+    /// The variable is set, but its value was not used.
+    Ignored,
+}
+
+/// Resolves the directory of the given kind, recording every step taken.
+///
+/// ```rust
+/// let explanation = user_dirs::explain(user_dirs::DirKind::Config);
+/// eprintln!("{explanation:#}");
+/// ```
+#[must_use]
+pub fn explain(kind: DirKind) -> Explanation {
+    explain_with(kind, &TracingEnv::default(), Vec::new())
+}
+
+/// Explains an application's directory of the given kind, which is `dir` unless the variable `var` overrides it.
+pub(crate) fn explain_app(kind: DirKind, var: Option<&str>, dir: Option<PathBuf>) -> Explanation {
+    let env = TracingEnv {
+        app_var: var.map(str::to_owned),
+        ..TracingEnv::default()
+    };
+    let mut steps = Vec::new();
+    if let Some(var) = var {
+        let value = app::env_dir(var, &env);
+        steps.extend(env.take(value.as_deref()));
+        if value.is_some() {
+            return Explanation {
+                kind,
+                steps,
+                result: Ok(dir),
+            };
+        }
+    }
+
+    let base = explain_with(kind, &env, steps);
+    Explanation {
+        result: base.result.map(|_| dir),
+        ..base
+    }
+}
+
+/// Resolves the directory of the given kind with `env`, recording every step taken after `steps`.
+fn explain_with(kind: DirKind, env: &TracingEnv, mut steps: Vec<Step>) -> Explanation {
+    if let Some(path) = env::overridden(kind) {
+        steps.push(Step::Override {
+            kind,
+            path: path.clone(),
+        });
+        return Explanation {
+            kind,
+            steps,
+            result: Ok(Some(path)),
+        };
+    }
+
+    let result = match env::xdg_dir(kind, TargetOs::current(), env) {
+        Some(dir) => Ok(Some(dir)),
+        None if kind == DirKind::Runtime => Ok(None),
+        None => {
+            steps.extend(env.take(None));
+            let home = home_dir_with(env);
+            steps.extend(env.take(home.as_deref().ok()));
+            if let Some(path) = env::overridden(DirKind::Home) {
+                steps.push(Step::Override {
+                    kind: DirKind::Home,
+                    path,
+                });
+            }
+            steps.push(Step::Home {
+                path: home.as_ref().ok().cloned(),
+            });
+
+            home.map(|home| env::os_dir(kind, TargetOs::current(), &home, env))
+        }
+    };
+
+    let result_path = result.as_ref().ok().and_then(Option::as_deref);
+    let vars = env.take(result_path);
+    let used_var = vars.iter().any(|step| {
+        matches!(
+            step,
+            Step::Var {
+                outcome: VarOutcome::Used,
+                ..
+            }
+        )
+    });
+    steps.extend(vars);
+
+    if let (Some(path), false, false) = (result_path, used_var, kind == DirKind::Home) {
+        steps.push(Step::Default {
+            path: path.to_path_buf(),
+        });
+    }
+
+    Explanation {
+        kind,
+        steps,
+        result,
+    }
+}
+
+/// An [`Env`] reading the process environment while recording every lookup.
+#[derive(Default)]
+struct TracingEnv {
+    lookups: RefCell<Vec<(String, Option<OsString>)>>,
+    /// The application's variable, which must be an absolute path like the XDG variables.
+    app_var: Option<String>,
+}
+
+impl TracingEnv {
+    /// Takes the lookups made so far as steps, marking the one whose value is `result` as used.
+    fn take(&self, result: Option<&Path>) -> Vec<Step> {
+        let mut used = false;
+        self.lookups
+            .take()
+            .into_iter()
+            .map(|(name, value)| {
+                let outcome = match &value {
+                    None => VarOutcome::Unset,
+                    Some(value) if !used && result == Some(Path::new(value)) => {
+                        used = true;
+                        VarOutcome::Used
+                    }
+                    Some(value) if value.is_empty() => VarOutcome::Empty,
+                    Some(value)
+                        if (name.starts_with("XDG_")
+                            || name == HOME_VAR
+                            || self.app_var.as_ref() == Some(&name))
+                            && !Path::new(value).is_absolute() =>
+                    {
+                        VarOutcome::Relative
+                    }
+                    Some(_) => VarOutcome::Ignored,
+                };
+                Step::Var {
+                    name,
+                    value,
+                    outcome,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Env for TracingEnv {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        let value = ProcessEnv.var_os(key);
+        self.lookups
+            .borrow_mut()
+            .push((key.to_owned(), value.clone()));
+        value
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let home = if f.alternate() { self.home() } else { None };
+        let show = |path: &Path| match home.and_then(|home| path.strip_prefix(home).ok()) {
+            Some(rest) if rest.as_os_str().is_empty() => "~".to_owned(),
+            Some(rest) => Path::new("~").join(rest).display().to_string(),
+            None => path.display().to_string(),
+        };
+
+        writeln!(f, "{} directory:", self.kind)?;
+        for step in &self.steps {
+            match step {
+                Step::Var {
+                    name, value: None, ..
+                } => writeln!(f, "  ${name} is unset"),
+                Step::Var {
+                    name,
+                    value: Some(value),
+                    outcome,
+                } => {
+                    let verdict = match outcome {
+                        VarOutcome::Used => "used",
+                        VarOutcome::Empty => "ignored: empty",
+                        VarOutcome::Relative => "ignored: not an absolute path",
+                        VarOutcome::Unset | VarOutcome::Ignored => "ignored",
+                    };
+                    writeln!(f, "  ${name} = {} ({verdict})", show(Path::new(value)))
+                }
+                Step::Override { kind, path } => {
+                    writeln!(
+                        f,
+                        "  {kind} directory is overridden on this thread to {}",
+                        show(path)
+                    )
+                }
+                Step::Home { path: Some(path) } => {
+                    writeln!(f, "  home directory is {}", show(path))
+                }
+                Step::Home { path: None } => writeln!(f, "  home directory could not be located"),
+                Step::Default { path } => writeln!(f, "  platform default is {}", show(path)),
+            }?;
+        }
+
+        match &self.result {
+            Ok(Some(path)) => write!(f, "  => {}", show(path)),
+            Ok(None) => write!(f, "  => unavailable on this platform"),
+            Err(err) => write!(f, "  => error: {err}"),
+        }
+    }
+}
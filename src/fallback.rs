@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use crate::{cache_dir, config_dir, data_dir, runtime, state_dir, DirKind, HomeDirError};
+
+/// How far a `*_or_default` function had to fall back, from best to worst.
+///
+/// The chain is fixed, so an application can tell its users exactly where a file went:
+///
+/// 1. [`DirFallback::Resolved`]: the directory the corresponding function, such as [`cache_dir`], returns.
+/// 2. [`DirFallback::TempDir`]: `<kind>` in the per-user subdirectory of the temporary directory,
+///    `<temp_dir>/user-dirs-<uid>/<kind>` on Unix and `<temp_dir>\user-dirs\<kind>` on Windows, where `<kind>` is
+///    `cache`, `config`, `data` or `state`. On macOS, the per-user temporary directory is used instead, as
+///    `<darwin_user_temp_dir>/user-dirs/<kind>`. This is skipped if the temporary directory is not an absolute path.
+/// 3. [`DirFallback::CurrentDir`]: the relative path `.`, so the current directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DirFallback {
+    /// The directory was resolved as usual.
+    Resolved,
+    /// The directory could not be resolved, so one in the temporary directory was used.
+    TempDir,
+    /// Neither the directory nor the temporary directory are available, so the current directory was used.
+    CurrentDir,
+}
+
+/// Returns the path to the cache directory, falling back as [`DirFallback`] describes instead of failing.
+///
+/// This is meant for when some directory is needed no matter what, such as for a crash dump written by a panic hook.
+///
+/// ```rust
+/// let (dir, fallback) = user_dirs::cache_dir_or_default();
+/// if fallback != user_dirs::DirFallback::Resolved {
+///     eprintln!("the cache directory is unavailable, using {}", dir.display());
+/// }
+/// ```
+#[must_use]
+pub fn cache_dir_or_default() -> (PathBuf, DirFallback) {
+    or_default(DirKind::Cache, cache_dir().map(Some))
+}
+
+/// Returns the path to the config directory, falling back as [`DirFallback`] describes instead of failing.
+#[must_use]
+pub fn config_dir_or_default() -> (PathBuf, DirFallback) {
+    or_default(DirKind::Config, config_dir().map(Some))
+}
+
+/// Returns the path to the data directory, falling back as [`DirFallback`] describes instead of failing.
+#[must_use]
+pub fn data_dir_or_default() -> (PathBuf, DirFallback) {
+    or_default(DirKind::Data, data_dir().map(Some))
+}
+
+/// Returns the path to the state directory, falling back as [`DirFallback`] describes instead of failing.
+///
+/// On platforms without a state directory, such as macOS and Windows without `$XDG_STATE_HOME`, the [`data_dir`] is
+/// resolved instead, which still counts as [`DirFallback::Resolved`].
+#[must_use]
+pub fn state_dir_or_default() -> (PathBuf, DirFallback) {
+    let state = match state_dir() {
+        Ok(None) => data_dir().map(Some),
+        state => state,
+    };
+    or_default(DirKind::State, state)
+}
+
+/// Returns the resolved directory, or falls back to the temporary or the current directory.
+fn or_default(
+    kind: DirKind,
+    resolved: Result<Option<PathBuf>, HomeDirError>,
+) -> (PathBuf, DirFallback) {
+    if let Ok(Some(dir)) = resolved {
+        return (dir, DirFallback::Resolved);
+    }
+
+    let temp = runtime::tmp_app_dir("user-dirs");
+    if temp.is_absolute() {
+        (temp.join(kind.to_string()), DirFallback::TempDir)
+    } else {
+        (PathBuf::from("."), DirFallback::CurrentDir)
+    }
+}
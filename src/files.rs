@@ -0,0 +1,174 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    app::app_dir_name, find_all_config_files, runtime, unicode_path, DirKind, UserDirsError,
+};
+
+/// Returns the path to the file `filename` in the config directory, such as `~/.config/myapp.toml`.
+///
+/// `filename` must be a single path component; anything containing `/` or `\` is rejected with
+/// [`UserDirsError::InvalidFileName`].
+pub fn config_file(filename: &str) -> Result<PathBuf, UserDirsError> {
+    file(DirKind::Config, filename)
+}
+
+/// Returns the path to the file `filename` in the cache directory, see [`config_file`].
+pub fn cache_file(filename: &str) -> Result<PathBuf, UserDirsError> {
+    file(DirKind::Cache, filename)
+}
+
+/// Returns the path to the file `filename` in the data directory, see [`config_file`].
+pub fn data_file(filename: &str) -> Result<PathBuf, UserDirsError> {
+    file(DirKind::Data, filename)
+}
+
+/// Returns the path to the file `filename` in the state directory, see [`config_file`].
+///
+/// This fails with [`UserDirsError::Unavailable`] if there is no state directory on this platform.
+pub fn state_file(filename: &str) -> Result<PathBuf, UserDirsError> {
+    file(DirKind::State, filename)
+}
+
+/// Returns the path to `relative` within the config directory, such as `~/.config/myapp/config.toml`.
+///
+/// This is the same as [`config_path`], which has counterparts for the other directories. Unlike [`Path::join`], it
+/// never escapes the config directory, which makes it safe to use with paths from untrusted input.
+pub fn config_dir_relative(relative: impl AsRef<Path>) -> Result<PathBuf, UserDirsError> {
+    config_path(relative)
+}
+
+/// Returns the path to `relative` within the config directory, such as `~/.config/myapp/plugins/foo.toml`.
+///
+/// The result is guaranteed to be inside the config directory lexically: `relative` is rejected with
+/// [`UserDirsError::InvalidRelativePath`] if it is empty, absolute, has a root or a drive or verbatim prefix (including
+/// `C:relative`), contains a `..` component, or contains a NUL byte. Both `/` and `\` count as separators on every
+/// platform. The file system is not touched.
+pub fn config_path(relative: impl AsRef<Path>) -> Result<PathBuf, UserDirsError> {
+    join_relative(DirKind::Config, relative.as_ref())
+}
+
+/// Returns the path to `relative` within the data directory, see [`config_path`].
+pub fn data_path(relative: impl AsRef<Path>) -> Result<PathBuf, UserDirsError> {
+    join_relative(DirKind::Data, relative.as_ref())
+}
+
+/// Returns the path to `relative` within the cache directory, see [`config_path`].
+pub fn cache_path(relative: impl AsRef<Path>) -> Result<PathBuf, UserDirsError> {
+    join_relative(DirKind::Cache, relative.as_ref())
+}
+
+/// Returns the path to `relative` within the state directory, see [`config_path`].
+///
+/// This fails with [`UserDirsError::Unavailable`] if there is no state directory on this platform.
+pub fn state_path(relative: impl AsRef<Path>) -> Result<PathBuf, UserDirsError> {
+    join_relative(DirKind::State, relative.as_ref())
+}
+
+fn join_relative(kind: DirKind, relative: &Path) -> Result<PathBuf, UserDirsError> {
+    if relative.as_os_str().is_empty() {
+        return Err(UserDirsError::InvalidRelativePath(relative.to_path_buf()));
+    }
+    check_relative(relative)?;
+
+    let dir = kind.resolve()?.ok_or(UserDirsError::Unavailable(kind))?;
+    unicode_path(dir.join(relative))
+}
+
+/// Checks that joining `relative` onto a directory stays inside it, whatever the platform's path syntax.
+fn check_relative(relative: &Path) -> Result<(), UserDirsError> {
+    let bytes = relative.as_os_str().as_encoded_bytes();
+    let mut segments = bytes.split(|&c| c == b'/' || c == b'\\');
+    let has_drive = matches!(bytes, [drive, b':', ..] if drive.is_ascii_alphabetic());
+
+    let contained = !has_drive
+        && !bytes.starts_with(b"/")
+        && !bytes.starts_with(b"\\")
+        && !bytes.contains(&0)
+        && segments.all(|segment| segment != b"..")
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if contained {
+        Ok(())
+    } else {
+        Err(UserDirsError::InvalidRelativePath(relative.to_path_buf()))
+    }
+}
+
+fn file(kind: DirKind, filename: &str) -> Result<PathBuf, UserDirsError> {
+    if !runtime::is_valid_component(filename) {
+        return Err(UserDirsError::InvalidFileName(filename.to_owned()));
+    }
+
+    let dir = kind.resolve()?.ok_or(UserDirsError::Unavailable(kind))?;
+    unicode_path(dir.join(filename))
+}
+
+/// An application's config file, located through the config search path.
+///
+/// The file is looked for at `<app>/<file_name>` in the user's config directory first, then in each system config
+/// directory, see [`find_all_config_files`]. The application's name is normalized like for [`AppDirs`](crate::AppDirs).
+///
+/// With the `figment` feature, this is a [`figment::Provider`] which loads the file with the highest precedence as
+/// TOML, and contributes nothing if there is no such file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserConfigFile {
+    relative: PathBuf,
+}
+
+impl UserConfigFile {
+    /// Describes the config file `file_name` of the application `app_name`.
+    ///
+    /// This fails with [`UserDirsError::InvalidAppName`] or [`UserDirsError::InvalidFileName`] if either is unusable.
+    pub fn new(app_name: &str, file_name: &str) -> Result<UserConfigFile, UserDirsError> {
+        let app = app_dir_name(app_name)?;
+        if !runtime::is_valid_component(file_name) {
+            return Err(UserDirsError::InvalidFileName(file_name.to_owned()));
+        }
+
+        Ok(UserConfigFile {
+            relative: PathBuf::from(app).join(file_name),
+        })
+    }
+
+    /// Returns the path to the file, relative to a config directory.
+    #[must_use]
+    pub fn relative_path(&self) -> &Path {
+        &self.relative
+    }
+
+    /// Returns the path to the existing file with the highest precedence, if any.
+    #[must_use]
+    pub fn path(&self) -> Option<PathBuf> {
+        crate::config_files(&self.relative).next()
+    }
+
+    /// Returns the paths to every existing copy of the file, highest precedence first.
+    #[must_use]
+    pub fn paths(&self) -> Vec<PathBuf> {
+        find_all_config_files(&self.relative)
+    }
+}
+
+#[cfg(feature = "figment")]
+impl figment::Provider for UserConfigFile {
+    fn metadata(&self) -> figment::Metadata {
+        use figment::providers::{Format, Toml};
+
+        match self.path() {
+            Some(path) => Toml::file(path).metadata(),
+            None => figment::Metadata::named("user config file"),
+        }
+    }
+
+    fn data(
+        &self,
+    ) -> Result<figment::value::Map<figment::Profile, figment::value::Dict>, figment::Error> {
+        use figment::providers::{Format, Toml};
+
+        match self.path() {
+            Some(path) => Toml::file(path).data(),
+            None => Ok(figment::value::Map::new()),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use crate::{
+    cache_dir,
+    env::{Env, ProcessEnv},
+    state_dir, HomeDirError,
+};
+
+/// The variable setting a stable host identifier instead of the hostname, see [`host_id`].
+const HOST_ID_VAR: &str = "USER_DIRS_HOST_ID";
+
+/// The name of the directory the per-host directories are kept in, see [`state_dir_per_host`].
+const HOSTS_DIR: &str = "hosts";
+
+/// Returns the identifier of this machine used by [`state_dir_per_host`] and [`cache_dir_per_host`].
+///
+/// This is `$USER_DIRS_HOST_ID` if it is set, for users who want an identifier that survives renaming the machine, and
+/// the hostname otherwise (`%COMPUTERNAME%` on Windows). Either way, it is lowercased and every character other than
+/// ASCII letters, digits, `-`, `_` and `.` is replaced by `_`, and leading and trailing dots are removed, so
+/// `My-PC.local` becomes `my-pc.local`. This returns `None` if nothing is left.
+#[must_use]
+pub fn host_id() -> Option<String> {
+    let id = ProcessEnv
+        .var_os(HOST_ID_VAR)
+        .filter(|id| !id.is_empty())
+        .or_else(hostname)?;
+    let id: String = id
+        .to_string_lossy()
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '-' | '_' | '.') => c,
+            _ => '_',
+        })
+        .collect();
+
+    let id = id.trim_matches('.');
+    (!id.is_empty()).then(|| id.to_owned())
+}
+
+/// Returns the path to this machine's own state directory, `<state_dir>/hosts/<host_id>`, if available.
+///
+/// When the home directory is shared between machines, such as over NFS, state that only makes sense on one machine
+/// (window positions, device caches) must be kept apart. The directory is named after [`host_id`], or `unknown` if
+/// there is none, so `ls <state_dir>/hosts` lists every machine that wrote state.
+pub fn state_dir_per_host() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(state_dir()?.map(per_host))
+}
+
+/// Returns the path to this machine's own cache directory, `<cache_dir>/hosts/<host_id>`.
+///
+/// See [`state_dir_per_host`].
+pub fn cache_dir_per_host() -> Result<PathBuf, HomeDirError> {
+    Ok(per_host(cache_dir()?))
+}
+
+/// Returns this machine's own subdirectory of `dir`.
+fn per_host(mut dir: PathBuf) -> PathBuf {
+    dir.push(HOSTS_DIR);
+    dir.push(host_id().as_deref().unwrap_or("unknown"));
+    dir
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<std::ffi::OsString> {
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut buf = vec![0u8; 256];
+    // SAFETY: the buffer is valid for writes of its length.
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&c| c == 0)?;
+    buf.truncate(len);
+    Some(std::ffi::OsString::from_vec(buf))
+}
+
+#[cfg(windows)]
+fn hostname() -> Option<std::ffi::OsString> {
+    ProcessEnv
+        .var_os("COMPUTERNAME")
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn hostname() -> Option<std::ffi::OsString> {
+    None
+}
@@ -0,0 +1,192 @@
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    env::{is_absolute, var, xdg_dir, Env, ProcessEnv, TargetOs},
+    home_dir, runtime, DirKind, HomeDirError,
+};
+
+/// Returns the path to the directory for user-installed executables, `$XDG_BIN_HOME` or `~/.local/bin`.
+///
+/// `$XDG_BIN_HOME` is used if it is set to an absolute path. `~/.local/bin` is on `$PATH` by default on many Linux
+/// distributions, and is used on every platform like other user-level installers do, including Windows as
+/// `%USERPROFILE%\.local\bin`. Elsewhere, users may need to add it, see [`is_executable_dir_on_path`].
+pub fn executable_dir() -> Result<PathBuf, HomeDirError> {
+    let os = TargetOs::current();
+    match var(os, &ProcessEnv, "XDG_BIN_HOME").filter(|dir| is_absolute(os, dir)) {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(home_dir()?.join(".local").join("bin")),
+    }
+}
+
+/// Returns whether the [`executable_dir`] is in `$PATH`, so installed executables can be run by name.
+///
+/// Trailing separators are ignored, and on Windows so are case and the difference between `/` and `\`.
+#[must_use]
+pub fn is_executable_dir_on_path() -> bool {
+    let (Ok(dir), Some(path)) = (executable_dir(), ProcessEnv.var_os("PATH")) else {
+        return false;
+    };
+    let dir = path_key(&dir);
+    env::split_paths(&path).any(|entry| path_key(&entry) == dir)
+}
+
+/// Returns `path` in a form that compares equal for every spelling of the same `$PATH` entry.
+fn path_key(path: &Path) -> String {
+    let key = path.to_string_lossy();
+    if cfg!(windows) {
+        key.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+    } else {
+        key.trim_end_matches('/').to_owned()
+    }
+}
+
+/// Installs a copy of the executable `src` as `name` in the [`executable_dir`], returning its path.
+///
+/// The directory is created if needed, and `.exe` is appended to `name` on Windows unless it already ends with it.
+/// The file is copied next to its destination first and then renamed over it, so a running program is never
+/// half-overwritten. On Unix, the installed file's mode is `0755`. On Windows, where a running program can't be
+/// replaced, an existing file is first renamed to `.<name>.old`, which can be removed once the program has exited.
+///
+/// This fails with [`InstallError::InvalidName`] if `name` is not a single path component.
+pub fn install_executable(src: &Path, name: &str) -> Result<PathBuf, InstallError> {
+    if !runtime::is_valid_component(name) {
+        return Err(InstallError::InvalidName(name.to_owned()));
+    }
+    let name = if cfg!(windows) && !name.to_ascii_lowercase().ends_with(".exe") {
+        format!("{name}.exe")
+    } else {
+        name.to_owned()
+    };
+
+    let dir = executable_dir()?;
+    fs::create_dir_all(&dir)?;
+    let dest = dir.join(&name);
+    let temp = dir.join(format!(
+        ".{name}.{}.{}.tmp",
+        process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp)?;
+
+    let result = copy_executable(src, file).and_then(|()| {
+        if cfg!(windows) && dest.exists() {
+            fs::rename(&dest, dir.join(format!(".{name}.old")))?;
+        }
+        fs::rename(&temp, &dest)
+    });
+    if let Err(err) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(err.into());
+    }
+    Ok(dest)
+}
+
+/// Asynchronous version of [`install_executable`], running it on tokio's blocking thread pool.
+#[cfg(feature = "tokio")]
+pub async fn install_executable_async(src: &Path, name: &str) -> Result<PathBuf, InstallError> {
+    let (src, name) = (src.to_path_buf(), name.to_owned());
+    crate::ensure::unblock(move || install_executable(&src, &name)).await
+}
+
+/// Distinguishes the temporary files of installs running at the same time in this process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Copies `src` into the newly created `dest`, making it executable, and closes it.
+fn copy_executable(src: &Path, mut dest: File) -> io::Result<()> {
+    io::copy(&mut File::open(src)?, &mut dest)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        dest.set_permissions(fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// This error occurs when an executable cannot be installed, see [`install_executable`].
+#[derive(Debug)]
+pub enum InstallError {
+    /// The home directory could not be located.
+    HomeDir(HomeDirError),
+    /// The executable's name is empty or is not a single path component.
+    InvalidName(String),
+    /// The executable could not be copied into place.
+    Io(io::Error),
+}
+
+impl fmt::Display for InstallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HomeDir(err) => err.fmt(f),
+            Self::InvalidName(name) => write!(f, "invalid executable name {name:?}"),
+            Self::Io(_) => write!(f, "could not install executable"),
+        }
+    }
+}
+
+impl Error for InstallError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::HomeDir(err) => Some(err),
+            Self::InvalidName(_) => None,
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<HomeDirError> for InstallError {
+    fn from(err: HomeDirError) -> Self {
+        Self::HomeDir(err)
+    }
+}
+
+impl From<io::Error> for InstallError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Returns the path to the directory for user-installed manual pages, `$XDG_DATA_HOME/man`, if available.
+///
+/// `man` searches this directory when `$MANPATH` is unset on modern Linux systems. This is `~/.local/share/man` on
+/// macOS too, but `/usr/bin/man` only searches it there if it is configured to (such as with `$MANPATH` or man-db's
+/// `manpath`), so users may need to add it. There is no manual page directory on Windows.
+pub fn man_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(match env::consts::OS {
+        "windows" => None,
+        _ => Some(xdg_style(DirKind::Data)?.join("man")),
+    })
+}
+
+/// Returns the path to the directory for user-installed manual pages in `section`, such as `man1` for section 1.
+///
+/// See [`man_dir`].
+pub fn man_section_dir(section: u8) -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(man_dir()?.map(|dir| dir.join(format!("man{section}"))))
+}
+
+/// Resolves the data or config directory the way XDG-following programs do on every platform, ignoring the
+/// platform's conventions.
+pub(crate) fn xdg_style(kind: DirKind) -> Result<PathBuf, HomeDirError> {
+    if let Some(dir) = xdg_dir(kind, TargetOs::current(), &ProcessEnv) {
+        return Ok(dir);
+    }
+
+    let home = home_dir()?;
+    Ok(match kind {
+        DirKind::Config => home.join(".config"),
+        _ => home.join(".local").join("share"),
+    })
+}
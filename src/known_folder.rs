@@ -0,0 +1,38 @@
+//! Windows Known Folder lookups, used when the `native` feature is enabled.
+
+use std::{ffi::OsString, os::windows::ffi::OsStringExt, path::PathBuf, ptr, slice};
+
+use windows_sys::{
+    core::{GUID, PWSTR},
+    Win32::{
+        System::Com::CoTaskMemFree,
+        UI::Shell::{SHGetKnownFolderPath, KF_FLAG_DEFAULT},
+    },
+};
+
+pub(crate) use windows_sys::Win32::UI::Shell::{
+    FOLDERID_Desktop, FOLDERID_Documents, FOLDERID_Downloads, FOLDERID_Music, FOLDERID_Pictures,
+    FOLDERID_Public, FOLDERID_Recent, FOLDERID_Templates, FOLDERID_Videos,
+};
+
+/// Returns the current location of a Known Folder, following any redirection (such as into OneDrive).
+pub(crate) fn known_folder(id: &GUID) -> Option<PathBuf> {
+    let mut path: PWSTR = ptr::null_mut();
+    // SAFETY: `path` is a valid out pointer and is freed below regardless of the result.
+    let result =
+        unsafe { SHGetKnownFolderPath(id, KF_FLAG_DEFAULT as _, ptr::null_mut(), &raw mut path) };
+
+    let folder = (result == 0).then(|| {
+        // SAFETY: on success, `path` is a NUL-terminated wide string.
+        let mut len = 0;
+        while unsafe { *path.add(len) } != 0 {
+            len += 1;
+        }
+        let wide = unsafe { slice::from_raw_parts(path, len) };
+        PathBuf::from(OsString::from_wide(wide))
+    });
+
+    // SAFETY: `path` was allocated by `SHGetKnownFolderPath` (or is null).
+    unsafe { CoTaskMemFree(path.cast()) };
+    folder
+}
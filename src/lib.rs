@@ -18,7 +18,7 @@
 //!
 //! ```rust
 //! user_dirs::home_dir();
-//! // See the [`home`](https://docs.rs/home) crate.
+//! // $USER_DIRS_HOME?, then see the [`home`](https://docs.rs/home) crate.
 //!
 //! user_dirs::cache_dir();
 //! // XDG? => $XDG_CACHE_HOME
@@ -49,120 +49,518 @@
 //! // macOS => None
 //! // Windows => None
 //! // Linux => Some(/home/leah/.local/state)
+//!
+//! user_dirs::calendar_dir();
+//! // macOS => Some(/Users/Leah/Library/Calendars)
+//! // Windows => Some(C:\Users\Leah\Contacts)
+//! // Linux => Some($XDG_DATA_HOME/evolution/calendar)
+//!
+//! user_dirs::recently_used_path();
+//! // macOS => None
+//! // Windows => Some(C:\Users\Leah\AppData\Roaming\Microsoft\Windows\Recent)
+//! // Linux => Some($XDG_DATA_HOME/recently-used.xbel)
 //! ```
+//!
+//! ## Features
+//!
+//! - `home-crate` *(default)*: locate the home directory with the [`home`](https://docs.rs/home) crate when `$HOME` (`%USERPROFILE%` on Windows) is unset. When disabled, `%HOMEDRIVE%%HOMEPATH%` is used instead on Windows, and nothing on Unix.
+//! - `debug-print`: enable [`print_dirs_to_stderr`], which dumps every resolved directory for `--debug` style output.
+//! - `tokio`: enable asynchronous `*_async` variants of the helpers that create directories or files, with the same semantics and errors. The `ensure_*_dir_async` functions use `tokio::fs`; the others, such as [`install_executable`], run their sync version on tokio's blocking thread pool.
+//! - `camino`: enable the `utf8` module, returning [`camino::Utf8PathBuf`](https://docs.rs/camino) paths.
+//! - `cap-std`: enable the `cap` module, opening the base directories as [`cap_std::fs::Dir`](https://docs.rs/cap-std) capability handles.
+//! - `figment`: implement [`figment::Provider`](https://docs.rs/figment) for [`UserConfigFile`], which loads the file as TOML.
+//! - `test-util`: enable the `test_util` module, with a scoped, thread-safe way of overriding environment variables in tests.
+//! - `watch`: enable [`watch_user_dirs`], which reports changes to `user-dirs.dirs`, and [`UserDirs::watch`], which reports changes to the resolved directories, using [`notify`](https://docs.rs/notify).
+//! - `derive`: enable the [`UserDir`] derive macro, which generates a constructor resolving annotated struct fields.
+//! - `registry`: on Windows, read the user directories from the registry's `User Shell Folders` key when the Known Folder API of the `native` feature is not used, without initializing COM. This also locates `%APPDATA%` when the variable is unset, as in some services.
+//! - `unicode-path`: reject resolved paths that are not valid Unicode with [`UserDirsError::NonUtf8Path`], so they fail early with a clear error rather than when the application formats them. Only functions that return a [`UserDirsError`] are checked, such as [`UserDirs::new`], [`resolve_all_to_map`] and [`config_file`]. The plain `*_dir` functions, like [`config_dir`], are **not** checked: they return [`HomeDirError`], which can't carry the path, and changing their error type would break every caller. Resolve through a [`UserDirs`] snapshot, or use the `utf8` module (which always checks), when every path must be Unicode.
+//! - `compat-app-dirs`: enable the `compat::app_dirs` module, a replacement for the API of the [`app_dirs2`](https://docs.rs/app_dirs2) crate.
+//! - `compat-electron`: enable the `compat::electron` module, which reproduces the paths of Electron's `app.getPath()` so that ports of Electron apps can find their old files.
+//! - `compat-platformdirs`: enable the `compat::platformdirs` module, with the API and layout of Python's [`platformdirs`](https://platformdirs.readthedocs.io).
+//! - `compat-qt`: enable the `compat::qt` module, which reproduces the locations of Qt's `QStandardPaths`.
+//! - `native`: use the platform's native APIs where paths can't be derived reliably, such as the Known Folder API for user directories on Windows (which follows redirection into OneDrive) and `NSFileManager` for App Group containers on macOS. Without this or the `registry` feature, user directories redirected elsewhere on Windows are not followed.
+
+use std::{collections::HashMap, fmt, path::PathBuf};
 
-use std::{env, path::PathBuf};
+use crate::env::{xdg_dir, Env, ProcessEnv, TargetOs};
+
+mod app;
+mod audit;
+mod candidates;
+#[cfg(feature = "cap-std")]
+pub mod cap;
+mod check;
+#[cfg(any(
+    feature = "compat-app-dirs",
+    feature = "compat-electron",
+    feature = "compat-platformdirs",
+    feature = "compat-qt"
+))]
+pub mod compat;
+mod completion;
+mod desktop;
+mod diff;
+mod ensure;
+pub mod env;
+mod explain;
+mod fallback;
+mod files;
+mod host;
+mod install;
+#[cfg(all(windows, feature = "native"))]
+mod known_folder;
+mod lock;
+mod media;
+pub mod msys;
+pub mod os;
+mod persistent;
+mod portable;
+mod project;
+mod purge;
+#[cfg(all(windows, any(feature = "native", feature = "registry")))]
+mod registry;
+mod runtime;
+mod scratch;
+mod search;
+mod snap;
+mod snapshot;
+mod socket;
+mod statics;
+pub mod system;
+mod template;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod users;
+#[cfg(feature = "camino")]
+pub mod utf8;
+#[cfg(feature = "watch")]
+mod watch;
+
+pub use app::{
+    app_env_prefix, app_name_normalize, config_dir_for_app_versioned, config_dir_for_app_with_org,
+    AppDirs, AppDirsVersioned,
+};
+pub use audit::{audit, audit_all, AuditCode, AuditFinding, Severity};
+pub use candidates::{config_dir_candidates, data_dir_candidates, Candidate, CandidateSource};
+pub use check::{
+    all_dirs_exist, cache_dir_checked, config_dir_checked, data_dir_checked, integrity_check,
+    runtime_dir_checked, state_dir_checked,
+};
+pub use completion::{completion_dir, Shell};
+pub use desktop::{
+    application_dir, install_desktop_entry, uninstall_desktop_entry, DesktopEntryError,
+    DesktopEntryOptions,
+};
+#[cfg(feature = "tokio")]
+pub use desktop::{install_desktop_entry_async, uninstall_desktop_entry_async};
+pub use diff::{compare, UserDirsDiff};
+pub use ensure::{
+    ensure_cache_dir, ensure_config_dir, ensure_data_dir, ensure_state_dir, ensure_user_dirs,
+};
+#[cfg(feature = "tokio")]
+pub use ensure::{
+    ensure_cache_dir_async, ensure_config_dir_async, ensure_data_dir_async, ensure_state_dir_async,
+    ensure_user_dirs_async,
+};
+pub use explain::{explain, Explanation, Step, VarOutcome};
+pub use fallback::{
+    cache_dir_or_default, config_dir_or_default, data_dir_or_default, state_dir_or_default,
+    DirFallback,
+};
+pub use files::{
+    cache_file, cache_path, config_dir_relative, config_file, config_path, data_file, data_path,
+    state_file, state_path, UserConfigFile,
+};
+pub use host::{cache_dir_per_host, host_id, state_dir_per_host};
+#[cfg(feature = "tokio")]
+pub use install::install_executable_async;
+pub use install::{
+    executable_dir, install_executable, is_executable_dir_on_path, man_dir, man_section_dir,
+    InstallError,
+};
+pub use lock::{InstanceLock, InstanceLockError};
+pub use media::{
+    desktop_dir, documents_dir, download_dir, is_cloud_redirected, music_dir, pictures_dir,
+    public_dir, templates_dir, user_dir, videos_dir, UserDirKind,
+};
+pub use persistent::{persistent_dirs, PersistentDir, PersistentDirKind};
+pub use portable::{PortableDirs, PortableOptions};
+pub use project::{find_project_config, ProjectConfigOptions};
+pub use purge::{PurgeError, PurgeOptions, PurgeReport, PurgedDir};
+pub use scratch::{runtime_scratch_file, ScratchFile, ScratchFileError, ScratchFileOptions};
+pub use search::{
+    cfg_dirs_for, config_dirs, config_files, data_dirs, data_dirs_with_extra,
+    find_all_config_files, font_search_paths, sounds_dir, sounds_search_paths, system_config_dirs,
+    system_data_dirs, themes_dir, themes_search_paths, XdgConfigDirsIter, XdgDataDirsIter,
+};
+pub use snap::{is_snap, SnapStrategy};
+pub use snapshot::{DirSource, SensitiveDirs, UserDirs, UserDirsBuilder};
+pub use socket::{socket_path, SocketPathError};
+pub use statics::{
+    cache_dir_static, config_dir_static, data_dir_static, home_dir_static, home_path,
+};
+pub use template::{contract, expand_template};
+#[cfg(feature = "derive")]
+pub use user_dirs_derive::UserDir;
+pub use users::{all_users, UserEntry};
+#[cfg(feature = "watch")]
+pub use watch::{watch_user_dirs, UserDirChange, UserDirsWatcher, WatchError};
 
 /// Returns the path to the home directory.
+///
+/// `$USER_DIRS_HOME` takes precedence over the platform's detection if it is set to an absolute path, so a service
+/// can be given its own home without changing `$HOME`. Every default derived from the home directory moves along with
+/// it, while the XDG variables still take precedence over those defaults (as do `%APPDATA%` and `%LOCALAPPDATA%` on
+/// Windows).
 pub fn home_dir() -> Result<PathBuf, HomeDirError> {
-    home::home_dir().ok_or(HomeDirError)
+    home_dir_with(&ProcessEnv)
 }
 
-/// Directory functions for obtaining operating system specified locations.
-pub mod os {
-    use std::{env, path::PathBuf};
-
-    use crate::{home_dir, HomeDirError};
-
-    /// Returns the path to the data directory.
-    pub fn data_dir() -> Result<PathBuf, HomeDirError> {
-        let home = home_dir()?;
+/// Locates the home directory like [`home_dir`], reading variables from `env`.
+pub(crate) fn home_dir_with(env: &impl Env) -> Result<PathBuf, HomeDirError> {
+    if let Some(home) = env::overridden(DirKind::Home) {
+        return Ok(home);
+    }
+    // Fuchsia components have no home directory, only the directories in their namespace.
+    if cfg!(target_os = "fuchsia") {
+        return Err(HomeDirError);
+    }
+    if let Some(home) = env::var(TargetOs::current(), env, HOME_VAR)
+        .filter(|home| env::is_absolute(TargetOs::current(), home))
+    {
+        return Ok(PathBuf::from(home));
+    }
 
-        Ok(match env::consts::OS {
-            "macos" => home.join("Library").join("Application Support"),
-            "windows" => env::var("APPDATA")
-                .map_or_else(|_| home.join("AppData").join("Roaming"), PathBuf::from),
-            _ => home.join(".local").join("share"),
-        })
+    if let Some(home) = env_home_dir(env) {
+        return Ok(home);
     }
 
-    /// Returns the path to the config directory.
-    pub fn config_dir() -> Result<PathBuf, HomeDirError> {
-        let home = home_dir()?;
+    #[cfg(feature = "home-crate")]
+    let home = env::with_env_lock(home::home_dir);
+    #[cfg(not(feature = "home-crate"))]
+    let home = home_drive_dir(env);
 
-        Ok(match env::consts::OS {
-            "macos" => home.join("Library").join("Preferences"),
-            "windows" => env::var("APPDATA")
-                .map_or_else(|_| home.join("AppData").join("Roaming"), PathBuf::from),
-            _ => home.join(".config"),
-        })
-    }
+    home.ok_or(HomeDirError)
+}
 
-    /// Returns the path to the cache directory.
-    pub fn cache_dir() -> Result<PathBuf, HomeDirError> {
-        let home = home_dir()?;
+/// Returns the home directory set by `$HOME`, or `%USERPROFILE%` on Windows, like the `home` crate checks first.
+fn env_home_dir(env: &impl Env) -> Option<PathBuf> {
+    let name = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env.var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
 
-        Ok(match env::consts::OS {
-            "macos" => home.join("Library").join("Caches"),
-            "windows" => env::var("LOCALAPPDATA")
-                .map_or_else(|_| home.join("AppData").join("Local"), PathBuf::from),
-            _ => home.join(".cache"),
-        })
+/// Returns `%HOMEDRIVE%%HOMEPATH%` on Windows, the fallback for [`env_home_dir`] without the `home` crate.
+#[cfg(not(feature = "home-crate"))]
+fn home_drive_dir(env: &impl Env) -> Option<PathBuf> {
+    if !cfg!(windows) {
+        return None;
     }
 
-    /// Returns the path to the state directory, if available.
-    pub fn state_dir() -> Result<Option<PathBuf>, HomeDirError> {
-        let home = home_dir()?;
+    let var = |key: &str| env.var_os(key).filter(|value| !value.is_empty());
+    let mut home = var("HOMEDRIVE")?;
+    home.push(var("HOMEPATH")?);
+    Some(PathBuf::from(home))
+}
 
-        Ok(Some(match env::consts::OS {
-            "macos" | "windows" => return Ok(None), // No state directory on macOS or Windows by default.
-            _ => home.join(".local").join("state"),
-        }))
-    }
+/// The variable overriding the home directory detection, see [`home_dir`].
+pub(crate) const HOME_VAR: &str = "USER_DIRS_HOME";
+
+/// Returns the directory overridden on this thread with [`env::set_override`], or else the one set by its XDG variable.
+fn overridden_or_xdg(kind: DirKind) -> Option<PathBuf> {
+    env::overridden(kind).or_else(|| xdg_dir(kind, TargetOs::current(), &ProcessEnv))
 }
 
 /// Returns the path to the data directory.
+///
+/// This is `$XDG_DATA_HOME` if it is set to an absolute path, and the platform's default otherwise.
 pub fn data_dir() -> Result<PathBuf, HomeDirError> {
-    let dir = if let Ok(xdg_data) = env::var("XDG_DATA_HOME") {
-        PathBuf::from(xdg_data)
-    } else {
-        crate::os::data_dir()?
-    };
-
-    Ok(dir)
+    match overridden_or_xdg(DirKind::Data) {
+        Some(dir) => Ok(dir),
+        None => crate::os::data_dir(),
+    }
 }
 
 /// Returns the path to the config directory.
+///
+/// This is `$XDG_CONFIG_HOME` if it is set to an absolute path, and the platform's default otherwise. As the XDG Base
+/// Directory specification requires, a relative value such as `XDG_CONFIG_HOME=.config` is ignored just like an unset
+/// one, so on Linux the directory falls back to `$HOME/.config`.
 pub fn config_dir() -> Result<PathBuf, HomeDirError> {
-    let dir = if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
-        PathBuf::from(xdg_config)
-    } else {
-        crate::os::config_dir()?
-    };
-    Ok(dir)
+    match overridden_or_xdg(DirKind::Config) {
+        Some(dir) => Ok(dir),
+        None => crate::os::config_dir(),
+    }
+}
+
+/// Returns the path to the config directory, falling back instead of failing.
+///
+/// This is [`UserDirs::config_or_home`] for the current environment, or `.` (the current directory) if the directories
+/// can't be resolved at all, such as when the home directory can't be located. It is meant for command-line tools that
+/// must always be able to run.
+#[must_use]
+pub fn config_or_home() -> PathBuf {
+    UserDirs::new().map_or_else(|_| PathBuf::from("."), |dirs| dirs.config_or_home())
 }
 
 /// Returns the path to the cache directory.
+///
+/// This is `$XDG_CACHE_HOME` if it is set to an absolute path, and the platform's default otherwise.
 pub fn cache_dir() -> Result<PathBuf, HomeDirError> {
-    let dir = if let Ok(xdg_cache) = env::var("XDG_CACHE_HOME") {
-        PathBuf::from(xdg_cache)
-    } else {
-        crate::os::cache_dir()?
-    };
-    Ok(dir)
+    match overridden_or_xdg(DirKind::Cache) {
+        Some(dir) => Ok(dir),
+        None => crate::os::cache_dir(),
+    }
 }
 
 /// Returns the path to the state directory, if available.
+///
+/// `$XDG_STATE_HOME` is respected on every platform if it is set to an absolute path; only without it is there no state
+/// directory on macOS and Windows.
 pub fn state_dir() -> Result<Option<PathBuf>, HomeDirError> {
-    let dir = if let Ok(xdg_state) = env::var("XDG_STATE_HOME") {
-        Some(PathBuf::from(xdg_state))
-    } else {
-        crate::os::state_dir()?
-    };
+    match overridden_or_xdg(DirKind::State) {
+        Some(dir) => Ok(Some(dir)),
+        None => crate::os::state_dir(),
+    }
+}
 
-    Ok(dir)
+/// Returns the path to the calendar directory, if available.
+pub fn calendar_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    crate::os::calendar_dir_in(data_dir)
+}
+
+/// Returns the path to the recently used files store, if available.
+///
+/// This is the freedesktop `recently-used.xbel` file in the data directory on Linux, and the Recent folder on Windows
+/// (looked up with the Known Folder API under the `native` feature). There is no equivalent path on macOS, where recent
+/// documents are only exposed through an API.
+#[must_use]
+pub fn recently_used_path() -> Option<PathBuf> {
+    match std::env::consts::OS {
+        "macos" | "ios" | "android" => None,
+        "windows" => {
+            #[cfg(all(windows, feature = "native"))]
+            if let Some(dir) = known_folder::known_folder(&known_folder::FOLDERID_Recent) {
+                return Some(dir);
+            }
+
+            let roaming = crate::os::data_dir().ok()?;
+            Some(roaming.join("Microsoft").join("Windows").join("Recent"))
+        }
+        _ => Some(data_dir().ok()?.join("recently-used.xbel")),
+    }
 }
 
 /// Returns the path to the runtime directory, if available.
+///
+/// This is `$XDG_RUNTIME_DIR` if it is set to an absolute path.
+#[must_use]
 pub fn runtime_dir() -> Option<PathBuf> {
-    if let Ok(xdg_runtime) = env::var("XDG_RUNTIME_DIR") {
-        return Some(PathBuf::from(xdg_runtime));
+    overridden_or_xdg(DirKind::Runtime)
+}
+
+/// Resolves the directory of every [`DirKind`] at once.
+///
+/// Directories that are unavailable on this platform, such as the runtime directory on macOS, map to `None`. Unlike a
+/// [`UserDirs`] snapshot, this has no options and keeps no record of where each directory came from.
+pub fn resolve_all_to_map() -> Result<HashMap<DirKind, Option<PathBuf>>, UserDirsError> {
+    DirKind::ALL
+        .into_iter()
+        .map(|kind| Ok((kind, kind.resolve()?.map(unicode_path).transpose()?)))
+        .collect()
+}
+
+/// Returns the raw value of `$XDG_DATA_HOME`, without any processing.
+///
+/// This is the value as set by the user, which is useful for diagnostics. Returns `None` if the variable is unset or
+/// not valid Unicode.
+#[must_use]
+pub fn xdg_data_home_raw() -> Option<String> {
+    ProcessEnv.var_os("XDG_DATA_HOME")?.into_string().ok()
+}
+
+/// Returns the raw value of `$XDG_CONFIG_HOME`, see [`xdg_data_home_raw`].
+#[must_use]
+pub fn xdg_config_home_raw() -> Option<String> {
+    ProcessEnv.var_os("XDG_CONFIG_HOME")?.into_string().ok()
+}
+
+/// Returns the raw value of `$XDG_CACHE_HOME`, see [`xdg_data_home_raw`].
+#[must_use]
+pub fn xdg_cache_home_raw() -> Option<String> {
+    ProcessEnv.var_os("XDG_CACHE_HOME")?.into_string().ok()
+}
+
+/// Returns the raw value of `$XDG_STATE_HOME`, see [`xdg_data_home_raw`].
+#[must_use]
+pub fn xdg_state_home_raw() -> Option<String> {
+    ProcessEnv.var_os("XDG_STATE_HOME")?.into_string().ok()
+}
+
+/// Returns the raw value of `$XDG_RUNTIME_DIR`, see [`xdg_data_home_raw`].
+#[must_use]
+pub fn xdg_runtime_dir_raw() -> Option<String> {
+    ProcessEnv.var_os("XDG_RUNTIME_DIR")?.into_string().ok()
+}
+
+/// Returns every variable defined by the XDG Base Directory Specification, with its description from the
+/// specification.
+///
+/// ```rust
+/// for (name, description) in user_dirs::well_known_xdg_dirs() {
+///     println!("${name}: {description}");
+/// }
+/// ```
+#[must_use]
+pub fn well_known_xdg_dirs() -> &'static [(&'static str, &'static str)] {
+    &[
+        (
+            "XDG_DATA_HOME",
+            "base directory relative to which user-specific data files should be stored",
+        ),
+        (
+            "XDG_CONFIG_HOME",
+            "base directory relative to which user-specific configuration files should be stored",
+        ),
+        (
+            "XDG_STATE_HOME",
+            "base directory relative to which user-specific state files should be stored",
+        ),
+        (
+            "XDG_DATA_DIRS",
+            "preference-ordered set of base directories to search for data files in addition to the \
+             $XDG_DATA_HOME base directory",
+        ),
+        (
+            "XDG_CONFIG_DIRS",
+            "preference-ordered set of base directories to search for configuration files in addition to the \
+             $XDG_CONFIG_HOME base directory",
+        ),
+        (
+            "XDG_CACHE_HOME",
+            "base directory relative to which user-specific non-essential data files should be stored",
+        ),
+        (
+            "XDG_RUNTIME_DIR",
+            "base directory relative to which user-specific non-essential runtime files and other file objects \
+             (such as sockets, named pipes, ...) should be stored",
+        ),
+    ]
+}
+
+/// The kinds of directories this crate resolves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DirKind {
+    /// See [`home_dir`].
+    Home,
+    /// See [`cache_dir`].
+    Cache,
+    /// See [`config_dir`].
+    Config,
+    /// See [`data_dir`].
+    Data,
+    /// See [`runtime_dir`].
+    Runtime,
+    /// See [`state_dir`].
+    State,
+}
+
+impl DirKind {
+    /// Every directory kind.
+    pub const ALL: [DirKind; 6] = [
+        Self::Home,
+        Self::Cache,
+        Self::Config,
+        Self::Data,
+        Self::Runtime,
+        Self::State,
+    ];
+
+    /// Resolves the directory of this kind, returning `None` if it is not available on this platform.
+    pub fn resolve(self) -> Result<Option<PathBuf>, HomeDirError> {
+        Ok(match self {
+            Self::Home => Some(home_dir()?),
+            Self::Cache => Some(cache_dir()?),
+            Self::Config => Some(config_dir()?),
+            Self::Data => Some(data_dir()?),
+            Self::Runtime => runtime_dir(),
+            Self::State => state_dir()?,
+        })
+    }
+
+    /// Returns the lowercase name of this kind, as displayed.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Home => "home",
+            Self::Cache => "cache",
+            Self::Config => "config",
+            Self::Data => "data",
+            Self::Runtime => "runtime",
+            Self::State => "state",
+        }
+    }
+}
+
+/// Kinds are ordered alphabetically by name, so sorted output is the same regardless of declaration order.
+impl Ord for DirKind {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+impl PartialOrd for DirKind {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for DirKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Prints every resolved directory (or the error resolving it) to standard error.
+///
+/// Intended for the `--debug` output of command-line tools.
+#[cfg(feature = "debug-print")]
+pub fn print_dirs_to_stderr() {
+    for kind in DirKind::ALL {
+        match kind.resolve() {
+            Ok(Some(path)) => eprintln!("{kind:<7} = {}", path.display()),
+            Ok(None) => eprintln!("{kind:<7} = (unavailable)"),
+            Err(err) => eprintln!("{kind:<7} = error: {err}"),
+        }
+    }
+}
+
+/// Support code for the [`UserDir`](user_dirs_derive::UserDir) derive macro.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    use std::path::PathBuf;
+
+    use crate::{DirKind, UserDirsError};
+
+    pub fn optional(
+        kind: DirKind,
+        app_name: Option<&str>,
+    ) -> Result<Option<PathBuf>, UserDirsError> {
+        let dir = kind.resolve()?;
+        Ok(match app_name {
+            Some(app_name) => dir.map(|dir| dir.join(app_name)),
+            None => dir,
+        })
+    }
+
+    pub fn required(kind: DirKind, app_name: Option<&str>) -> Result<PathBuf, UserDirsError> {
+        optional(kind, app_name)?.ok_or(UserDirsError::Unavailable(kind))
     }
-    None
 }
 
 /// This error occurs when the home directory cannot be located.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct HomeDirError;
 impl std::fmt::Display for HomeDirError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -170,3 +568,150 @@ impl std::fmt::Display for HomeDirError {
     }
 }
 impl std::error::Error for HomeDirError {}
+
+/// Checks that `path` is valid Unicode, if the `unicode-path` feature is enabled.
+pub(crate) fn unicode_path(path: PathBuf) -> Result<PathBuf, UserDirsError> {
+    if cfg!(feature = "unicode-path") && path.to_str().is_none() {
+        return Err(UserDirsError::NonUtf8Path(path));
+    }
+    Ok(path)
+}
+
+/// The errors that can occur when resolving directories.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UserDirsError {
+    /// The home directory could not be located.
+    HomeDir(HomeDirError),
+    /// A resolved path is not valid UTF-8, see the `unicode-path` feature.
+    NonUtf8Path(PathBuf),
+    /// The directory is not available on this platform.
+    Unavailable(DirKind),
+    /// The application name is empty after normalization, see [`AppDirs`].
+    InvalidAppName(String),
+    /// The file name is not a single path component, see [`config_file`].
+    InvalidFileName(String),
+    /// The profile name is empty after normalization, see [`AppDirs::with_profile`].
+    InvalidProfileName(String),
+    /// The prefix is empty or not made of ASCII letters, digits and `_`, see [`AppDirs::with_env_prefix`].
+    InvalidEnvPrefix(String),
+    /// The path is not relative or leaves its base directory, see [`config_path`].
+    InvalidRelativePath(PathBuf),
+    /// The directory must be an absolute path, see [`UserDirs::set_data`].
+    NotAbsolute(DirKind, PathBuf),
+    /// The path to the current executable could not be determined, see [`PortableDirs`].
+    CurrentExe(std::io::Error),
+    /// The template refers to a variable that is not a known directory, see [`expand_template`].
+    UnknownVariable(String),
+    /// The template has a `${` without a matching `}`, see [`expand_template`].
+    InvalidTemplate(String),
+    /// Something other than a directory exists at the directory's path, see [`config_dir_checked`].
+    NotADirectory(DirKind, PathBuf),
+    /// The directory could not be accessed, or is a broken symbolic link, see [`integrity_check`].
+    Inaccessible(DirKind, PathBuf, std::io::Error),
+    /// The directory is accessible to other users when it must not be, see [`integrity_check`].
+    InsecurePermissions(DirKind, PathBuf),
+    /// The target has no notion of a home directory, such as `wasm32-unknown-unknown` or Fuchsia.
+    ///
+    /// This is returned instead of [`UserDirsError::HomeDir`] on such targets, so that callers can tell a missing home
+    /// directory apart from a platform without one.
+    UnsupportedPlatform {
+        /// The name of the operating system, as in [`std::env::consts::OS`].
+        os: &'static str,
+    },
+}
+impl fmt::Display for UserDirsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HomeDir(err) => err.fmt(f),
+            Self::NonUtf8Path(path) => write!(f, "path is not valid UTF-8: {}", path.display()),
+            Self::Unavailable(kind) => write!(f, "no {kind} directory on this platform"),
+            Self::InvalidAppName(name) => write!(f, "invalid application name: {name:?}"),
+            Self::InvalidFileName(name) => write!(f, "invalid file name: {name:?}"),
+            Self::InvalidProfileName(name) => write!(f, "invalid profile name: {name:?}"),
+            Self::InvalidEnvPrefix(prefix) => {
+                write!(f, "invalid environment variable prefix: {prefix:?}")
+            }
+            Self::InvalidRelativePath(path) => {
+                write!(
+                    f,
+                    "path is not relative to its base directory: {}",
+                    path.display()
+                )
+            }
+            Self::NotAbsolute(kind, path) => {
+                write!(
+                    f,
+                    "{kind} directory is not an absolute path: {}",
+                    path.display()
+                )
+            }
+            Self::CurrentExe(_) => write!(f, "failed to locate the current executable"),
+            Self::UnknownVariable(name) => write!(f, "unknown variable in template: ${name}"),
+            Self::InvalidTemplate(template) => {
+                write!(f, "unterminated variable in template: {template:?}")
+            }
+            Self::NotADirectory(kind, path) => {
+                write!(f, "{kind} directory is not a directory: {}", path.display())
+            }
+            Self::Inaccessible(kind, path, _) => {
+                write!(f, "{kind} directory is not accessible: {}", path.display())
+            }
+            Self::InsecurePermissions(kind, path) => {
+                write!(
+                    f,
+                    "{kind} directory is accessible to other users: {}",
+                    path.display()
+                )
+            }
+            Self::UnsupportedPlatform { os } => {
+                write!(
+                    f,
+                    "user directories are not supported on this platform ({os})"
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for UserDirsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::HomeDir(err) => Some(err),
+            Self::Inaccessible(_, _, err) | Self::CurrentExe(err) => Some(err),
+            Self::NonUtf8Path(_)
+            | Self::Unavailable(_)
+            | Self::InvalidAppName(_)
+            | Self::InvalidFileName(_)
+            | Self::InvalidProfileName(_)
+            | Self::InvalidEnvPrefix(_)
+            | Self::InvalidRelativePath(_)
+            | Self::NotAbsolute(..)
+            | Self::UnknownVariable(_)
+            | Self::InvalidTemplate(_)
+            | Self::NotADirectory(..)
+            | Self::InsecurePermissions(..)
+            | Self::UnsupportedPlatform { .. } => None,
+        }
+    }
+}
+impl From<HomeDirError> for UserDirsError {
+    fn from(err: HomeDirError) -> Self {
+        if cfg!(any(
+            target_os = "unknown",
+            target_os = "none",
+            target_os = "fuchsia"
+        )) {
+            return Self::UnsupportedPlatform {
+                os: std::env::consts::OS,
+            };
+        }
+
+        Self::HomeDir(err)
+    }
+}
+
+impl From<HomeDirError> for std::io::Error {
+    fn from(err: HomeDirError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::NotFound, err)
+    }
+}
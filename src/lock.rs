@@ -0,0 +1,183 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Read, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{cache_dir, runtime, state_dir};
+
+/// A lock ensuring only one instance of an application runs per user.
+///
+/// The lock is an advisory exclusive lock (`flock` on Unix, `LockFileEx` on Windows) on a file named `instance.lock`
+/// in the application's directory, which is the first available of:
+///
+/// 1. `<runtime_dir>/<app>`, created with mode `0700` on Unix;
+/// 2. `<state_dir>/<app>`;
+/// 3. `<cache_dir>/<app>`.
+///
+/// The file contains the PID of the owning process for diagnostics, though on Windows the lock also prevents other
+/// processes from reading it, so [`InstanceLockError::AlreadyRunning`] carries no PID there. Since the lock (not the file's existence) is what
+/// matters, files left behind by crashed processes do not block acquisition. The lock is released and the file is
+/// removed when the [`InstanceLock`] is dropped.
+#[derive(Debug)]
+pub struct InstanceLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// Acquires the instance lock for `app`, failing with [`InstanceLockError::AlreadyRunning`] if another process holds it.
+    pub fn acquire(app: &str) -> Result<InstanceLock, InstanceLockError> {
+        if !runtime::is_valid_component(app) {
+            return Err(InstanceLockError::InvalidName(app.to_owned()));
+        }
+        let path = lock_dir(app)?.join("instance.lock");
+
+        loop {
+            let mut file = open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    return Err(InstanceLockError::AlreadyRunning {
+                        pid: read_pid(&mut file),
+                    })
+                }
+                Err(TryLockError::Error(err)) => return Err(err.into()),
+            }
+
+            // The previous owner may have removed the file between opening and locking it, in which case the lock is
+            // on an orphaned file and another process could lock a new one at the same path.
+            if !is_same_file(&file, &path)? {
+                continue;
+            }
+
+            file.set_len(0)?;
+            write!(file, "{}", process::id())?;
+            file.flush()?;
+
+            return Ok(InstanceLock { file, path });
+        }
+    }
+
+    /// Returns the path to the lock file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // The file is removed while still locked: unlocking first would let another process lock it just before it is
+        // removed, leaving that process with a lock on an orphaned file. A process that opened the file before the
+        // removal notices it in `acquire`, as the path no longer refers to the file it locked. On Windows, the file is
+        // opened with `FILE_FLAG_DELETE_ON_CLOSE` instead.
+        #[cfg(not(windows))]
+        let _ = fs::remove_file(&self.path);
+        let _ = self.file.unlock();
+    }
+}
+
+fn lock_dir(app: &str) -> Result<PathBuf, InstanceLockError> {
+    if let Some(dir) = runtime::app_dir(app, false) {
+        runtime::create_private_dir(&dir)?;
+        return Ok(dir);
+    }
+
+    let base = match state_dir()? {
+        Some(state) => state,
+        None => cache_dir()?,
+    };
+    let dir = base.join(app);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(false);
+    #[cfg(windows)]
+    {
+        const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+        std::os::windows::fs::OpenOptionsExt::custom_flags(&mut options, FILE_FLAG_DELETE_ON_CLOSE);
+    }
+    options.open(path)
+}
+
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut contents = String::new();
+    file.rewind().ok()?;
+    file.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let opened = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(current) => Ok(opened.dev() == current.dev() && opened.ino() == current.ino()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+fn is_same_file(_file: &File, _path: &Path) -> io::Result<bool> {
+    // Files opened with `FILE_FLAG_DELETE_ON_CLOSE` cannot be reopened once deletion is pending, so the path always
+    // refers to the locked file.
+    Ok(true)
+}
+
+/// This error occurs when an [`InstanceLock`] cannot be acquired.
+#[derive(Debug)]
+pub enum InstanceLockError {
+    /// Another process holds the lock.
+    AlreadyRunning {
+        /// The PID of the other process, if it could be read from the lock file. This is always `None` on Windows.
+        pid: Option<u32>,
+    },
+    /// The application name is empty or is not a single path component.
+    InvalidName(String),
+    /// The lock file could not be created or locked.
+    Io(io::Error),
+}
+
+impl fmt::Display for InstanceLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRunning { pid: Some(pid) } => {
+                write!(f, "another instance is already running (PID {pid})")
+            }
+            Self::AlreadyRunning { pid: None } => write!(f, "another instance is already running"),
+            Self::InvalidName(name) => write!(f, "invalid application name {name:?}"),
+            Self::Io(_) => write!(f, "could not acquire instance lock"),
+        }
+    }
+}
+
+impl Error for InstanceLockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for InstanceLockError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<crate::HomeDirError> for InstanceLockError {
+    fn from(err: crate::HomeDirError) -> Self {
+        Self::Io(err.into())
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config_dir,
+    env::{Env, ProcessEnv, TargetOs},
+    home_dir, system_config_dirs, HomeDirError,
+};
+
+/// The kinds of user directories, as defined by [`xdg-user-dirs`](https://www.freedesktop.org/wiki/Software/xdg-user-dirs/).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserDirKind {
+    /// See [`desktop_dir`].
+    Desktop,
+    /// See [`documents_dir`].
+    Documents,
+    /// See [`download_dir`].
+    Download,
+    /// See [`music_dir`].
+    Music,
+    /// See [`pictures_dir`].
+    Pictures,
+    /// See [`public_dir`].
+    PublicShare,
+    /// See [`templates_dir`].
+    Templates,
+    /// See [`videos_dir`].
+    Videos,
+}
+
+impl UserDirKind {
+    /// Every user directory kind.
+    pub const ALL: [UserDirKind; 8] = [
+        Self::Desktop,
+        Self::Documents,
+        Self::Download,
+        Self::Music,
+        Self::Pictures,
+        Self::PublicShare,
+        Self::Templates,
+        Self::Videos,
+    ];
+
+    /// Returns the key of this kind in `user-dirs.dirs`, without the `XDG_` prefix and `_DIR` suffix.
+    fn key(self) -> &'static str {
+        match self {
+            Self::Desktop => "DESKTOP",
+            Self::Documents => "DOCUMENTS",
+            Self::Download => "DOWNLOAD",
+            Self::Music => "MUSIC",
+            Self::Pictures => "PICTURES",
+            Self::PublicShare => "PUBLICSHARE",
+            Self::Templates => "TEMPLATES",
+            Self::Videos => "VIDEOS",
+        }
+    }
+
+    /// Resolves the directory of this kind, see [`user_dir`].
+    pub fn resolve(self) -> Result<Option<PathBuf>, HomeDirError> {
+        user_dir(self)
+    }
+}
+
+impl fmt::Display for UserDirKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Desktop => "desktop",
+            Self::Documents => "documents",
+            Self::Download => "download",
+            Self::Music => "music",
+            Self::Pictures => "pictures",
+            Self::PublicShare => "public",
+            Self::Templates => "templates",
+            Self::Videos => "videos",
+        })
+    }
+}
+
+/// Returns the path to the `user-dirs.dirs` file, `$XDG_CONFIG_HOME/user-dirs.dirs`.
+pub(crate) fn user_dirs_file() -> Result<PathBuf, HomeDirError> {
+    Ok(config_dir()?.join("user-dirs.dirs"))
+}
+
+/// Parses the contents of a `user-dirs.dirs` file, mapping directories set to `$HOME` (disabled) to `None`.
+pub(crate) fn parse_user_dirs(
+    contents: &str,
+    home: &Path,
+) -> HashMap<UserDirKind, Option<PathBuf>> {
+    let mut dirs = HashMap::new();
+
+    for line in contents.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some(kind) = key
+            .strip_prefix("XDG_")
+            .and_then(|key| key.strip_suffix("_DIR"))
+            .and_then(|key| UserDirKind::ALL.into_iter().find(|kind| kind.key() == key))
+        else {
+            continue;
+        };
+        let Some(value) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        else {
+            continue;
+        };
+
+        let path = if value == "$HOME" {
+            None
+        } else if let Some(relative) = value.strip_prefix("$HOME/") {
+            match relative.trim_start_matches('/') {
+                "" => None,
+                relative => Some(home.join(relative)),
+            }
+        } else if value.starts_with('/') {
+            Some(PathBuf::from(value))
+        } else {
+            // Only `$HOME`-relative and absolute values are allowed.
+            continue;
+        };
+        dirs.insert(kind, path);
+    }
+
+    dirs
+}
+
+/// Parses the contents of a `user-dirs.defaults` file, which maps keys like `DOWNLOAD` to paths relative to home.
+fn parse_user_dirs_defaults(contents: &str, home: &Path) -> HashMap<UserDirKind, PathBuf> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let kind = UserDirKind::ALL
+                .into_iter()
+                .find(|kind| kind.key() == key.trim())?;
+            let value = value.trim().trim_start_matches('/');
+            (!value.is_empty()).then(|| (kind, home.join(value)))
+        })
+        .collect()
+}
+
+/// Reads the first `user-dirs.defaults` file in the system config directories, which distributions use to set the
+/// (possibly translated) names of the user directories.
+fn user_dirs_defaults(home: &Path) -> HashMap<UserDirKind, PathBuf> {
+    if matches!(env::consts::OS, "macos" | "windows") {
+        return HashMap::new();
+    }
+
+    system_config_dirs()
+        .find_map(|dir| fs::read_to_string(dir.join("user-dirs.defaults")).ok())
+        .map(|contents| parse_user_dirs_defaults(&contents, home))
+        .unwrap_or_default()
+}
+
+/// Resolves every user directory, see [`user_dir`].
+pub(crate) fn user_dirs() -> Result<HashMap<UserDirKind, Option<PathBuf>>, HomeDirError> {
+    let home = home_dir()?;
+    let configured = fs::read_to_string(user_dirs_file()?)
+        .map(|contents| parse_user_dirs(&contents, &home))
+        .unwrap_or_default();
+    let mut defaults = user_dirs_defaults(&home);
+
+    Ok(UserDirKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let dir = match configured.get(&kind) {
+                Some(dir) => dir.clone(),
+                None => defaults
+                    .remove(&kind)
+                    .or_else(|| default_user_dir(kind, &home)),
+            };
+            (kind, dir)
+        })
+        .collect())
+}
+
+#[cfg(all(windows, feature = "native"))]
+fn known_folder(kind: UserDirKind) -> Option<PathBuf> {
+    use crate::known_folder::{
+        known_folder, FOLDERID_Desktop, FOLDERID_Documents, FOLDERID_Downloads, FOLDERID_Music,
+        FOLDERID_Pictures, FOLDERID_Public, FOLDERID_Templates, FOLDERID_Videos,
+    };
+
+    known_folder(match kind {
+        UserDirKind::Desktop => &FOLDERID_Desktop,
+        UserDirKind::Documents => &FOLDERID_Documents,
+        UserDirKind::Download => &FOLDERID_Downloads,
+        UserDirKind::Music => &FOLDERID_Music,
+        UserDirKind::Pictures => &FOLDERID_Pictures,
+        UserDirKind::PublicShare => &FOLDERID_Public,
+        UserDirKind::Templates => &FOLDERID_Templates,
+        UserDirKind::Videos => &FOLDERID_Videos,
+    })
+}
+
+/// Reads the location of the user directory from the registry's `User Shell Folders` key.
+#[cfg(all(windows, feature = "registry"))]
+fn shell_folder(kind: UserDirKind) -> Option<PathBuf> {
+    crate::registry::shell_folder(match kind {
+        UserDirKind::Desktop => "Desktop",
+        UserDirKind::Documents => "Personal",
+        UserDirKind::Download => "{374DE290-123F-4565-9164-39C4925E467B}",
+        UserDirKind::Music => "My Music",
+        UserDirKind::Pictures => "My Pictures",
+        UserDirKind::Templates => "Templates",
+        UserDirKind::Videos => "My Video",
+        // The public folder is shared by every user, so it is not a user shell folder.
+        UserDirKind::PublicShare => return None,
+    })
+}
+
+fn default_user_dir(kind: UserDirKind, home: &Path) -> Option<PathBuf> {
+    #[cfg(all(windows, feature = "native"))]
+    if let Some(dir) = known_folder(kind) {
+        return Some(dir);
+    }
+    #[cfg(all(windows, feature = "registry"))]
+    if let Some(dir) = shell_folder(kind) {
+        return Some(dir);
+    }
+
+    let name = match (env::consts::OS, kind) {
+        (_, UserDirKind::Desktop) => "Desktop",
+        (_, UserDirKind::Documents) => "Documents",
+        (_, UserDirKind::Download) => "Downloads",
+        (_, UserDirKind::Music) => "Music",
+        (_, UserDirKind::Pictures) => "Pictures",
+        ("windows", UserDirKind::PublicShare) => {
+            return crate::env::var(TargetOs::Windows, &ProcessEnv, "PUBLIC").map(PathBuf::from);
+        }
+        (_, UserDirKind::PublicShare) => "Public",
+        ("macos", UserDirKind::Templates) => return None,
+        ("windows", UserDirKind::Templates) => {
+            return Some(
+                crate::os::data_dir()
+                    .ok()?
+                    .join("Microsoft")
+                    .join("Windows")
+                    .join("Templates"),
+            );
+        }
+        (_, UserDirKind::Templates) => "Templates",
+        ("macos", UserDirKind::Videos) => "Movies",
+        (_, UserDirKind::Videos) => "Videos",
+    };
+
+    Some(home.join(name))
+}
+
+/// Returns the path to the user directory of the given kind, if available.
+///
+/// Directories configured in `$XDG_CONFIG_HOME/user-dirs.dirs` take precedence on every platform, and a directory set
+/// to `$HOME` there is disabled. Otherwise, on Linux and other Unix systems, the names in the first
+/// `user-dirs.defaults` file in the [`system_config_dirs`](crate::system_config_dirs) are used, which is how
+/// distributions set translated names such as `Téléchargements`. Failing that, the platform's conventional folder in
+/// the home directory is used. With the `native` feature on Windows, the Known Folder API is consulted first, which
+/// follows folders redirected elsewhere (such as into OneDrive); see also [`is_cloud_redirected`]. With the `registry`
+/// feature, the registry's `User Shell Folders` key is consulted next, which also follows redirection.
+///
+/// Without either feature, redirection is **not** followed on Windows: a documents folder moved into OneDrive is still
+/// reported as `%USERPROFILE%\Documents`, which is then usually an empty leftover folder.
+pub fn user_dir(kind: UserDirKind) -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(user_dirs()?.remove(&kind).flatten())
+}
+
+/// Returns the path to the desktop directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn desktop_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Desktop)
+}
+
+/// Returns the path to the documents directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn documents_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Documents)
+}
+
+/// Returns the path to the download directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn download_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Download)
+}
+
+/// Returns the path to the music directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn music_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Music)
+}
+
+/// Returns the path to the pictures directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn pictures_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Pictures)
+}
+
+/// Returns the path to the public share directory, if available.
+pub fn public_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::PublicShare)
+}
+
+/// Returns the path to the templates directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn templates_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Templates)
+}
+
+/// Returns the path to the videos directory, if available.
+///
+/// On Windows, this only follows folder redirection with the `native` or `registry` feature, see [`user_dir`].
+pub fn videos_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    user_dir(UserDirKind::Videos)
+}
+
+/// Returns whether `path` is inside a OneDrive folder, which is synced to the cloud.
+///
+/// The OneDrive roots are read from the `%OneDrive%`, `%OneDriveConsumer%` and `%OneDriveCommercial%` environment
+/// variables set by the OneDrive client. This is useful to warn before writing large amounts of data into a folder
+/// that has been redirected into OneDrive, such as the documents directory.
+#[must_use]
+pub fn is_cloud_redirected(path: &Path) -> bool {
+    ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+        .into_iter()
+        .filter_map(|name| ProcessEnv.var_os(name))
+        .filter(|root| !root.is_empty())
+        .any(|root| starts_with_ignore_case(path, Path::new(&root)))
+}
+
+/// Returns whether `path` starts with `base`, comparing components case-insensitively like Windows does.
+fn starts_with_ignore_case(path: &Path, base: &Path) -> bool {
+    let mut components = path.components();
+    base.components().all(|base| {
+        components.next().is_some_and(|component| {
+            component
+                .as_os_str()
+                .to_string_lossy()
+                .eq_ignore_ascii_case(&base.as_os_str().to_string_lossy())
+        })
+    })
+}
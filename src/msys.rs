@@ -0,0 +1,173 @@
+//! Conversion between the POSIX and Windows forms of paths under MSYS2, Git Bash and Cygwin.
+//!
+//! These environments present a POSIX view of the Windows file system, so the same directory can be `/home/leah` to
+//! a shell and `C:\msys64\home\leah` to a Windows program. Variables set in the shell often keep the POSIX form,
+//! while variables inherited from Windows, such as `APPDATA`, keep the Windows form.
+//!
+//! This crate returns paths in the form matching how it was compiled: a Windows program launched from an MSYS2 shell
+//! gets Windows paths, with POSIX values of XDG and Windows variables converted using [`MsysMounts`]. Conversion
+//! follows the default mount table of these environments rather than running `cygpath`:
+//!
+//! | POSIX form              | Windows form          |
+//! | ----------------------- | --------------------- |
+//! | `/c/Users` (MSYS2)      | `C:\Users`            |
+//! | `/cygdrive/c` (Cygwin)  | `C:\`                 |
+//! | `/bin/bash`             | `<root>\usr\bin\bash` |
+//! | `/home/leah`            | `<root>\home\leah`    |
+
+use crate::env::Env;
+
+/// The mount table of an MSYS2 or Cygwin installation, for converting paths between the POSIX and Windows forms.
+///
+/// ```rust
+/// use user_dirs::msys::MsysMounts;
+///
+/// let mounts = MsysMounts::msys2(Some(r"C:\msys64"));
+///
+/// assert_eq!(mounts.to_windows_form("/home/leah").as_deref(), Some(r"C:\msys64\home\leah"));
+/// assert_eq!(mounts.to_windows_form("/d/projects").as_deref(), Some(r"D:\projects"));
+/// assert_eq!(mounts.to_posix_form(r"C:\Users\Leah").as_deref(), Some("/c/Users/Leah"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsysMounts {
+    root: Option<String>,
+    cygdrive: &'static str,
+}
+
+impl MsysMounts {
+    /// Returns the mount table of MSYS2 or Git Bash installed at `root`, where drives are mounted as `/c`.
+    #[must_use]
+    pub fn msys2(root: Option<&str>) -> MsysMounts {
+        MsysMounts {
+            root: root.map(trim_separators),
+            cygdrive: "/",
+        }
+    }
+
+    /// Returns the mount table of Cygwin installed at `root`, where drives are mounted as `/cygdrive/c`.
+    #[must_use]
+    pub fn cygwin(root: Option<&str>) -> MsysMounts {
+        MsysMounts {
+            root: root.map(trim_separators),
+            cygdrive: "/cygdrive/",
+        }
+    }
+
+    /// Detects whether the process runs in an MSYS2, Git Bash or Cygwin environment, from the variables in `env`.
+    ///
+    /// `$MSYSTEM`, which MSYS2 and Git Bash set, or `$OSTYPE` being `msys` or `cygwin`, mark such an environment. The
+    /// installation root is taken from `%WD%`, which MSYS2's launcher sets to `<root>\usr\bin`, or from `%EXEPATH%`,
+    /// which Git Bash sets to its installation directory. Without either, only drive mounts can be converted.
+    #[must_use]
+    pub fn detect(env: &impl Env) -> Option<MsysMounts> {
+        let ostype = env.var_os("OSTYPE");
+        let cygwin = ostype.as_deref().is_some_and(|ostype| ostype == "cygwin");
+        if env.var_os("MSYSTEM").is_none()
+            && !cygwin
+            && ostype.as_deref().is_none_or(|ostype| ostype != "msys")
+        {
+            return None;
+        }
+
+        let root = env
+            .var_os("WD")
+            .and_then(|wd| {
+                let wd = trim_separators(wd.to_str()?);
+                let root = wd
+                    .strip_suffix(r"\usr\bin")
+                    .or(wd.strip_suffix("/usr/bin"))?;
+                Some(root.to_owned())
+            })
+            .or_else(|| env.var_os("EXEPATH")?.into_string().ok());
+
+        Some(if cygwin {
+            MsysMounts::cygwin(root.as_deref())
+        } else {
+            MsysMounts::msys2(root.as_deref())
+        })
+    }
+
+    /// Converts a POSIX path like `/c/Users/Leah` or `/home/leah` into the Windows form, `C:\Users\Leah`.
+    ///
+    /// Returns `None` if `path` is not an absolute POSIX path, or is outside the drive mounts and the installation root
+    /// is unknown.
+    #[must_use]
+    pub fn to_windows_form(&self, path: &str) -> Option<String> {
+        if !path.starts_with('/') {
+            return None;
+        }
+
+        if let Some(rest) = path.strip_prefix(self.cygdrive) {
+            let mut chars = rest.chars();
+            if let (Some(drive), None | Some('/')) = (chars.next(), chars.next()) {
+                if drive.is_ascii_alphabetic() {
+                    let rest = rest[1..].trim_start_matches('/');
+                    return Some(format!(
+                        r"{}:\{}",
+                        drive.to_ascii_uppercase(),
+                        rest.replace('/', r"\")
+                    ));
+                }
+            }
+        }
+
+        // MSYS2 and Cygwin both mount `/usr/bin` on `/bin`.
+        let path = match path.strip_prefix("/bin") {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("/usr/bin{rest}"),
+            _ => path.to_owned(),
+        };
+        let root = self.root.as_deref()?;
+        Some(format!(
+            "{root}{}",
+            path.trim_end_matches('/').replace('/', r"\")
+        ))
+    }
+
+    /// Converts a Windows path like `C:\Users\Leah` into the POSIX form, `/c/Users/Leah`.
+    ///
+    /// Paths inside the installation root are converted relative to `/`. Returns `None` if `path` is not an absolute
+    /// Windows path with a drive letter.
+    #[must_use]
+    pub fn to_posix_form(&self, path: &str) -> Option<String> {
+        let path = path.replace('\\', "/");
+        let mut chars = path.chars();
+        let (Some(drive), Some(':'), None | Some('/')) = (chars.next(), chars.next(), chars.next())
+        else {
+            return None;
+        };
+        if !drive.is_ascii_alphabetic() {
+            return None;
+        }
+
+        if let Some(root) = &self.root {
+            let root = root.replace('\\', "/");
+            if let Some(rest) = strip_prefix_ignore_case(&path, &root) {
+                if rest.is_empty() || rest.starts_with('/') {
+                    return Some(if rest.is_empty() {
+                        "/".to_owned()
+                    } else {
+                        rest.to_owned()
+                    });
+                }
+            }
+        }
+
+        let rest = path[2..].trim_matches('/');
+        let drive = drive.to_ascii_lowercase();
+        Some(if rest.is_empty() {
+            format!("{}{drive}", self.cygdrive)
+        } else {
+            format!("{}{drive}/{rest}", self.cygdrive)
+        })
+    }
+}
+
+fn trim_separators(path: &str) -> String {
+    path.trim_end_matches(['/', '\\']).to_owned()
+}
+
+fn strip_prefix_ignore_case<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = path.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &path[prefix.len()..])
+}
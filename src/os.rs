@@ -0,0 +1,80 @@
+//! Directory functions for obtaining operating system specified locations.
+//!
+//! These functions ignore every XDG variable and return the platform's native locations, replicating the behavior of
+//! the `dirs` crate. This is useful for telling users what the default location is when they have overridden it:
+//!
+//! ```rust
+//! let default = user_dirs::os::config_dir()?;
+//! let config = user_dirs::config_dir()?;
+//!
+//! if config != default {
+//!     println!("Using {} instead of the default {}", config.display(), default.display());
+//! }
+//! # Ok::<(), user_dirs::HomeDirError>(())
+//! ```
+
+use std::{env, path::PathBuf};
+
+#[cfg(target_os = "macos")]
+pub mod macos;
+
+use crate::{
+    env::{os_cache_dir, os_config_dir, os_data_dir, os_state_dir, ProcessEnv, TargetOs},
+    home_dir, HomeDirError,
+};
+
+/// Returns the path to the data directory.
+pub fn data_dir() -> Result<PathBuf, HomeDirError> {
+    Ok(os_data_dir(TargetOs::current(), &base_home()?, &ProcessEnv))
+}
+
+/// Returns the path to the config directory.
+pub fn config_dir() -> Result<PathBuf, HomeDirError> {
+    Ok(os_config_dir(
+        TargetOs::current(),
+        &base_home()?,
+        &ProcessEnv,
+    ))
+}
+
+/// Returns the path to the cache directory.
+pub fn cache_dir() -> Result<PathBuf, HomeDirError> {
+    Ok(os_cache_dir(
+        TargetOs::current(),
+        &base_home()?,
+        &ProcessEnv,
+    ))
+}
+
+/// Returns the path to the state directory, if available.
+pub fn state_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(os_state_dir(TargetOs::current(), &base_home()?))
+}
+
+/// Returns the home directory the defaults are based on.
+///
+/// Fuchsia has no home directory, but its defaults don't need one as they are in the component's namespace.
+fn base_home() -> Result<PathBuf, HomeDirError> {
+    match TargetOs::current() {
+        TargetOs::Fuchsia => Ok(PathBuf::new()),
+        _ => home_dir(),
+    }
+}
+
+/// Returns the path to the calendar directory, if available.
+pub fn calendar_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    calendar_dir_in(data_dir)
+}
+
+/// Returns the path to the calendar directory, which is in the data directory returned by `data_dir` on Linux and
+/// other Unix systems.
+pub(crate) fn calendar_dir_in(
+    data_dir: impl FnOnce() -> Result<PathBuf, HomeDirError>,
+) -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(Some(match env::consts::OS {
+        "macos" => home_dir()?.join("Library").join("Calendars"),
+        "windows" => home_dir()?.join("Contacts"), // Windows only has a general PIM folder.
+        "ios" | "android" => return Ok(None),
+        _ => data_dir()?.join("evolution").join("calendar"),
+    }))
+}
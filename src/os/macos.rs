@@ -0,0 +1,120 @@
+//! Directories specific to macOS.
+
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+use crate::home_dir;
+
+/// Returns `~/Library/Mobile Documents/<name>` if it is an existing directory.
+fn mobile_documents(name: &str) -> Option<PathBuf> {
+    let dir = home_dir()
+        .ok()?
+        .join("Library")
+        .join("Mobile Documents")
+        .join(name);
+    dir.is_dir().then_some(dir)
+}
+
+/// Returns the path to iCloud Drive, `~/Library/Mobile Documents/com~apple~CloudDocs`.
+///
+/// Returns `None` if iCloud Drive is not set up. Only the well-known location is checked; no network or entitlement
+/// checks are made.
+#[must_use]
+pub fn icloud_drive_dir() -> Option<PathBuf> {
+    mobile_documents("com~apple~CloudDocs")
+}
+
+/// Returns the path to an app's iCloud container, such as `~/Library/Mobile Documents/iCloud~com~example~app` for
+/// the container identifier `iCloud.com.example.app`.
+///
+/// The `iCloud.` prefix of `container_id` is optional. Returns `None` if the container does not exist or the identifier
+/// is invalid.
+#[must_use]
+pub fn icloud_container_dir(container_id: &str) -> Option<PathBuf> {
+    let id = container_id.strip_prefix("iCloud.").unwrap_or(container_id);
+    if id.is_empty() || id.contains(['/', '\\', '\0', '~']) {
+        return None;
+    }
+
+    mobile_documents(&format!("iCloud~{}", id.replace('.', "~")))
+}
+
+/// Returns the path to an App Group container, `~/Library/Group Containers/<group_id>`, shared between an app and its
+/// helpers and extensions.
+///
+/// With the `native` feature, the path is looked up with `NSFileManager`'s
+/// `containerURLForSecurityApplicationGroupIdentifier:`, which also creates the container if needed. Otherwise, the
+/// well-known location is returned without checking that it exists. Returns `None` if the group identifier is empty or
+/// contains path separators.
+#[must_use]
+pub fn group_container_dir(group_id: &str) -> Option<PathBuf> {
+    if group_id.is_empty()
+        || group_id == "."
+        || group_id == ".."
+        || group_id.contains(['/', '\\', '\0', ':'])
+    {
+        return None;
+    }
+
+    #[cfg(feature = "native")]
+    {
+        use objc2_foundation::{NSFileManager, NSString};
+
+        let manager = NSFileManager::defaultManager();
+        let url = manager
+            .containerURLForSecurityApplicationGroupIdentifier(&NSString::from_str(group_id));
+        if let Some(path) = url.and_then(|url| url.path()) {
+            return Some(PathBuf::from(path.to_string()));
+        }
+    }
+
+    Some(
+        home_dir()
+            .ok()?
+            .join("Library")
+            .join("Group Containers")
+            .join(group_id),
+    )
+}
+
+/// Returns the per-user cache directory assigned by the system, such as `/var/folders/xx/<id>/C`.
+///
+/// This is `confstr(_CS_DARWIN_USER_CACHE_DIR)`, without the trailing slash. Returns `None` if the call fails.
+#[must_use]
+pub fn darwin_user_cache_dir() -> Option<PathBuf> {
+    confstr(libc::_CS_DARWIN_USER_CACHE_DIR)
+}
+
+/// Returns the per-user temporary directory assigned by the system, such as `/var/folders/xx/<id>/T`.
+///
+/// This is `confstr(_CS_DARWIN_USER_TEMP_DIR)`, without the trailing slash. Unlike `/tmp`, the directory is only
+/// accessible by the current user. Returns `None` if the call fails.
+#[must_use]
+pub fn darwin_user_temp_dir() -> Option<PathBuf> {
+    confstr(libc::_CS_DARWIN_USER_TEMP_DIR)
+}
+
+fn confstr(name: libc::c_int) -> Option<PathBuf> {
+    // The first call returns the buffer size needed, including the NUL terminator.
+    // SAFETY: a null buffer of length 0 is allowed and only queries the size.
+    let len = unsafe { libc::confstr(name, std::ptr::null_mut(), 0) };
+    if len == 0 {
+        return None;
+    }
+
+    let mut buf = vec![0u8; len];
+    // SAFETY: the buffer is valid for writes of its length.
+    let written = unsafe { libc::confstr(name, buf.as_mut_ptr().cast(), buf.len()) };
+    if written == 0 || written > buf.len() {
+        return None;
+    }
+
+    buf.truncate(written - 1);
+    while buf.len() > 1 && buf.ends_with(b"/") {
+        buf.pop();
+    }
+    if !buf.starts_with(b"/") {
+        return None;
+    }
+
+    Some(PathBuf::from(OsStr::from_bytes(&buf)))
+}
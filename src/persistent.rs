@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{user_dir, DirKind, HomeDirError, UserDirKind};
+
+/// A directory worth backing up, see [`persistent_dirs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistentDir {
+    kind: PersistentDirKind,
+    path: PathBuf,
+}
+
+impl PersistentDir {
+    /// Returns the kind of the directory.
+    #[must_use]
+    pub fn kind(&self) -> PersistentDirKind {
+        self.kind
+    }
+
+    /// Returns the path to the directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The kind of a [`PersistentDir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PersistentDirKind {
+    /// The config, data or state directory.
+    Base(DirKind),
+    /// A user directory, such as the documents directory.
+    User(UserDirKind),
+}
+
+/// Returns every directory worth backing up for this user.
+///
+/// These are the config, data and state directories, followed by every [user directory](UserDirKind) if
+/// `include_user_dirs` is set. The cache and runtime directories are never included, since their contents can be
+/// recreated or only live as long as the session. A directory is only listed once, under its first kind, even if
+/// another kind resolves to the same place (such as config and data on Windows) or to a symbolic link to it.
+pub fn persistent_dirs(include_user_dirs: bool) -> Result<Vec<PersistentDir>, HomeDirError> {
+    let mut dirs = Vec::new();
+    for kind in [DirKind::Config, DirKind::Data, DirKind::State] {
+        if let Some(path) = kind.resolve()? {
+            dirs.push((PersistentDirKind::Base(kind), path));
+        }
+    }
+    if include_user_dirs {
+        for kind in UserDirKind::ALL {
+            if let Some(path) = user_dir(kind)? {
+                dirs.push((PersistentDirKind::User(kind), path));
+            }
+        }
+    }
+
+    let mut seen = Vec::new();
+    Ok(dirs
+        .into_iter()
+        .filter(|(_, path)| {
+            // Directories that don't exist yet can't be aliases of each other through links.
+            let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            let new = !seen.contains(&canonical);
+            seen.push(canonical);
+            new
+        })
+        .map(|(kind, path)| PersistentDir { kind, path })
+        .collect())
+}
@@ -0,0 +1,180 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    env::{Env, ProcessEnv},
+    UserDirsError,
+};
+
+/// Directories next to the executable, for portable installations such as on a USB drive.
+///
+/// Instead of the user's home directory, the directory containing the executable is the base, with the config, data
+/// and cache directories as its `config`, `data` and `cache` subdirectories:
+///
+/// ```text
+/// myapp/
+/// ├── myapp.exe
+/// ├── cache/
+/// ├── config/
+/// └── data/
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableDirs {
+    base: PathBuf,
+}
+
+impl PortableDirs {
+    /// Uses the directory containing the current executable as the base.
+    ///
+    /// This fails with [`UserDirsError::CurrentExe`] if the executable's path can't be determined.
+    pub fn new() -> Result<PortableDirs, UserDirsError> {
+        let exe = std::env::current_exe().map_err(UserDirsError::CurrentExe)?;
+        Ok(PortableDirs::with_base(
+            exe.parent().map_or_else(PathBuf::new, Path::to_path_buf),
+        ))
+    }
+
+    /// Uses `base` as the base directory.
+    #[must_use]
+    pub fn with_base(base: impl Into<PathBuf>) -> PortableDirs {
+        PortableDirs { base: base.into() }
+    }
+
+    /// Returns the path to the base directory.
+    #[must_use]
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// Returns the path to the cache directory, `<base>/cache`.
+    #[must_use]
+    pub fn cache(&self) -> PathBuf {
+        self.base.join("cache")
+    }
+
+    /// Returns the path to the config directory, `<base>/config`.
+    #[must_use]
+    pub fn config(&self) -> PathBuf {
+        self.base.join("config")
+    }
+
+    /// Returns the path to the data directory, `<base>/data`.
+    #[must_use]
+    pub fn data(&self) -> PathBuf {
+        self.base.join("data")
+    }
+
+    /// Returns the path to the state directory, `<base>/state`.
+    #[must_use]
+    pub fn state(&self) -> PathBuf {
+        self.base.join("state")
+    }
+}
+
+/// The rules for detecting portable mode, see [`AppDirs::with_portable_mode`](crate::AppDirs::with_portable_mode).
+///
+/// Portable mode is only enabled when one of these explicitly holds:
+///
+/// - a regular file named `portable.txt` (or the name set with [`PortableOptions::marker_file`]) is next to the
+///   executable;
+/// - the `<APP>_PORTABLE` environment variable is exactly `1`, such as `MY_APP_PORTABLE=1` for `"My App"`.
+///
+/// The executable's directory is where it was invoked from, so a symbolic link to the executable can enable portable
+/// mode for its own directory; see [`PortableOptions::resolve_symlinks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableOptions {
+    marker_file: Option<String>,
+    env_var: bool,
+    resolve_symlinks: bool,
+}
+
+impl Default for PortableOptions {
+    fn default() -> Self {
+        PortableOptions {
+            marker_file: Some("portable.txt".to_owned()),
+            env_var: true,
+            resolve_symlinks: false,
+        }
+    }
+}
+
+impl PortableOptions {
+    /// Creates the default options, which check for both `portable.txt` and `<APP>_PORTABLE=1`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the marker file next to the executable, or `None` to not look for one.
+    pub fn marker_file(&mut self, name: Option<&str>) -> &mut Self {
+        self.marker_file = name.map(str::to_owned);
+        self
+    }
+
+    /// Sets whether `<APP>_PORTABLE=1` enables portable mode.
+    pub fn env_var(&mut self, enabled: bool) -> &mut Self {
+        self.env_var = enabled;
+        self
+    }
+
+    /// Sets whether the executable's directory is that of the file it resolves to, rather than the symbolic link it
+    /// was invoked through.
+    pub fn resolve_symlinks(&mut self, resolve: bool) -> &mut Self {
+        self.resolve_symlinks = resolve;
+        self
+    }
+
+    /// Returns the portable directories if portable mode is enabled for the application with the given variable
+    /// prefix.
+    pub(crate) fn detect(&self, env_prefix: &str) -> Result<Option<PortableDirs>, UserDirsError> {
+        let forced = self.env_var
+            && ProcessEnv
+                .var_os(&format!("{env_prefix}_PORTABLE"))
+                .is_some_and(|value| value == "1");
+        let exe_dir = match self.exe_dir() {
+            Ok(dir) => dir,
+            // The marker can't be looked for without the executable's directory, so this is only an error if the
+            // variable asks for portable mode.
+            Err(_) if !forced => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let marked = self
+            .marker_file
+            .as_ref()
+            .is_some_and(|name| exe_dir.join(name).is_file());
+        Ok((forced || marked).then(|| PortableDirs::with_base(exe_dir)))
+    }
+
+    fn exe_dir(&self) -> Result<PathBuf, UserDirsError> {
+        let mut exe = env::current_exe().map_err(UserDirsError::CurrentExe)?;
+        if self.resolve_symlinks {
+            exe = exe.canonicalize().map_err(UserDirsError::CurrentExe)?;
+        } else if let Some(invoked) = invoked_path() {
+            // On some platforms, `current_exe` has already resolved symbolic links, but the path the executable was
+            // invoked with has not.
+            exe = invoked;
+        }
+
+        exe.parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| UserDirsError::CurrentExe(std::io::ErrorKind::NotFound.into()))
+    }
+}
+
+/// Returns the absolute path the executable was invoked with, if it was invoked with a path rather than through
+/// `$PATH`.
+fn invoked_path() -> Option<PathBuf> {
+    let arg = PathBuf::from(env::args_os().next()?);
+    if arg
+        .parent()
+        .is_none_or(|parent| parent.as_os_str().is_empty())
+    {
+        return None;
+    }
+
+    let path = env::current_dir().ok()?.join(arg);
+    path.is_file().then_some(path)
+}
@@ -0,0 +1,100 @@
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// Returns the nearest project config named one of `names`, searching `start` and then each of its parents.
+///
+/// This is shorthand for `ProjectConfigOptions::new().find(names, start)`; see [`ProjectConfigOptions`]. Without a
+/// boundary, the search goes up to the filesystem root.
+///
+/// ```rust,no_run
+/// let cwd = std::env::current_dir()?;
+/// let config = user_dirs::find_project_config(&[".myapp.toml", ".myapp"], &cwd);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[must_use]
+pub fn find_project_config(names: &[&str], start: &Path) -> Option<PathBuf> {
+    ProjectConfigOptions::new().find(names, start)
+}
+
+/// Options for finding a per-project config, like tools such as ripgrep and prettier read before the user's.
+///
+/// Project configs override the user's configs, found with [`find_all_config_files`](crate::find_all_config_files),
+/// which in turn override the system's.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectConfigOptions {
+    boundary: Option<PathBuf>,
+    stop_at_git: bool,
+}
+
+impl ProjectConfigOptions {
+    /// Creates the default options, which search every directory up to the filesystem root.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the search below `boundary`, such as the user's home directory, which is not searched itself.
+    ///
+    /// The boundary only applies if the start directory is inside it.
+    pub fn boundary(&mut self, boundary: Option<&Path>) -> &mut Self {
+        self.boundary = boundary.map(Path::to_path_buf);
+        self
+    }
+
+    /// Stops the search at the root of a Git repository, the first directory containing `.git`.
+    ///
+    /// The repository's root is searched, so a config next to `.git` is found.
+    pub fn stop_at_git(&mut self, stop: bool) -> &mut Self {
+        self.stop_at_git = stop;
+        self
+    }
+
+    /// Returns the nearest file or directory named one of `names`, searching `start` and then each of its parents.
+    ///
+    /// Within a directory, `names` are tried in order. The search follows the real parents: symbolic links in `start`
+    /// are resolved first, so a link can't lead the search in circles. A relative `start` is relative to the current
+    /// directory.
+    #[must_use]
+    pub fn find(&self, names: &[&str], start: &Path) -> Option<PathBuf> {
+        let start = real_path(start)?;
+        let boundary = self.boundary.as_deref().and_then(real_path);
+
+        for dir in start.ancestors() {
+            if boundary.as_deref() == Some(dir) {
+                break;
+            }
+            if let Some(path) = names
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.exists())
+            {
+                return Some(path);
+            }
+            if self.stop_at_git && dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+}
+
+/// Returns `path` made absolute with symbolic links resolved, or with `.` and `..` removed if it doesn't exist.
+fn real_path(path: &Path) -> Option<PathBuf> {
+    if let Ok(path) = fs::canonicalize(path) {
+        return Some(path);
+    }
+
+    let mut real = env::current_dir().ok()?;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                real.pop();
+            }
+            component => real.push(component),
+        }
+    }
+    Some(real)
+}
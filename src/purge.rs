@@ -0,0 +1,210 @@
+//! Removing an application's directories, such as when it is uninstalled.
+
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{AppDirs, DirKind};
+
+/// Options for [`AppDirs::purge`].
+#[derive(Debug, Clone)]
+pub struct PurgeOptions {
+    dry_run: bool,
+    kinds: Vec<DirKind>,
+}
+
+impl Default for PurgeOptions {
+    fn default() -> Self {
+        PurgeOptions {
+            dry_run: false,
+            kinds: vec![
+                DirKind::Cache,
+                DirKind::Config,
+                DirKind::Data,
+                DirKind::Runtime,
+                DirKind::State,
+            ],
+        }
+    }
+}
+
+impl PurgeOptions {
+    /// Creates the default options, which remove every directory of the application.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only reports what would be removed, without removing anything.
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Removes the application's directory of the given kind, which is the default for every kind.
+    pub fn include(&mut self, kind: DirKind) -> &mut Self {
+        if kind != DirKind::Home && !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+        self
+    }
+
+    /// Keeps the application's directory of the given kind, such as its config when the user wants to reinstall.
+    pub fn exclude(&mut self, kind: DirKind) -> &mut Self {
+        self.kinds.retain(|&other| other != kind);
+        self
+    }
+}
+
+/// A directory removed by [`AppDirs::purge`], or that would be in a dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurgedDir {
+    kind: DirKind,
+    path: PathBuf,
+    size: u64,
+}
+
+impl PurgedDir {
+    /// Returns the kind of the directory.
+    #[must_use]
+    pub fn kind(&self) -> DirKind {
+        self.kind
+    }
+
+    /// Returns the path to the directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the total size of the files in the directory, in bytes, not counting the targets of symbolic links.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// What [`AppDirs::purge`] removed, or would remove in a dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurgeReport {
+    dirs: Vec<PurgedDir>,
+    dry_run: bool,
+}
+
+impl PurgeReport {
+    /// Returns the directories that existed, in the order they were removed.
+    #[must_use]
+    pub fn dirs(&self) -> &[PurgedDir] {
+        &self.dirs
+    }
+
+    /// Returns the total size of every directory, in bytes.
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.dirs.iter().map(PurgedDir::size).sum()
+    }
+
+    /// Returns whether nothing was actually removed.
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+/// This error occurs when [`AppDirs::purge`] refuses to remove or fails to remove a directory.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PurgeError {
+    /// The application's directory is not strictly inside its base directory, or the base directory is the file
+    /// system's root or the home directory, so removing it could delete files of other applications.
+    Unsafe(DirKind, PathBuf),
+    /// The directory could not be read or removed.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for PurgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsafe(kind, path) => {
+                write!(f, "refusing to remove {kind} directory {}", path.display())
+            }
+            Self::Io(path, _) => write!(f, "could not remove {}", path.display()),
+        }
+    }
+}
+
+impl Error for PurgeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Unsafe(..) => None,
+        }
+    }
+}
+
+impl AppDirs {
+    /// Removes the application's directories, as for `myapp uninstall --purge`.
+    ///
+    /// Every directory selected by `options` is checked before anything is removed: if any is not strictly inside its
+    /// base directory, or the base directory is the file system's root or the home directory (such as with
+    /// `XDG_DATA_HOME=/`), this fails with [`PurgeError::Unsafe`] without removing anything. Directories that do not
+    /// exist are skipped. Symbolic links inside the directories are removed themselves, never followed.
+    pub fn purge(&self, options: &PurgeOptions) -> Result<PurgeReport, PurgeError> {
+        let home = self.base_dirs().home();
+        let mut targets = Vec::new();
+        for &kind in &options.kinds {
+            let Some((dir, base)) = self.dir_in_base(kind) else {
+                continue;
+            };
+            let inside = dir.strip_prefix(base).is_ok_and(|rest| {
+                rest.components().next().is_some()
+                    && rest.components().all(|c| matches!(c, Component::Normal(_)))
+            });
+            if !inside || base.parent().is_none() || base == home || home.starts_with(dir) {
+                return Err(PurgeError::Unsafe(kind, dir.to_path_buf()));
+            }
+            if !targets.iter().any(|&(_, target)| target == dir) {
+                targets.push((kind, dir));
+            }
+        }
+
+        let mut dirs = Vec::new();
+        for (kind, dir) in targets {
+            let size = match tree_size(dir) {
+                Ok(size) => size,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(PurgeError::Io(dir.to_path_buf(), err)),
+            };
+            if !options.dry_run {
+                // This removes symbolic links rather than following them.
+                fs::remove_dir_all(dir).map_err(|err| PurgeError::Io(dir.to_path_buf(), err))?;
+            }
+            dirs.push(PurgedDir {
+                kind,
+                path: dir.to_path_buf(),
+                size,
+            });
+        }
+
+        Ok(PurgeReport {
+            dirs,
+            dry_run: options.dry_run,
+        })
+    }
+}
+
+/// Returns the total size of the files in `path`, without following symbolic links.
+fn tree_size(path: &Path) -> io::Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        size += tree_size(&entry?.path())?;
+    }
+    Ok(size)
+}
@@ -0,0 +1,123 @@
+//! Windows registry reads, used when the `native` or `registry` feature is enabled.
+
+use std::{
+    ffi::{OsStr, OsString},
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    ptr,
+};
+
+use windows_sys::Win32::{
+    Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS},
+    System::Registry::{RegGetValueW, HKEY, RRF_RT_REG_SZ},
+};
+
+#[cfg(feature = "native")]
+pub(crate) use windows_sys::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+/// The key holding the locations of the user's shell folders, with environment variables unexpanded.
+#[cfg(feature = "registry")]
+const USER_SHELL_FOLDERS: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\User Shell Folders";
+
+/// The maximum length of a key name, including the terminating NUL.
+#[cfg(feature = "native")]
+const MAX_KEY_LEN: u32 = 256;
+
+/// Returns the names of the subkeys of `root\path`.
+#[cfg(feature = "native")]
+pub(crate) fn subkeys(root: HKEY, path: &str) -> std::io::Result<Vec<OsString>> {
+    use std::io;
+
+    use windows_sys::Win32::{
+        Foundation::ERROR_NO_MORE_ITEMS,
+        System::Registry::{RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, KEY_READ},
+    };
+
+    let mut key = ptr::null_mut();
+    // SAFETY: `path` is NUL-terminated and `key` is a valid out pointer.
+    let result = unsafe { RegOpenKeyExW(root, wide(path).as_ptr(), 0, KEY_READ, &raw mut key) };
+    if result != ERROR_SUCCESS {
+        return Err(io::Error::from_raw_os_error(result.cast_signed()));
+    }
+
+    let (mut names, mut index) = (Vec::new(), 0);
+    let mut name = [0u16; MAX_KEY_LEN as usize];
+    let result = loop {
+        let mut len = MAX_KEY_LEN;
+        // SAFETY: `name` holds `len` characters and the optional out pointers are null.
+        let result = unsafe {
+            RegEnumKeyExW(
+                key,
+                index,
+                name.as_mut_ptr(),
+                &raw mut len,
+                ptr::null(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        if result != ERROR_SUCCESS {
+            break result;
+        }
+        names.push(OsString::from_wide(&name[..len as usize]));
+        index += 1;
+    };
+
+    // SAFETY: `key` was opened above and is not used afterwards.
+    unsafe { RegCloseKey(key) };
+    match result {
+        ERROR_NO_MORE_ITEMS => Ok(names),
+        err => Err(io::Error::from_raw_os_error(err.cast_signed())),
+    }
+}
+
+/// Reads the string value `name` of `root\path`, expanding any environment variables in it.
+pub(crate) fn string(root: HKEY, path: &str, name: &str) -> Option<OsString> {
+    let (path, name) = (wide(path), wide(name));
+    let read = |data: *mut u16, size: &mut u32| {
+        // SAFETY: `data` is either null or holds `size` bytes.
+        unsafe {
+            RegGetValueW(
+                root,
+                path.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_SZ,
+                ptr::null_mut(),
+                data.cast(),
+                size,
+            )
+        }
+    };
+
+    let mut size = 0;
+    if read(ptr::null_mut(), &mut size) != ERROR_SUCCESS {
+        return None;
+    }
+    // Expanding environment variables can need more space than the first call reported.
+    let mut data = vec![0u16; size as usize / 2];
+    loop {
+        match read(data.as_mut_ptr(), &mut size) {
+            ERROR_SUCCESS => break,
+            ERROR_MORE_DATA => data.resize(size as usize / 2, 0),
+            _ => return None,
+        }
+    }
+
+    let len = data.iter().position(|&c| c == 0).unwrap_or(data.len());
+    Some(OsString::from_wide(&data[..len]))
+}
+
+/// Reads the location of the shell folder `name`, such as `Personal` for the documents directory.
+#[cfg(feature = "registry")]
+pub(crate) fn shell_folder(name: &str) -> Option<std::path::PathBuf> {
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+
+    string(HKEY_CURRENT_USER, USER_SHELL_FOLDERS, name)
+        .filter(|value| !value.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
+fn wide(s: &str) -> Vec<u16> {
+    OsStr::new(s).encode_wide().chain([0]).collect()
+}
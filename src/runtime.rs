@@ -0,0 +1,76 @@
+//! Private, per-user directories for runtime files such as sockets, locks and scratch files.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Returns whether `name` is usable as a single path component.
+pub(crate) fn is_valid_component(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', '\0'])
+}
+
+/// Returns the private directory for `app`, without touching the file system.
+///
+/// The fallback chain is:
+///
+/// 1. `<runtime_dir>/<app>`, see [`crate::runtime_dir`];
+/// 2. if `allow_tmp` is set, [`tmp_app_dir`].
+pub(crate) fn app_dir(app: &str, allow_tmp: bool) -> Option<PathBuf> {
+    match crate::runtime_dir() {
+        Some(runtime) => Some(runtime.join(app)),
+        None => allow_tmp.then(|| tmp_app_dir(app)),
+    }
+}
+
+/// Returns the per-user directory for `app` in the temporary directory: `<temp_dir>/<app>-<uid>` on Unix and
+/// `<temp_dir>\<app>` on Windows (where the temporary directory is already per-user).
+///
+/// On macOS, the per-user temporary directory from [`darwin_user_temp_dir`](crate::os::macos::darwin_user_temp_dir)
+/// is used if available, as `<darwin_user_temp_dir>/<app>`.
+pub(crate) fn tmp_app_dir(app: &str) -> PathBuf {
+    #[cfg(target_os = "macos")]
+    if let Some(temp) = crate::os::macos::darwin_user_temp_dir() {
+        return temp.join(app);
+    }
+
+    #[cfg(unix)]
+    // SAFETY: `getuid` has no preconditions and cannot fail.
+    let name = format!("{app}-{}", unsafe { libc::getuid() });
+    #[cfg(not(unix))]
+    let name = app.to_owned();
+
+    env::temp_dir().join(name)
+}
+
+/// Creates `dir` and its parents, making sure the final directory is only accessible by the current user.
+pub(crate) fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        // The directory may have existed before, possibly created by someone else in a shared temporary directory.
+        let metadata = fs::symlink_metadata(dir)?;
+        // SAFETY: `getuid` has no preconditions and cannot fail.
+        if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is not a directory owned by the current user",
+                    dir.display()
+                ),
+            ));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+        }
+    }
+
+    Ok(())
+}
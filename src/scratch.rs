@@ -0,0 +1,214 @@
+use std::{
+    collections::hash_map::RandomState,
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io, iter, mem,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+    time::SystemTime,
+};
+
+use crate::runtime;
+
+/// How many names are tried before giving up on creating a scratch file.
+const MAX_ATTEMPTS: usize = 64;
+
+/// Creates a private scratch file for `app` in the runtime directory.
+///
+/// This is shorthand for `ScratchFileOptions::new().create(app)`; see [`ScratchFileOptions`].
+pub fn runtime_scratch_file(app: &str) -> Result<ScratchFile, ScratchFileError> {
+    ScratchFileOptions::new().create(app)
+}
+
+/// Options for creating a [`ScratchFile`].
+#[derive(Debug, Clone, Default)]
+pub struct ScratchFileOptions {
+    allow_tmp_fallback: bool,
+}
+
+impl ScratchFileOptions {
+    /// Creates the default options, which refuse to create scratch files without a runtime directory.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows falling back to a per-user directory in the temporary directory when there is no runtime directory.
+    ///
+    /// The directory is `<temp_dir>/<app>-<uid>` (mode `0700`, owned by the current user) on Unix and
+    /// `<temp_dir>\<app>` on Windows.
+    pub fn allow_tmp_fallback(&mut self, allow: bool) -> &mut Self {
+        self.allow_tmp_fallback = allow;
+        self
+    }
+
+    /// Creates a uniquely named scratch file in `<runtime_dir>/<app>/`.
+    ///
+    /// The directory is created with mode `0700` and the file is created exclusively (`O_EXCL`) with mode `0600` on
+    /// Unix, retrying with a new name if one is already taken.
+    pub fn create(&self, app: &str) -> Result<ScratchFile, ScratchFileError> {
+        if !runtime::is_valid_component(app) {
+            return Err(ScratchFileError::InvalidName(app.to_owned()));
+        }
+        let dir =
+            runtime::app_dir(app, self.allow_tmp_fallback).ok_or(ScratchFileError::NoRuntimeDir)?;
+        runtime::create_private_dir(&dir)?;
+        Ok(create_in(&dir, iter::repeat_with(unique_name))?)
+    }
+}
+
+/// Creates a file in `dir` with the first of `names` that isn't taken, trying at most [`MAX_ATTEMPTS`] names.
+fn create_in(dir: &Path, names: impl IntoIterator<Item = String>) -> io::Result<ScratchFile> {
+    for name in names.into_iter().take(MAX_ATTEMPTS) {
+        let path = dir.join(name);
+        match open_new(&path) {
+            Ok(file) => {
+                return Ok(ScratchFile {
+                    file,
+                    remove: RemoveOnDrop { path, armed: true },
+                })
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "could not find an unused scratch file name",
+    ))
+}
+
+fn open_new(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+fn unique_name() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    // `RandomState` is randomly seeded per process, which is enough to make names unpredictable.
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+
+    format!("scratch-{}-{:016x}", process::id(), hasher.finish())
+}
+
+/// A private file that is deleted when dropped, see [`runtime_scratch_file`].
+#[derive(Debug)]
+pub struct ScratchFile {
+    // The file is declared first so it is closed before it is removed, which Windows requires.
+    file: File,
+    remove: RemoveOnDrop,
+}
+
+impl ScratchFile {
+    /// Returns the open file.
+    #[must_use]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns the open file mutably.
+    pub fn file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Returns the path to the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.remove.path
+    }
+
+    /// Keeps the file instead of deleting it on drop, returning the open file and its path.
+    #[must_use]
+    pub fn keep(self) -> (File, PathBuf) {
+        let Self { file, mut remove } = self;
+        remove.armed = false;
+        (file, mem::take(&mut remove.path))
+    }
+}
+
+/// Removes the file at `path` when dropped, unless it was disarmed.
+#[derive(Debug)]
+struct RemoveOnDrop {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// This error occurs when a [`ScratchFile`] cannot be created.
+#[derive(Debug)]
+pub enum ScratchFileError {
+    /// The application name is empty or is not a single path component.
+    InvalidName(String),
+    /// There is no runtime directory and the temporary directory fallback was not allowed.
+    NoRuntimeDir,
+    /// The scratch directory or file could not be created.
+    Io(io::Error),
+}
+
+impl fmt::Display for ScratchFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "invalid application name {name:?}"),
+            Self::NoRuntimeDir => write!(f, "could not locate runtime directory"),
+            Self::Io(_) => write!(f, "could not create scratch file"),
+        }
+    }
+}
+
+impl Error for ScratchFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScratchFileError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::create_in;
+
+    #[test]
+    fn taken_names_are_skipped() {
+        let dir =
+            std::env::temp_dir().join(format!("user-dirs-scratch-retry-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("taken"), "").unwrap();
+
+        let scratch = create_in(&dir, ["taken".to_owned(), "free".to_owned()]).unwrap();
+        assert_eq!(scratch.path(), dir.join("free"));
+
+        let err = create_in(&dir, iter::repeat("taken".to_owned())).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+        drop(scratch);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
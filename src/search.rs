@@ -0,0 +1,290 @@
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    vec,
+};
+
+use crate::{
+    app_name_normalize, config_dir, data_dir,
+    env::{Env, ProcessEnv, TargetOs},
+    home_dir, HomeDirError,
+};
+
+/// Returns the data directories to search, in order of precedence.
+///
+/// This is the user's [`data_dir`] followed by the [`system_data_dirs`]. The user's directory is left out if the home
+/// directory can't be located.
+#[must_use]
+pub fn data_dirs() -> XdgDataDirsIter {
+    let mut dirs: Vec<PathBuf> = data_dir().ok().into_iter().collect();
+    dirs.extend(system_data_dirs());
+    XdgDataDirsIter(dirs.into_iter())
+}
+
+/// Returns the config directories to search, in order of precedence.
+///
+/// This is the user's [`config_dir`] followed by the [`system_config_dirs`]. The user's directory is left out if the
+/// home directory can't be located.
+#[must_use]
+pub fn config_dirs() -> XdgConfigDirsIter {
+    let mut dirs: Vec<PathBuf> = config_dir().ok().into_iter().collect();
+    dirs.extend(system_config_dirs());
+    XdgConfigDirsIter(dirs.into_iter())
+}
+
+/// An iterator over data directories, returned by [`data_dirs`] and [`system_data_dirs`].
+///
+/// The directories are determined when the iterator is created, so later changes to the environment don't affect it
+/// and its [`len`](ExactSizeIterator::len) is known up front.
+#[derive(Debug, Clone)]
+pub struct XdgDataDirsIter(vec::IntoIter<PathBuf>);
+
+impl Iterator for XdgDataDirsIter {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for XdgDataDirsIter {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// An iterator over config directories, returned by [`config_dirs`] and [`system_config_dirs`].
+///
+/// The directories are determined when the iterator is created, so later changes to the environment don't affect it
+/// and its [`len`](ExactSizeIterator::len) is known up front.
+#[derive(Debug, Clone)]
+pub struct XdgConfigDirsIter(vec::IntoIter<PathBuf>);
+
+impl Iterator for XdgConfigDirsIter {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for XdgConfigDirsIter {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Returns the data directories to search, with the entries of the application's own variable `extra_var` first.
+///
+/// This supports application-specific variables like `MYAPP_DATA_DIRS` extending the search: its entries, separated
+/// like `$PATH`, are followed by the [`data_dirs`]. Relative entries are ignored.
+pub fn data_dirs_with_extra(extra_var: &str) -> impl Iterator<Item = PathBuf> {
+    let extra: Vec<PathBuf> = ProcessEnv
+        .var_os(extra_var)
+        .map(|value| env::split_paths(&value).collect())
+        .unwrap_or_default();
+
+    extra
+        .into_iter()
+        .filter(|dir| dir.is_absolute())
+        .chain(data_dirs())
+}
+
+/// Returns the system-wide data directories, in order of precedence.
+///
+/// These are the entries of `$XDG_DATA_DIRS`, or `/usr/local/share` and `/usr/share` if it is unset or empty. There
+/// are no default system data directories on Windows.
+#[must_use]
+pub fn system_data_dirs() -> XdgDataDirsIter {
+    XdgDataDirsIter(search_path(
+        "XDG_DATA_DIRS",
+        &["/usr/local/share", "/usr/share"],
+    ))
+}
+
+/// Returns the system-wide config directories, in order of precedence.
+///
+/// These are the entries of `$XDG_CONFIG_DIRS`, or `/etc/xdg` if it is unset or empty. There are no default system
+/// config directories on Windows.
+#[must_use]
+pub fn system_config_dirs() -> XdgConfigDirsIter {
+    XdgConfigDirsIter(search_path("XDG_CONFIG_DIRS", &["/etc/xdg"]))
+}
+
+/// Returns the config directories of the application `app` to search, in order of precedence.
+///
+/// These are the application's subdirectory of the user's [`config_dir`], then of each of the
+/// [`system_config_dirs`], then `/etc/<app>` except on Windows. The name is normalized with
+/// [`app_name_normalize`], and there are no directories if nothing is left of it. A directory listed more than once
+/// is only yielded the first time, and the user's directory is left out if the home directory can't be located.
+///
+/// ```rust
+/// for dir in user_dirs::cfg_dirs_for("My App") {
+///     println!("{}", dir.join("config.toml").display());
+/// }
+/// ```
+pub fn cfg_dirs_for(app: &str) -> impl Iterator<Item = PathBuf> {
+    let app = app_name_normalize(app);
+    let mut dirs = Vec::new();
+    if !app.is_empty() {
+        dirs.extend(config_dir().ok());
+        dirs.extend(system_config_dirs());
+        for dir in &mut dirs {
+            dir.push(&app);
+        }
+        if !cfg!(windows) {
+            dirs.push(Path::new("/etc").join(&app));
+        }
+    }
+
+    dedup_dirs(&mut dirs, false);
+    dirs.into_iter()
+}
+
+/// Returns every existing config file at `relative` in the config directories, highest precedence first.
+///
+/// The user's config directory comes first, followed by each entry of `$XDG_CONFIG_DIRS`. To layer configs, apply the
+/// files in reverse order so that earlier ones override later ones. A directory listed more than once is only searched
+/// the first time. See [`config_files`] for a lazy version.
+pub fn find_all_config_files(relative: impl AsRef<Path>) -> Vec<PathBuf> {
+    config_files(relative).collect()
+}
+
+/// Lazily yields every existing config file at `relative` in the config directories, highest precedence first.
+///
+/// This is the lazy version of [`find_all_config_files`], for when only the first few layers are needed.
+pub fn config_files(relative: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
+    let relative = relative.as_ref().to_path_buf();
+    let mut seen = HashSet::new();
+
+    config_dirs()
+        .filter(move |dir| seen.insert(dir.clone()))
+        .map(move |dir| dir.join(&relative))
+        .filter(|file| file.is_file())
+}
+
+/// Returns every directory fonts can be installed in, user directories before system ones.
+///
+/// | Platform | Directories                                                                      |
+/// | -------- | -------------------------------------------------------------------------------- |
+/// | Linux    | `<data_dir>/fonts`, `~/.fonts`, then `<dir>/fonts` for each [`system_data_dirs`] |
+/// | macOS    | `~/Library/Fonts`, `/Library/Fonts`, `/System/Library/Fonts`                     |
+/// | Windows  | `%LOCALAPPDATA%\Microsoft\Windows\Fonts`, `%WINDIR%\Fonts`                       |
+///
+/// If `existing_only` is set, directories that don't exist are left out.
+#[must_use]
+pub fn font_search_paths(existing_only: bool) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = match env::consts::OS {
+        "macos" => home_dir()
+            .ok()
+            .map(|home| home.join("Library").join("Fonts"))
+            .into_iter()
+            .chain(["/Library/Fonts", "/System/Library/Fonts"].map(PathBuf::from))
+            .collect(),
+        "windows" => {
+            let windir = crate::env::var(TargetOs::Windows, &ProcessEnv, "WINDIR")
+                .map_or_else(|| PathBuf::from(r"C:\Windows"), PathBuf::from);
+            crate::os::cache_dir()
+                .ok()
+                .map(|local| local.join("Microsoft").join("Windows").join("Fonts"))
+                .into_iter()
+                .chain([windir.join("Fonts")])
+                .collect()
+        }
+        _ => return data_subdir_search_paths("fonts", Some(".fonts"), existing_only),
+    };
+
+    dedup_dirs(&mut dirs, existing_only);
+    dirs
+}
+
+/// Returns the path to the user's themes directory, `<data_dir>/themes`, if available.
+///
+/// GTK and icon themes are only installed here on Linux and other XDG platforms, so there is no themes directory on
+/// macOS and Windows.
+pub fn themes_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(match env::consts::OS {
+        "macos" | "windows" => None,
+        _ => Some(data_dir()?.join("themes")),
+    })
+}
+
+/// Returns every directory themes can be installed in, user directories before system ones.
+///
+/// This is the [`themes_dir`], then the legacy `~/.themes`, then `<dir>/themes` for each [`system_data_dirs`]. There
+/// are no such directories on macOS and Windows. If `existing_only` is set, directories that don't exist are left out.
+#[must_use]
+pub fn themes_search_paths(existing_only: bool) -> Vec<PathBuf> {
+    match env::consts::OS {
+        "macos" | "windows" => Vec::new(),
+        _ => data_subdir_search_paths("themes", Some(".themes"), existing_only),
+    }
+}
+
+/// Returns the path to the user's sound themes directory, `<data_dir>/sounds`, if available.
+///
+/// This is where the freedesktop sound theme specification looks for event sounds, so there is no sounds directory
+/// on macOS and Windows.
+pub fn sounds_dir() -> Result<Option<PathBuf>, HomeDirError> {
+    Ok(match env::consts::OS {
+        "macos" | "windows" => None,
+        _ => Some(data_dir()?.join("sounds")),
+    })
+}
+
+/// Returns every directory sound themes can be installed in, user directories before system ones.
+///
+/// This is the [`sounds_dir`], then `<dir>/sounds` for each [`system_data_dirs`]. There are no such directories on
+/// macOS and Windows. If `existing_only` is set, directories that don't exist are left out.
+#[must_use]
+pub fn sounds_search_paths(existing_only: bool) -> Vec<PathBuf> {
+    match env::consts::OS {
+        "macos" | "windows" => Vec::new(),
+        _ => data_subdir_search_paths("sounds", None, existing_only),
+    }
+}
+
+/// Returns `subdir` in the user's data directory, then the legacy `~/<legacy>` directory, then `subdir` in each system
+/// data directory.
+fn data_subdir_search_paths(
+    subdir: &str,
+    legacy: Option<&str>,
+    existing_only: bool,
+) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    dirs.extend(data_dir().ok().map(|dir| dir.join(subdir)));
+    if let Some(legacy) = legacy {
+        dirs.extend(home_dir().ok().map(|home| home.join(legacy)));
+    }
+    dirs.extend(system_data_dirs().map(|dir| dir.join(subdir)));
+
+    dedup_dirs(&mut dirs, existing_only);
+    dirs
+}
+
+/// Removes repeated directories, and those that don't exist if `existing_only` is set.
+fn dedup_dirs(dirs: &mut Vec<PathBuf>, existing_only: bool) {
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()) && (!existing_only || dir.is_dir()));
+}
+
+/// Splits a search path variable, ignoring relative entries as the XDG specification requires.
+fn search_path(var: &str, default: &[&str]) -> vec::IntoIter<PathBuf> {
+    let mut dirs: Vec<PathBuf> = match ProcessEnv.var_os(var).filter(|value| !value.is_empty()) {
+        Some(value) => env::split_paths(&value).collect(),
+        None if cfg!(windows) => Vec::new(),
+        None => default.iter().map(PathBuf::from).collect(),
+    };
+
+    dirs.retain(|dir| dir.is_absolute());
+    dirs.into_iter()
+}
@@ -0,0 +1,43 @@
+//! Support for applications packaged as Snaps.
+
+use std::path::PathBuf;
+
+use crate::env::{self, Env, ProcessEnv, TargetOs};
+
+/// Which directory the default directories are based on when running as a Snap package, see
+/// [`UserDirsBuilder::snap_strategy`](crate::UserDirsBuilder::snap_strategy).
+///
+/// Confined Snaps run with `$HOME` set to a private directory of the Snap. The strategy only applies when `$SNAP` is
+/// set, and XDG variables still take precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum SnapStrategy {
+    /// Base the directories on `$HOME`, as outside of a Snap.
+    #[default]
+    Home,
+    /// Base the directories on `$SNAP_USER_DATA`, which is specific to the Snap's revision and copied on refresh.
+    UserData,
+    /// Base the directories on `$SNAP_USER_COMMON`, which is shared by every revision of the Snap.
+    UserCommon,
+}
+
+impl SnapStrategy {
+    /// Returns the directory to use instead of the home directory, if running as a Snap and the strategy changes it.
+    pub(crate) fn base(self, env: &impl Env) -> Option<PathBuf> {
+        let var = match self {
+            Self::Home => return None,
+            Self::UserData => "SNAP_USER_DATA",
+            Self::UserCommon => "SNAP_USER_COMMON",
+        };
+        env.var_os("SNAP")?;
+        env.var_os(var)
+            .filter(|value| env::is_absolute(TargetOs::Linux, value))
+            .map(PathBuf::from)
+    }
+}
+
+/// Returns whether the process runs as a Snap package, which is when `$SNAP` is set.
+#[must_use]
+pub fn is_snap() -> bool {
+    ProcessEnv.var_os("SNAP").is_some()
+}
@@ -0,0 +1,546 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    fs,
+    hash::BuildHasher,
+    io,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+
+use crate::{
+    env::{self, Env, ProcessEnv, TargetOs},
+    home_dir, unicode_path, DirKind, SnapStrategy, UserDirsError,
+};
+
+/// A snapshot of every resolved directory.
+///
+/// The directories are resolved once, when the snapshot is created, and do not change afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserDirs {
+    home: PathBuf,
+    cache: PathBuf,
+    config: PathBuf,
+    data: PathBuf,
+    runtime: Option<PathBuf>,
+    state: Option<PathBuf>,
+    from_xdg: Vec<DirKind>,
+    overridden: Vec<DirKind>,
+    options: UserDirsBuilder,
+}
+
+impl UserDirs {
+    /// Resolves every directory.
+    ///
+    /// This is the same as building with the default [`UserDirsBuilder`].
+    #[must_use = "computing user directories without using them is a no-op"]
+    pub fn new() -> Result<UserDirs, UserDirsError> {
+        UserDirsBuilder::new().build()
+    }
+
+    /// Returns a builder for customizing how the directories are resolved.
+    #[must_use]
+    pub fn builder() -> UserDirsBuilder {
+        UserDirsBuilder::new()
+    }
+
+    /// Returns the platform's default directories for the home directory `home`, ignoring the environment.
+    ///
+    /// No variable is read, so there is no runtime directory, and on Windows the directories are in `<home>\AppData`
+    /// regardless of `%APPDATA%`. This is meant for tests, together with [`UserDirs::copy_to`].
+    ///
+    /// ```rust,no_run
+    /// let tmp = std::env::temp_dir().join("my-test");
+    /// let dirs = user_dirs::UserDirs::default_for_home(&tmp).copy_to(&tmp)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[must_use]
+    pub fn default_for_home(home: &Path) -> UserDirs {
+        let os = TargetOs::current();
+        let default =
+            |kind| env::os_dir(kind, os, home, &HashMap::<&str, &str>::new()).unwrap_or_default();
+
+        UserDirs {
+            home: home.to_path_buf(),
+            cache: default(DirKind::Cache),
+            config: default(DirKind::Config),
+            data: default(DirKind::Data),
+            runtime: None,
+            state: env::os_state_dir(os, home),
+            from_xdg: Vec::new(),
+            overridden: Vec::new(),
+            options: UserDirsBuilder::new(),
+        }
+    }
+
+    /// Creates every directory of this snapshot under `dest`, returning the snapshot of the created directories.
+    ///
+    /// `dest` takes the place of the home directory. A directory inside the home directory keeps its place relative
+    /// to it, and any other is placed at its absolute path inside `dest`, so `/run/user/1000` becomes
+    /// `<dest>/run/user/1000`. The directories are created empty, mirroring the real structure in an isolated tree for
+    /// integration tests. The sources of the directories are kept.
+    pub fn copy_to(&self, dest: &Path) -> io::Result<UserDirs> {
+        let rebase = |path: &Path| {
+            let dir = match path.strip_prefix(&self.home) {
+                Ok(rest) => dest.join(rest),
+                Err(_) => dest.join(
+                    path.components()
+                        .filter(|component| matches!(component, Component::Normal(_)))
+                        .collect::<PathBuf>(),
+                ),
+            };
+            fs::create_dir_all(&dir)?;
+            Ok::<_, io::Error>(dir)
+        };
+
+        Ok(UserDirs {
+            home: rebase(&self.home)?,
+            cache: rebase(&self.cache)?,
+            config: rebase(&self.config)?,
+            data: rebase(&self.data)?,
+            runtime: self.runtime.as_deref().map(rebase).transpose()?,
+            state: self.state.as_deref().map(rebase).transpose()?,
+            ..self.clone()
+        })
+    }
+
+    /// Resolves every directory again, with the same options, and updates this snapshot in place.
+    ///
+    /// This is useful for long-running daemons that reload their configuration, e.g. on `SIGHUP`. If resolution fails,
+    /// the snapshot is left unchanged. Directories changed with setters such as [`UserDirs::set_data`] are resolved
+    /// again too.
+    pub fn reload(&mut self) -> Result<(), UserDirsError> {
+        *self = self.options.build()?;
+        Ok(())
+    }
+
+    /// Wraps this snapshot in an [`Arc`] for sharing across threads.
+    ///
+    /// This is the same as [`Arc::new`], but states the intent: the snapshot is no longer modified, only shared.
+    #[must_use]
+    pub fn freeze(self) -> Arc<UserDirs> {
+        Arc::new(self)
+    }
+
+    /// Checks that every directory in this snapshot exists on disk, collecting an error for each one that doesn't.
+    ///
+    /// A directory that is missing or inaccessible reports the error from reading its metadata, and a path that is not
+    /// a directory reports [`io::ErrorKind::NotADirectory`]. Unavailable directories are skipped.
+    pub fn try_all_exist(&self) -> Result<(), Vec<(DirKind, io::Error)>> {
+        let errors: Vec<_> = DirKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let path = self.get(kind)?;
+                match fs::metadata(path) {
+                    Ok(metadata) if metadata.is_dir() => None,
+                    Ok(_) => Some((
+                        kind,
+                        io::Error::new(
+                            io::ErrorKind::NotADirectory,
+                            format!("{} is not a directory", path.display()),
+                        ),
+                    )),
+                    Err(err) => Some((kind, err)),
+                }
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns whether any directory differs between this snapshot and `other`.
+    ///
+    /// Only the resolved paths are compared, not the options used to resolve them.
+    #[must_use]
+    pub fn has_changed_since(&self, other: &UserDirs) -> bool {
+        DirKind::ALL
+            .into_iter()
+            .any(|kind| self.get(kind) != other.get(kind))
+    }
+
+    /// Returns where the directory of the given kind came from, or `None` for the home directory and directories that
+    /// are unavailable.
+    #[must_use]
+    pub fn source(&self, kind: DirKind) -> Option<DirSource> {
+        if kind == DirKind::Home || self.get(kind).is_none() {
+            None
+        } else if self.overridden.contains(&kind) {
+            Some(DirSource::Override)
+        } else if self.from_xdg.contains(&kind) {
+            Some(DirSource::Xdg)
+        } else {
+            Some(DirSource::Default)
+        }
+    }
+
+    /// Returns whether every directory is the platform's default, with no XDG variable or override in effect.
+    ///
+    /// The runtime directory is not considered, since it only ever comes from `$XDG_RUNTIME_DIR`, which the system
+    /// usually sets. This is meant for diagnostics such as "You are using the default directory locations".
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        DirKind::ALL
+            .into_iter()
+            .filter(|&kind| kind != DirKind::Runtime)
+            .all(|kind| matches!(self.source(kind), None | Some(DirSource::Default)))
+    }
+
+    /// Returns a human-readable listing of every directory and where it came from, one per line.
+    ///
+    /// ```text
+    /// HOME     = /home/leah
+    /// CACHE    = /home/leah/.cache [XDG]
+    /// CONFIG   = /home/leah/.config [DEFAULT]
+    /// ```
+    #[must_use]
+    pub fn to_debug_string(&self) -> String {
+        let mut out = String::new();
+        for kind in DirKind::ALL {
+            let name = kind.to_string().to_uppercase();
+            let _ = match (self.get(kind), self.source(kind)) {
+                (None, _) => writeln!(out, "{name:<8} = (unavailable)"),
+                (Some(path), None) => writeln!(out, "{name:<8} = {}", path.display()),
+                (Some(path), Some(source)) => {
+                    writeln!(out, "{name:<8} = {} [{source}]", path.display())
+                }
+            };
+        }
+        out
+    }
+
+    /// Returns the XDG variables describing this snapshot, such as `("XDG_CONFIG_HOME", config)`.
+    ///
+    /// Every available directory except the home directory is included, including platform defaults, so a process
+    /// given these variables resolves the same directories without applying its own defaults.
+    #[must_use]
+    pub fn to_env_vars(&self) -> Vec<(&'static str, &Path)> {
+        DirKind::ALL
+            .into_iter()
+            .filter_map(|kind| Some((env::xdg_var(kind)?, self.get(kind)?)))
+            .collect()
+    }
+
+    /// Sets the XDG variables of `cmd` to the directories of this snapshot, see [`UserDirs::to_env_vars`].
+    ///
+    /// This lets a subprocess inherit the resolved directories as they are, instead of resolving its own.
+    pub fn apply_to_command(&self, cmd: &mut Command) {
+        cmd.envs(self.to_env_vars());
+    }
+
+    /// Sets the cache directory, see [`UserDirs::set_data`].
+    pub fn set_cache(&mut self, path: PathBuf) -> Result<(), UserDirsError> {
+        self.cache = self.overriding(DirKind::Cache, path)?;
+        Ok(())
+    }
+
+    /// Sets the config directory, see [`UserDirs::set_data`].
+    pub fn set_config(&mut self, path: PathBuf) -> Result<(), UserDirsError> {
+        self.config = self.overriding(DirKind::Config, path)?;
+        Ok(())
+    }
+
+    /// Sets the data directory, such as from a per-project override in a config file.
+    ///
+    /// The path must be absolute, or this fails with [`UserDirsError::NotAbsolute`] and leaves the snapshot unchanged.
+    /// Afterwards, [`UserDirs::source`] reports [`DirSource::Override`] for the directory.
+    pub fn set_data(&mut self, path: PathBuf) -> Result<(), UserDirsError> {
+        self.data = self.overriding(DirKind::Data, path)?;
+        Ok(())
+    }
+
+    /// Sets the runtime directory, see [`UserDirs::set_data`].
+    pub fn set_runtime(&mut self, path: PathBuf) -> Result<(), UserDirsError> {
+        self.runtime = Some(self.overriding(DirKind::Runtime, path)?);
+        Ok(())
+    }
+
+    /// Sets the state directory, see [`UserDirs::set_data`].
+    pub fn set_state(&mut self, path: PathBuf) -> Result<(), UserDirsError> {
+        self.state = Some(self.overriding(DirKind::State, path)?);
+        Ok(())
+    }
+
+    /// Validates `path` as the new directory of the given kind, and records that it was overridden.
+    fn overriding(&mut self, kind: DirKind, path: PathBuf) -> Result<PathBuf, UserDirsError> {
+        if !path.is_absolute() {
+            return Err(UserDirsError::NotAbsolute(kind, path));
+        }
+        let path = unicode_path(path)?;
+        if !self.overridden.contains(&kind) {
+            self.overridden.push(kind);
+        }
+        Ok(path)
+    }
+
+    /// Returns the path to the home directory.
+    #[must_use]
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// Returns the path to the cache directory.
+    #[must_use]
+    pub fn cache(&self) -> &Path {
+        &self.cache
+    }
+
+    /// Returns the path to the config directory.
+    #[must_use]
+    pub fn config(&self) -> &Path {
+        &self.config
+    }
+
+    /// Returns the path to the config directory, falling back instead of failing.
+    ///
+    /// This is [`UserDirs::config`], or `.config` in [`UserDirs::home`] if the config directory is not an absolute path
+    /// (such as with [`UserDirs::default_for_home`] and a relative home), or `.` (the current directory) if neither is.
+    /// It is meant for command-line tools that must always be able to run, see [`config_or_home`](crate::config_or_home).
+    #[must_use]
+    pub fn config_or_home(&self) -> PathBuf {
+        if self.config.is_absolute() {
+            self.config.clone()
+        } else if self.home.is_absolute() {
+            self.home.join(".config")
+        } else {
+            PathBuf::from(".")
+        }
+    }
+
+    /// Returns the path to the data directory.
+    #[must_use]
+    pub fn data(&self) -> &Path {
+        &self.data
+    }
+
+    /// Returns the path to the runtime directory, if available.
+    #[must_use]
+    pub fn runtime(&self) -> Option<&Path> {
+        self.runtime.as_deref()
+    }
+
+    /// Returns the path to the state directory, if available.
+    #[must_use]
+    pub fn state(&self) -> Option<&Path> {
+        self.state.as_deref()
+    }
+
+    /// Returns the path to the directory of the given kind, if available.
+    #[must_use]
+    pub fn get(&self, kind: DirKind) -> Option<&Path> {
+        match kind {
+            DirKind::Home => Some(&self.home),
+            DirKind::Cache => Some(&self.cache),
+            DirKind::Config => Some(&self.config),
+            DirKind::Data => Some(&self.data),
+            DirKind::Runtime => self.runtime.as_deref(),
+            DirKind::State => self.state.as_deref(),
+        }
+    }
+}
+
+/// A [`UserDirs`] snapshot whose [`Debug`](fmt::Debug) output hides the home directory, for logging.
+///
+/// Every path inside the home directory is printed relative to a `<HOME>` placeholder, so `/home/alice/.config`
+/// becomes `<HOME>/.config` and the user name doesn't end up in logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SensitiveDirs(UserDirs);
+
+impl SensitiveDirs {
+    /// Wraps a snapshot.
+    #[must_use]
+    pub fn new(dirs: UserDirs) -> SensitiveDirs {
+        SensitiveDirs(dirs)
+    }
+
+    /// Returns the snapshot with the original paths.
+    #[must_use]
+    pub fn inner(&self) -> &UserDirs {
+        &self.0
+    }
+}
+
+impl From<UserDirs> for SensitiveDirs {
+    fn from(dirs: UserDirs) -> Self {
+        SensitiveDirs(dirs)
+    }
+}
+
+impl fmt::Debug for SensitiveDirs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Redacted<'a>(&'a Path, &'a Path);
+
+        impl fmt::Debug for Redacted<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0.strip_prefix(self.1) {
+                    Ok(rest) if rest.as_os_str().is_empty() => fmt::Debug::fmt("<HOME>", f),
+                    Ok(rest) => fmt::Debug::fmt(&Path::new("<HOME>").join(rest), f),
+                    Err(_) => fmt::Debug::fmt(self.0, f),
+                }
+            }
+        }
+
+        let (dirs, home) = (&self.0, self.0.home());
+        f.debug_struct("SensitiveDirs")
+            .field("home", &Redacted(home, home))
+            .field("cache", &Redacted(dirs.cache(), home))
+            .field("config", &Redacted(dirs.config(), home))
+            .field("data", &Redacted(dirs.data(), home))
+            .field("runtime", &dirs.runtime().map(|path| Redacted(path, home)))
+            .field("state", &dirs.state().map(|path| Redacted(path, home)))
+            .finish()
+    }
+}
+
+/// Where a directory in a [`UserDirs`] snapshot came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DirSource {
+    /// The directory was set by its XDG variable.
+    Xdg,
+    /// The platform's default was used.
+    Default,
+    /// The directory was set with a setter such as [`UserDirs::set_data`], or with [`env::set_override`].
+    Override,
+    /// The application's directory was set by its own variable, see [`AppDirs::with_env_overrides`](crate::AppDirs::with_env_overrides).
+    AppEnv,
+}
+
+impl fmt::Display for DirSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Xdg => "XDG",
+            Self::Default => "DEFAULT",
+            Self::Override => "OVERRIDE",
+            Self::AppEnv => "APP_ENV",
+        })
+    }
+}
+
+/// A builder for a [`UserDirs`] snapshot, customizing how the directories are resolved.
+///
+/// ```rust
+/// let dirs = user_dirs::UserDirs::builder().expand_tilde_in_xdg(true).build()?;
+/// # Ok::<(), user_dirs::UserDirsError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserDirsBuilder {
+    expand_tilde_in_xdg: bool,
+    snap_strategy: SnapStrategy,
+}
+
+impl UserDirsBuilder {
+    /// Creates a builder with the default options.
+    #[must_use]
+    pub fn new() -> UserDirsBuilder {
+        UserDirsBuilder::default()
+    }
+
+    /// Sets whether a leading `~/` in XDG variables is expanded to the home directory.
+    ///
+    /// Values such as `XDG_CONFIG_HOME=~/.config` are a common mistake, since shells don't expand a tilde inside
+    /// quotes or in most configuration files. By default, such values are taken literally.
+    #[must_use]
+    pub fn expand_tilde_in_xdg(mut self, expand: bool) -> UserDirsBuilder {
+        self.expand_tilde_in_xdg = expand;
+        self
+    }
+
+    /// Sets which directory the default directories are based on when running as a Snap package.
+    ///
+    /// By default, they are based on `$HOME` like anywhere else. The home directory of the snapshot is not changed.
+    #[must_use]
+    pub fn snap_strategy(mut self, strategy: SnapStrategy) -> UserDirsBuilder {
+        self.snap_strategy = strategy;
+        self
+    }
+
+    /// Resolves every directory using these options.
+    #[must_use = "computing user directories without using them is a no-op"]
+    pub fn build(&self) -> Result<UserDirs, UserDirsError> {
+        let home = home_dir()?;
+        let os = TargetOs::current();
+        let base = self.snap_strategy.base(&ProcessEnv);
+        let base = base.as_deref().unwrap_or(&home);
+        let mut from_xdg = Vec::new();
+        let mut overridden = Vec::new();
+
+        let mut resolve = |kind| {
+            if let Some(dir) = env::overridden(kind) {
+                overridden.push(kind);
+                return Some(dir);
+            }
+            let xdg = self
+                .expand_tilde(kind, &home)
+                .or_else(|| env::xdg_dir(kind, os, &ProcessEnv));
+            match xdg {
+                Some(dir) => {
+                    from_xdg.push(kind);
+                    Some(dir)
+                }
+                None if kind == DirKind::Runtime => None,
+                None => env::os_dir(kind, os, base, &ProcessEnv),
+            }
+        };
+        let mut optional = |kind| resolve(kind).map(unicode_path).transpose();
+        let mut required = |kind| optional(kind)?.ok_or(UserDirsError::Unavailable(kind));
+
+        Ok(UserDirs {
+            cache: required(DirKind::Cache)?,
+            config: required(DirKind::Config)?,
+            data: required(DirKind::Data)?,
+            runtime: optional(DirKind::Runtime)?,
+            state: optional(DirKind::State)?,
+            from_xdg,
+            overridden,
+            home: unicode_path(home)?,
+            options: self.clone(),
+        })
+    }
+
+    /// Returns the XDG variable for the given kind with a leading `~` expanded, if enabled and applicable.
+    fn expand_tilde(&self, kind: DirKind, home: &Path) -> Option<PathBuf> {
+        if !self.expand_tilde_in_xdg {
+            return None;
+        }
+
+        let value = ProcessEnv.var_os(env::xdg_var(kind)?)?;
+        let rest = Path::new(&value).strip_prefix("~").ok()?;
+        Some(if rest.as_os_str().is_empty() {
+            home.to_path_buf()
+        } else {
+            home.join(rest)
+        })
+    }
+}
+
+/// Collects the available directories, omitting those that are unavailable on this platform.
+impl<S: BuildHasher + Default> From<UserDirs> for HashMap<DirKind, PathBuf, S> {
+    fn from(dirs: UserDirs) -> Self {
+        let UserDirs {
+            home,
+            cache,
+            config,
+            data,
+            runtime,
+            state,
+            ..
+        } = dirs;
+
+        [
+            (DirKind::Home, Some(home)),
+            (DirKind::Cache, Some(cache)),
+            (DirKind::Config, Some(config)),
+            (DirKind::Data, Some(data)),
+            (DirKind::Runtime, runtime),
+            (DirKind::State, state),
+        ]
+        .into_iter()
+        .filter_map(|(kind, path)| Some((kind, path?)))
+        .collect()
+    }
+}
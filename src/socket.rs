@@ -0,0 +1,82 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use crate::runtime;
+
+/// The maximum length of a socket path in bytes, excluding the trailing NUL of `sockaddr_un.sun_path`.
+#[cfg(any(target_os = "linux", target_os = "android", windows))]
+const MAX_SOCKET_PATH_LEN: usize = 107;
+#[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+const MAX_SOCKET_PATH_LEN: usize = 103;
+
+/// Returns the path for a Unix domain socket named `<name>.sock` belonging to `app`, creating its parent directory.
+///
+/// The socket lives at `<runtime_dir>/<app>/<name>.sock`. Without a runtime directory, it is placed in a per-user
+/// directory in the temporary directory instead (`<temp_dir>/<app>-<uid>` on Unix, `<temp_dir>\<app>` on Windows).
+/// The parent directory is created with mode `0700` on Unix.
+///
+/// The length of the path is checked against the platform's `sun_path` limit before anything is created, so callers
+/// can fall back to an abstract socket or a shorter location when [`SocketPathError::TooLong`] is returned.
+pub fn socket_path(app: &str, name: &str) -> Result<PathBuf, SocketPathError> {
+    if !runtime::is_valid_component(app) {
+        return Err(SocketPathError::InvalidName(app.to_owned()));
+    }
+    if !runtime::is_valid_component(name) {
+        return Err(SocketPathError::InvalidName(name.to_owned()));
+    }
+
+    let dir = runtime::app_dir(app, false).unwrap_or_else(|| runtime::tmp_app_dir(app));
+    let path = dir.join(format!("{name}.sock"));
+
+    let len = path.as_os_str().len();
+    if len > MAX_SOCKET_PATH_LEN {
+        return Err(SocketPathError::TooLong {
+            path,
+            len,
+            max: MAX_SOCKET_PATH_LEN,
+        });
+    }
+
+    runtime::create_private_dir(&dir).map_err(SocketPathError::Io)?;
+    Ok(path)
+}
+
+/// This error occurs when a socket path cannot be created.
+#[derive(Debug)]
+pub enum SocketPathError {
+    /// The application or socket name is empty or is not a single path component.
+    InvalidName(String),
+    /// The socket path is longer than the platform allows.
+    TooLong {
+        /// The computed socket path.
+        path: PathBuf,
+        /// The length of the path in bytes.
+        len: usize,
+        /// The maximum length of a socket path on this platform in bytes.
+        max: usize,
+    },
+    /// The parent directory of the socket could not be created.
+    Io(io::Error),
+}
+
+impl fmt::Display for SocketPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "invalid socket path component {name:?}"),
+            Self::TooLong { path, len, max } => write!(
+                f,
+                "socket path {} is {len} bytes long, exceeding the limit of {max} bytes",
+                path.display()
+            ),
+            Self::Io(_) => write!(f, "could not create socket directory"),
+        }
+    }
+}
+
+impl Error for SocketPathError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
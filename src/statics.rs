@@ -0,0 +1,63 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{cache_dir, config_dir, data_dir, home_dir, HomeDirError};
+
+/// Returns the path to the home directory, located once for the lifetime of the process.
+///
+/// Later changes to the environment are not picked up, and a failure to locate the home directory is remembered too.
+/// See [`home_dir_static`] for a version that panics instead.
+pub fn home_path() -> Result<&'static Path, HomeDirError> {
+    static DIR: OnceLock<Result<PathBuf, HomeDirError>> = OnceLock::new();
+    match DIR.get_or_init(home_dir) {
+        Ok(dir) => Ok(dir),
+        Err(err) => Err(*err),
+    }
+}
+
+/// Returns the path to the home directory, resolved once for the lifetime of the process.
+///
+/// Later changes to the environment are not picked up.
+///
+/// # Panics
+///
+/// Panics if the home directory cannot be located, see [`home_dir`].
+#[must_use]
+pub fn home_dir_static() -> &'static Path {
+    home_path().expect("failed to locate the home directory")
+}
+
+/// Returns the path to the cache directory, resolved once for the lifetime of the process, see [`home_dir_static`].
+///
+/// # Panics
+///
+/// Panics if the cache directory cannot be located, see [`cache_dir`].
+#[must_use]
+pub fn cache_dir_static() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| cache_dir().expect("failed to locate the cache directory"))
+}
+
+/// Returns the path to the config directory, resolved once for the lifetime of the process, see [`home_dir_static`].
+///
+/// # Panics
+///
+/// Panics if the config directory cannot be located, see [`config_dir`].
+#[must_use]
+pub fn config_dir_static() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| config_dir().expect("failed to locate the config directory"))
+}
+
+/// Returns the path to the data directory, resolved once for the lifetime of the process, see [`home_dir_static`].
+///
+/// # Panics
+///
+/// Panics if the data directory cannot be located, see [`data_dir`].
+#[must_use]
+pub fn data_dir_static() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| data_dir().expect("failed to locate the data directory"))
+}
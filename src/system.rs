@@ -0,0 +1,124 @@
+//! Directory functions for system services, as opposed to user sessions.
+//!
+//! When a program runs as a system-wide daemon, the user directories are the wrong place for its files. These
+//! functions return the conventional system locations for the application `app` instead:
+//!
+//! | Function        | Linux              | macOS                                | Windows                      |
+//! | --------------- | ------------------ | ------------------------------------ | ---------------------------- |
+//! | [`config_dir`]  | `/etc/<app>`       | `/Library/Preferences/<app>`         | `%PROGRAMDATA%\<app>\config` |
+//! | [`state_dir`]   | `/var/lib/<app>`   | `/Library/Application Support/<app>` | `%PROGRAMDATA%\<app>\data`   |
+//! | [`cache_dir`]   | `/var/cache/<app>` | `/Library/Caches/<app>`              | `%PROGRAMDATA%\<app>\cache`  |
+//! | [`log_dir`]     | `/var/log/<app>`   | `/Library/Logs/<app>`                | `%PROGRAMDATA%\<app>\logs`   |
+//! | [`runtime_dir`] | `/run/<app>`       | `/var/run/<app>`                     | `%PROGRAMDATA%\<app>\run`    |
+//!
+//! When systemd provides a directory through `$CONFIGURATION_DIRECTORY`, `$STATE_DIRECTORY`, `$CACHE_DIRECTORY`,
+//! `$LOGS_DIRECTORY` or `$RUNTIME_DIRECTORY` (see `systemd.exec(5)`), it takes precedence. These variables may list
+//! several directories separated by colons, in which case the first one is used.
+
+use std::{env, path::PathBuf};
+
+use crate::env::{Env, ProcessEnv, TargetOs};
+
+/// Returns the path to the system-wide config directory of `app`.
+#[must_use]
+pub fn config_dir(app: &str) -> PathBuf {
+    dir(
+        "CONFIGURATION_DIRECTORY",
+        app,
+        ["/etc", "/Library/Preferences", "config"],
+    )
+}
+
+/// Returns the path to the system-wide state directory of `app`.
+#[must_use]
+pub fn state_dir(app: &str) -> PathBuf {
+    dir(
+        "STATE_DIRECTORY",
+        app,
+        ["/var/lib", "/Library/Application Support", "data"],
+    )
+}
+
+/// Returns the path to the system-wide cache directory of `app`.
+#[must_use]
+pub fn cache_dir(app: &str) -> PathBuf {
+    dir(
+        "CACHE_DIRECTORY",
+        app,
+        ["/var/cache", "/Library/Caches", "cache"],
+    )
+}
+
+/// Returns the path to the system-wide log directory of `app`.
+#[must_use]
+pub fn log_dir(app: &str) -> PathBuf {
+    dir("LOGS_DIRECTORY", app, ["/var/log", "/Library/Logs", "logs"])
+}
+
+/// Returns the path to the system-wide runtime directory of `app`.
+#[must_use]
+pub fn runtime_dir(app: &str) -> PathBuf {
+    dir("RUNTIME_DIRECTORY", app, ["/run", "/var/run", "run"])
+}
+
+/// Returns whether this process appears to be running as a system service rather than in a user session.
+///
+/// This is a heuristic: it is true when systemd has provided any of the service directory variables, or when running
+/// as `root` on Unix or as the `SYSTEM` account on Windows.
+#[must_use]
+pub fn is_system_context() -> bool {
+    let systemd = [
+        "CONFIGURATION_DIRECTORY",
+        "STATE_DIRECTORY",
+        "CACHE_DIRECTORY",
+        "LOGS_DIRECTORY",
+        "RUNTIME_DIRECTORY",
+    ];
+    if systemd
+        .into_iter()
+        .any(|var| ProcessEnv.var_os(var).is_some())
+    {
+        return true;
+    }
+
+    #[cfg(unix)]
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    return unsafe { libc::geteuid() } == 0;
+
+    #[cfg(windows)]
+    return ProcessEnv
+        .var_os("USERNAME")
+        .is_some_and(|user| user.eq_ignore_ascii_case("SYSTEM"))
+        || ProcessEnv.var_os("USERPROFILE").is_some_and(|profile| {
+            profile
+                .to_string_lossy()
+                .to_ascii_lowercase()
+                .ends_with("systemprofile")
+        });
+
+    #[cfg(not(any(unix, windows)))]
+    return false;
+}
+
+/// Returns the first directory in the systemd variable `var`, or else the platform's default for `app`.
+///
+/// The defaults are given as the Linux (and other Unix) parent, the macOS parent, and the subdirectory on Windows.
+fn dir(var: &str, app: &str, [unix, macos, windows]: [&str; 3]) -> PathBuf {
+    if let Some(dir) = ProcessEnv
+        .var_os(var)
+        .and_then(|dirs| env::split_paths(&dirs).next())
+    {
+        if dir.is_absolute() {
+            return dir;
+        }
+    }
+
+    match env::consts::OS {
+        "macos" => PathBuf::from(macos).join(app),
+        "windows" => crate::env::var(TargetOs::Windows, &ProcessEnv, "PROGRAMDATA")
+            .map_or_else(|| PathBuf::from(r"C:\ProgramData"), PathBuf::from)
+            .join(app)
+            .join(windows),
+        _ => PathBuf::from(unix).join(app),
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+};
+
+use crate::{env::xdg_var, unicode_path, DirKind, UserDirsError};
+
+/// Expands `~` and the directory variables in `template` into a path.
+///
+/// A leading `~` and `$HOME` expand to the home directory, and `$XDG_CACHE_HOME`, `$XDG_CONFIG_HOME`,
+/// `$XDG_DATA_HOME`, `$XDG_RUNTIME_DIR` and `$XDG_STATE_HOME` to the directories this crate resolves, including the
+/// platform defaults when the variables are unset. Variables can be written as `$NAME` or `${NAME}`. Any other
+/// variable is left as written, or rejected with [`UserDirsError::UnknownVariable`] if `reject_unknown` is set.
+///
+/// ```rust
+/// let db = user_dirs::expand_template("${XDG_DATA_HOME}/myapp/db.sqlite", false)?;
+/// assert_eq!(db, user_dirs::data_dir()?.join("myapp/db.sqlite"));
+/// # Ok::<(), user_dirs::UserDirsError>(())
+/// ```
+pub fn expand_template(template: &str, reject_unknown: bool) -> Result<PathBuf, UserDirsError> {
+    let mut expanded = OsString::new();
+    let mut rest = template;
+
+    if let Some(after) = template.strip_prefix('~') {
+        if after.is_empty() || after.starts_with(['/', '\\']) {
+            expanded.push(resolve(DirKind::Home)?);
+            rest = after;
+        }
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, written, remaining) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| UserDirsError::InvalidTemplate(template.to_owned()))?;
+            (
+                &braced[..end],
+                &rest[start..start + end + 3],
+                &braced[end + 1..],
+            )
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &rest[start..=start + end], &after[end..])
+        };
+
+        match kind_of(name) {
+            Some(kind) => expanded.push(resolve(kind)?),
+            None if name.is_empty() => expanded.push(written),
+            None if reject_unknown => return Err(UserDirsError::UnknownVariable(name.to_owned())),
+            None => expanded.push(written),
+        }
+        rest = remaining;
+    }
+    expanded.push(rest);
+
+    unicode_path(PathBuf::from(expanded))
+}
+
+/// Replaces the longest known directory at the start of `path` with its variable, the reverse of
+/// [`expand_template`].
+///
+/// The home directory becomes `~` and the others `${XDG_CONFIG_HOME}` and so on, so the result can be stored in a
+/// config file that is shared between machines. Paths outside every known directory are returned unchanged.
+#[must_use]
+pub fn contract(path: &Path) -> String {
+    let base = DirKind::ALL
+        .into_iter()
+        .filter_map(|kind| Some((kind, kind.resolve().ok()??)))
+        .filter(|(_, dir)| path.starts_with(dir))
+        .fold(
+            None,
+            |longest: Option<(DirKind, PathBuf)>, (kind, dir)| match &longest {
+                Some((_, longest_dir))
+                    if longest_dir.components().count() >= dir.components().count() =>
+                {
+                    longest
+                }
+                _ => Some((kind, dir)),
+            },
+        );
+
+    let Some((kind, dir)) = base else {
+        return path.display().to_string();
+    };
+    let variable = match xdg_var(kind) {
+        Some(var) => format!("${{{var}}}"),
+        None => "~".to_owned(),
+    };
+    match path.strip_prefix(&dir) {
+        Ok(rest) if !rest.as_os_str().is_empty() => {
+            format!("{variable}{MAIN_SEPARATOR}{}", rest.display())
+        }
+        _ => variable,
+    }
+}
+
+fn kind_of(name: &str) -> Option<DirKind> {
+    if name == "HOME" {
+        return Some(DirKind::Home);
+    }
+    DirKind::ALL
+        .into_iter()
+        .find(|&kind| xdg_var(kind) == Some(name))
+}
+
+fn resolve(kind: DirKind) -> Result<PathBuf, UserDirsError> {
+    kind.resolve()?.ok_or(UserDirsError::Unavailable(kind))
+}
@@ -0,0 +1,75 @@
+//! Helpers for tests that depend on environment variables.
+//!
+//! Mutating the process environment is racy under the parallel test runner: one test's `XDG_CONFIG_HOME` can leak
+//! into another test resolving its config directory. [`EnvOverride`] holds the lock the crate takes for every read of
+//! the process environment, so other threads can't observe the override, and restores the previous values when
+//! dropped, even if the test panics.
+//!
+//! ```rust
+//! use user_dirs::test_util::EnvOverride;
+//!
+//! let config = std::env::temp_dir().join("config");
+//! let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(&config))]);
+//! assert_eq!(user_dirs::config_dir().unwrap(), config);
+//! ```
+//!
+//! Resolution on other threads waits until the guard is dropped, as does any other [`EnvOverride`], except in the
+//! crate's own `*_async` helpers awaited by the test, which resolve on the test's behalf. Reads that bypass the crate,
+//! like a test calling [`std::env::var`] itself, are not serialized; such tests can hold [`EnvOverride::lock`].
+
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+};
+
+use crate::env::{lock_env, EnvLock};
+
+/// Overrides environment variables for the lifetime of the guard.
+#[derive(Debug)]
+#[must_use = "the overrides are reverted when the guard is dropped"]
+pub struct EnvOverride {
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: EnvLock,
+}
+
+impl EnvOverride {
+    /// Takes the environment lock and applies `vars`, setting each variable to `Some` value or removing it for `None`.
+    ///
+    /// Blocks until every other [`EnvOverride`] has been dropped. While the guard is held, this crate's reads of the
+    /// environment on other threads block too.
+    pub fn set<K: AsRef<OsStr>, V: AsRef<OsStr>>(vars: &[(K, Option<V>)]) -> EnvOverride {
+        let lock = lock_env();
+
+        let saved = vars
+            .iter()
+            .map(|(key, value)| {
+                let key = key.as_ref();
+                let previous = env::var_os(key);
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+                (key.to_owned(), previous)
+            })
+            .collect();
+
+        EnvOverride { saved, _lock: lock }
+    }
+
+    /// Takes the environment lock without changing any variables.
+    pub fn lock() -> EnvOverride {
+        Self::set::<&str, &str>(&[])
+    }
+}
+
+impl Drop for EnvOverride {
+    fn drop(&mut self) {
+        // Restore in reverse so a variable overridden twice ends up with its original value.
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+    }
+}
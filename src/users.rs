@@ -0,0 +1,140 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// A local user account, as returned by [`all_users`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserEntry {
+    name: String,
+    uid: Option<u32>,
+    sid: Option<String>,
+    home: PathBuf,
+    home_accessible: bool,
+}
+
+impl UserEntry {
+    #[cfg(any(unix, all(windows, feature = "native")))]
+    fn new(name: String, uid: Option<u32>, sid: Option<String>, home: PathBuf) -> UserEntry {
+        let home_accessible = std::fs::metadata(&home).is_ok_and(|metadata| metadata.is_dir());
+        UserEntry {
+            name,
+            uid,
+            sid,
+            home,
+            home_accessible,
+        }
+    }
+
+    /// Returns the user's login name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the user's numeric ID on Unix.
+    #[must_use]
+    pub fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// Returns the user's security identifier on Windows.
+    #[must_use]
+    pub fn sid(&self) -> Option<&str> {
+        self.sid.as_deref()
+    }
+
+    /// Returns the path to the user's home directory.
+    #[must_use]
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// Returns whether the home directory existed and was accessible when the users were enumerated.
+    #[must_use]
+    pub fn home_accessible(&self) -> bool {
+        self.home_accessible
+    }
+
+    /// Returns whether this is a system account (such as `root`, daemons, or `nobody`) rather than a person's.
+    ///
+    /// On Unix, this uses the conventional UID ranges: below 1000 on Linux and other systems, and below 500 on macOS.
+    /// On Windows, every account except local and domain user accounts (`S-1-5-21-...`) is a system account.
+    #[must_use]
+    pub fn is_system(&self) -> bool {
+        if let Some(sid) = &self.sid {
+            return !sid.starts_with("S-1-5-21-");
+        }
+
+        let min_uid = if cfg!(target_os = "macos") { 500 } else { 1000 };
+        self.uid.is_none_or(|uid| uid < min_uid || uid == 65534)
+    }
+}
+
+/// Returns every local user account, including system accounts.
+///
+/// On Unix, the accounts are read from `/etc/passwd`, so users from other sources such as LDAP (or Directory Services
+/// on macOS) are not included. On Windows, the profiles in the registry's `ProfileList` are used, which requires the
+/// `native` feature; the name is that of the profile directory.
+///
+/// Accounts whose home directory is missing or inaccessible are still included; see [`UserEntry::home_accessible`].
+/// To find where a user's other directories would be, pass their home to [`env::dir_for`](crate::env::dir_for).
+pub fn all_users() -> io::Result<Vec<UserEntry>> {
+    #[cfg(unix)]
+    return Ok(parse_passwd(&std::fs::read_to_string("/etc/passwd")?));
+
+    #[cfg(all(windows, feature = "native"))]
+    return profile_list();
+
+    #[cfg(not(any(unix, all(windows, feature = "native"))))]
+    return Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "enumerating users is not supported on this platform",
+    ));
+}
+
+#[cfg(unix)]
+fn parse_passwd(contents: &str) -> Vec<UserEntry> {
+    contents
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            let [name, _, uid, _, _, home, ..] = fields[..] else {
+                return None;
+            };
+
+            Some(UserEntry::new(
+                name.to_owned(),
+                Some(uid.parse().ok()?),
+                None,
+                PathBuf::from(home),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(all(windows, feature = "native"))]
+fn profile_list() -> io::Result<Vec<UserEntry>> {
+    use crate::registry::{self, HKEY_LOCAL_MACHINE};
+
+    const PROFILE_LIST: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList";
+
+    let users = registry::subkeys(HKEY_LOCAL_MACHINE, PROFILE_LIST)?
+        .into_iter()
+        .filter_map(|sid| {
+            let sid = sid.into_string().ok()?;
+            let key = format!(r"{PROFILE_LIST}\{sid}");
+            let home = PathBuf::from(registry::string(
+                HKEY_LOCAL_MACHINE,
+                &key,
+                "ProfileImagePath",
+            )?);
+            let name = home.file_name()?.to_string_lossy().into_owned();
+
+            Some(UserEntry::new(name, None, Some(sid), home))
+        })
+        .collect();
+
+    Ok(users)
+}
@@ -0,0 +1,58 @@
+//! [`Utf8PathBuf`] versions of the directory functions.
+//!
+//! Conversion is strict: if a resolved path is not valid UTF-8 (for example because `$HOME` or an XDG variable
+//! contains invalid bytes), [`UserDirsError::NonUtf8Path`] is returned with the offending path. Use [`lossy`] to
+//! explicitly opt into lossy conversion of a [`PathBuf`] instead.
+
+use std::path::PathBuf;
+
+use camino::Utf8PathBuf;
+
+use crate::UserDirsError;
+
+fn convert(path: PathBuf) -> Result<Utf8PathBuf, UserDirsError> {
+    Utf8PathBuf::from_path_buf(path).map_err(UserDirsError::NonUtf8Path)
+}
+
+fn convert_optional(path: Option<PathBuf>) -> Result<Option<Utf8PathBuf>, UserDirsError> {
+    path.map(convert).transpose()
+}
+
+/// Converts `path` to UTF-8, replacing invalid sequences with `U+FFFD REPLACEMENT CHARACTER`.
+#[must_use]
+pub fn lossy(path: PathBuf) -> Utf8PathBuf {
+    match Utf8PathBuf::from_path_buf(path) {
+        Ok(path) => path,
+        Err(path) => Utf8PathBuf::from(path.to_string_lossy().into_owned()),
+    }
+}
+
+/// Returns the path to the home directory, see [`crate::home_dir`].
+pub fn home_dir() -> Result<Utf8PathBuf, UserDirsError> {
+    convert(crate::home_dir()?)
+}
+
+/// Returns the path to the data directory, see [`crate::data_dir`].
+pub fn data_dir() -> Result<Utf8PathBuf, UserDirsError> {
+    convert(crate::data_dir()?)
+}
+
+/// Returns the path to the config directory, see [`crate::config_dir`].
+pub fn config_dir() -> Result<Utf8PathBuf, UserDirsError> {
+    convert(crate::config_dir()?)
+}
+
+/// Returns the path to the cache directory, see [`crate::cache_dir`].
+pub fn cache_dir() -> Result<Utf8PathBuf, UserDirsError> {
+    convert(crate::cache_dir()?)
+}
+
+/// Returns the path to the state directory, if available, see [`crate::state_dir`].
+pub fn state_dir() -> Result<Option<Utf8PathBuf>, UserDirsError> {
+    convert_optional(crate::state_dir()?)
+}
+
+/// Returns the path to the runtime directory, if available, see [`crate::runtime_dir`].
+pub fn runtime_dir() -> Result<Option<Utf8PathBuf>, UserDirsError> {
+    convert_optional(crate::runtime_dir())
+}
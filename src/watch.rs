@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    compare, env, media, DirKind, HomeDirError, UserDirKind, UserDirs, UserDirsDiff, UserDirsError,
+};
+
+/// How long the file must be quiet before changes are reported, to coalesce the bursts of events caused by editors
+/// writing to a temporary file and renaming it over the original.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// How often [`UserDirs::watch`] resolves the directories again when some of them can't be watched for events.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A change to a user directory, see [`watch_user_dirs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserDirChange {
+    /// The kind of directory that changed.
+    pub kind: UserDirKind,
+    /// The previous path of the directory, or `None` if it was disabled or unavailable.
+    pub old: Option<PathBuf>,
+    /// The new path of the directory, or `None` if it is now disabled or unavailable.
+    pub new: Option<PathBuf>,
+}
+
+/// Watches `$XDG_CONFIG_HOME/user-dirs.dirs`, calling `callback` with the user directories that changed.
+///
+/// This picks up edits made by `xdg-user-dirs-update` or by hand. Changes are debounced, so the callback is called
+/// once per burst of writes, and only if at least one directory actually changed. The directories are resolved with the
+/// overrides set on the calling thread with [`env::set_override`](crate::env::set_override). Watching stops when the
+/// returned [`UserDirsWatcher`] is dropped.
+pub fn watch_user_dirs<F>(mut callback: F) -> Result<UserDirsWatcher, WatchError>
+where
+    F: FnMut(&[UserDirChange]) + Send + 'static,
+{
+    let file = media::user_dirs_file()?;
+    let dir = file.parent().map(PathBuf::from).unwrap_or_default();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    // Watch the directory rather than the file, since the file may be replaced rather than modified.
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    let mut current = media::user_dirs()?;
+    let overrides = env::overrides();
+    let thread = thread::spawn(move || {
+        for (kind, path) in overrides {
+            env::set_override(kind, path);
+        }
+        let is_relevant = |event: &notify::Result<notify::Event>| {
+            event.as_ref().is_ok_and(|event| {
+                event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == file.file_name())
+            })
+        };
+
+        while let Ok(event) = receiver.recv() {
+            if !is_relevant(&event) {
+                continue;
+            }
+            loop {
+                match receiver.recv_timeout(DEBOUNCE) {
+                    Ok(_) => {}
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            let Ok(updated) = media::user_dirs() else {
+                continue;
+            };
+            let changes: Vec<_> = UserDirKind::ALL
+                .into_iter()
+                .filter(|kind| current.get(kind) != updated.get(kind))
+                .map(|kind| UserDirChange {
+                    kind,
+                    old: current.get(&kind).cloned().flatten(),
+                    new: updated.get(&kind).cloned().flatten(),
+                })
+                .collect();
+            current = updated;
+
+            if !changes.is_empty() {
+                callback(&changes);
+            }
+        }
+    });
+
+    Ok(UserDirsWatcher {
+        watcher: Some(watcher),
+        thread: Some(thread),
+    })
+}
+
+impl UserDirs {
+    /// Watches for changes to the resolved directories, calling `callback` with the differences.
+    ///
+    /// The parent of every resolved directory is watched, along with the directory of `user-dirs.dirs`. When one of
+    /// the directories or `user-dirs.dirs` is created, removed or replaced, such as by a symbolic link, the directories
+    /// are resolved again with this snapshot's options and the overrides set on the calling thread with
+    /// [`env::set_override`](crate::env::set_override); events for other files are ignored. If a parent can't be
+    /// watched, for example because it doesn't exist yet, the directories are resolved again every second instead.
+    ///
+    /// The directories are compared with a fresh resolution made when watching starts, so directories set with setters
+    /// like [`UserDirs::set_data`] are not considered. The callback is only called if at least one directory changed,
+    /// and watching stops when the returned [`UserDirsWatcher`] is dropped.
+    ///
+    /// ```rust,no_run
+    /// let dirs = user_dirs::UserDirs::new()?;
+    /// let _watcher = dirs.watch(|diff| eprint!("{diff}"))?;
+    /// # Ok::<(), user_dirs::WatchError>(())
+    /// ```
+    pub fn watch<F>(&self, mut callback: F) -> Result<UserDirsWatcher, WatchError>
+    where
+        F: FnMut(UserDirsDiff) + Send + 'static,
+    {
+        let mut current = self.clone();
+        current.reload()?;
+        let overrides = env::overrides();
+
+        let mut relevant: BTreeSet<PathBuf> = current.dirs().map(Path::to_path_buf).collect();
+        relevant.insert(media::user_dirs_file()?);
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        let parents: BTreeSet<&Path> = relevant.iter().filter_map(|path| path.parent()).collect();
+        let mut poll = false;
+        for parent in parents {
+            poll |= watcher.watch(parent, RecursiveMode::NonRecursive).is_err();
+        }
+
+        let thread = thread::spawn(move || {
+            for (kind, path) in overrides {
+                env::set_override(kind, path);
+            }
+            let is_relevant = |event: &notify::Result<notify::Event>| {
+                event
+                    .as_ref()
+                    .is_ok_and(|event| event.paths.iter().any(|path| relevant.contains(path)))
+            };
+
+            loop {
+                let event = if poll {
+                    receiver.recv_timeout(POLL_INTERVAL)
+                } else {
+                    receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+                };
+                match event {
+                    Ok(event) if !is_relevant(&event) => continue,
+                    Ok(_) => loop {
+                        match receiver.recv_timeout(DEBOUNCE) {
+                            Ok(_) => {}
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    },
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+
+                let mut updated = current.clone();
+                if updated.reload().is_err() {
+                    continue;
+                }
+                let diff = compare(&current, &updated);
+                current = updated;
+
+                if diff.has_changes() {
+                    callback(diff);
+                }
+            }
+        });
+
+        Ok(UserDirsWatcher {
+            watcher: Some(watcher),
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns every resolved directory.
+    fn dirs(&self) -> impl Iterator<Item = &Path> {
+        DirKind::ALL.into_iter().filter_map(|kind| self.get(kind))
+    }
+}
+
+/// A handle to a running [`watch_user_dirs`] or [`UserDirs::watch`] watcher, which stops watching when dropped.
+#[derive(Debug)]
+pub struct UserDirsWatcher {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for UserDirsWatcher {
+    fn drop(&mut self) {
+        // Dropping the watcher disconnects the channel, which ends the thread.
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// This error occurs when a watcher cannot be started.
+#[derive(Debug)]
+pub enum WatchError {
+    /// The home directory could not be located.
+    HomeDir(HomeDirError),
+    /// The directories could not be resolved, see [`UserDirs::watch`].
+    UserDirs(UserDirsError),
+    /// The file system watcher could not be created.
+    Notify(notify::Error),
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HomeDir(err) => err.fmt(f),
+            Self::UserDirs(err) => err.fmt(f),
+            Self::Notify(_) => write!(f, "could not watch for changes"),
+        }
+    }
+}
+
+impl Error for WatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::HomeDir(err) => Some(err),
+            Self::UserDirs(err) => Some(err),
+            Self::Notify(err) => Some(err),
+        }
+    }
+}
+
+impl From<HomeDirError> for WatchError {
+    fn from(err: HomeDirError) -> Self {
+        Self::HomeDir(err)
+    }
+}
+
+impl From<UserDirsError> for WatchError {
+    fn from(err: UserDirsError) -> Self {
+        Self::UserDirs(err)
+    }
+}
+
+impl From<notify::Error> for WatchError {
+    fn from(err: notify::Error) -> Self {
+        Self::Notify(err)
+    }
+}
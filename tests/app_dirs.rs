@@ -0,0 +1,207 @@
+use user_dirs::{
+    app_env_prefix, app_name_normalize, test_util::EnvOverride, DirKind, DirSource, Step, UserDirs,
+    UserDirsError, VarOutcome,
+};
+
+#[test]
+fn app_dirs_are_subdirectories_named_after_the_app() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+    let app = dirs.with_app("My App").unwrap();
+
+    assert_eq!(app.config(), dirs.config().join("my-app"));
+    assert_eq!(app.data(), dirs.data().join("my-app"));
+    assert_eq!(app.cache(), dirs.cache().join("my-app"));
+    assert_eq!(
+        app.state(),
+        dirs.state().map(|dir| dir.join("my-app")).as_deref()
+    );
+    assert_eq!(dirs.into_app_dirs("My App").unwrap(), app);
+}
+
+#[test]
+fn app_name_and_base_dirs_are_exposed() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+    let app = dirs.with_app("My App").unwrap();
+
+    assert_eq!(app.app_name(), "my-app");
+    assert_eq!(app.base_dirs(), &dirs);
+    assert_eq!(app.cache(), app.base_dirs().cache().join(app.app_name()));
+}
+
+#[test]
+fn names_that_normalize_to_nothing_are_rejected() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+
+    for name in ["", "..", "/", "✨"] {
+        assert!(matches!(
+            dirs.with_app(name),
+            Err(UserDirsError::InvalidAppName(_))
+        ));
+    }
+    assert_eq!(
+        dirs.with_app("../.evil/").unwrap().config(),
+        dirs.config().join("evil")
+    );
+}
+
+#[test]
+fn versioned_app_dirs_append_major_and_minor() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+    let app = user_dirs::AppDirsVersioned::new("My App", (2, 0)).unwrap();
+
+    assert_eq!(app.version(), (2, 0));
+    assert_eq!(app.config(), dirs.config().join("my-app-2.0"));
+    assert_eq!(app.data(), dirs.data().join("my-app-2.0"));
+    assert_eq!(
+        user_dirs::config_dir_for_app_versioned("My App", (2, 0)).unwrap(),
+        app.config()
+    );
+    assert!(matches!(
+        user_dirs::config_dir_for_app_versioned("..", (1, 0)),
+        Err(UserDirsError::InvalidAppName(_))
+    ));
+}
+
+#[test]
+fn org_config_dirs_nest_the_app_in_the_org() {
+    let _env = EnvOverride::lock();
+    let config = user_dirs::config_dir().unwrap();
+
+    assert_eq!(
+        user_dirs::config_dir_for_app_with_org("My Org", "My App").unwrap(),
+        config.join("my-org").join("my-app")
+    );
+    for (org, app) in [("", "app"), ("org", "..."), ("/", "app")] {
+        assert!(matches!(
+            user_dirs::config_dir_for_app_with_org(org, app),
+            Err(UserDirsError::InvalidAppName(_))
+        ));
+    }
+}
+
+#[test]
+fn profiles_are_kept_apart() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap().into_app_dirs("My App").unwrap();
+
+    let work = dirs.clone().with_profile("Work").unwrap();
+    assert_eq!(work.profile(), Some("work"));
+    assert_eq!(work.data(), dirs.data().join("profiles").join("work"));
+    assert_eq!(work.cache(), dirs.cache().join("profiles").join("work"));
+
+    let custom = dirs.clone().with_profile_in("users", "Leah").unwrap();
+    assert_eq!(custom.config(), dirs.config().join("users").join("leah"));
+
+    for profile in ["", "  ", "default"] {
+        assert_eq!(work.clone().with_profile(profile).unwrap(), dirs);
+    }
+    assert!(matches!(
+        dirs.clone().with_profile("..."),
+        Err(UserDirsError::InvalidProfileName(_))
+    ));
+    assert!(matches!(
+        dirs.clone().with_profile_in("a/b", "work"),
+        Err(UserDirsError::InvalidFileName(_))
+    ));
+}
+
+#[test]
+fn profile_is_selected_from_the_environment() {
+    let root = std::env::temp_dir().join(format!("user_dirs-profiles-{}", std::process::id()));
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(root.join("data").as_os_str())),
+        ("MY_APP_PROFILE", Some("Personal".as_ref())),
+    ]);
+    let dirs = UserDirs::new().unwrap().into_app_dirs("My App").unwrap();
+
+    let personal = dirs.clone().with_profile_from_env().unwrap();
+    assert_eq!(personal.profile(), Some("personal"));
+    std::env::remove_var("MY_APP_PROFILE");
+    assert_eq!(dirs.clone().with_profile_from_env().unwrap(), dirs);
+
+    assert_eq!(dirs.list_profiles().unwrap(), Vec::<String>::new());
+    std::fs::create_dir_all(personal.config()).unwrap();
+    std::fs::create_dir_all(dirs.clone().with_profile("work").unwrap().data()).unwrap();
+    assert_eq!(personal.list_profiles().unwrap(), ["personal", "work"]);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn app_variables_override_the_xdg_variables() {
+    let root = std::env::temp_dir().join("user_dirs-app-env");
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(root.join("data").as_os_str())),
+        ("MY_APP_CONFIG_DIR", Some(root.join("mine").as_os_str())),
+        ("MY_APP_DATA_DIR", Some("relative".as_ref())),
+        ("MY_APP_CACHE_DIR", Some("".as_ref())),
+        ("CUSTOM_CONFIG_DIR", None),
+    ]);
+    let dirs = UserDirs::new().unwrap().into_app_dirs("My App").unwrap();
+    assert_eq!(dirs.config(), root.join("config").join("my-app"));
+
+    let dirs = dirs.with_env_overrides();
+    assert_eq!(dirs.env_prefix(), "MY_APP");
+    assert_eq!(dirs.config(), root.join("mine"));
+    assert_eq!(dirs.source(DirKind::Config), Some(DirSource::AppEnv));
+    assert_eq!(dirs.data(), root.join("data").join("my-app"));
+    assert_eq!(dirs.source(DirKind::Data), Some(DirSource::Xdg));
+    assert_ne!(dirs.source(DirKind::Cache), Some(DirSource::AppEnv));
+
+    let work = dirs.clone().with_profile("work").unwrap();
+    assert_eq!(
+        work.config(),
+        root.join("mine").join("profiles").join("work")
+    );
+
+    let steps = dirs.explain(DirKind::Config).steps().to_vec();
+    assert_eq!(
+        steps,
+        [Step::Var {
+            name: "MY_APP_CONFIG_DIR".to_owned(),
+            value: Some(root.join("mine").into_os_string()),
+            outcome: VarOutcome::Used,
+        }]
+    );
+    let explanation = dirs.explain(DirKind::Data);
+    assert!(matches!(
+        &explanation.steps()[0],
+        Step::Var {
+            outcome: VarOutcome::Relative,
+            ..
+        }
+    ));
+    assert_eq!(explanation.result().unwrap(), Some(dirs.data()));
+
+    let custom = dirs.with_env_prefix("CUSTOM").unwrap();
+    assert_eq!(custom.config(), root.join("config").join("my-app"));
+    for prefix in ["", "MY-APP", "MY APP"] {
+        assert!(matches!(
+            custom.clone().with_env_prefix(prefix),
+            Err(UserDirsError::InvalidEnvPrefix(_))
+        ));
+    }
+}
+
+#[test]
+fn colliding_env_prefixes_are_detectable() {
+    assert_eq!(app_env_prefix("My App").as_deref(), Some("MY_APP"));
+    assert_eq!(app_env_prefix("my.app"), app_env_prefix("my-app"));
+    assert_ne!(app_env_prefix("my-app"), app_env_prefix("myapp"));
+    assert_eq!(app_env_prefix("..."), None);
+}
+
+#[test]
+fn app_dirs_use_the_public_normalization() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+    for name in ["My App", " Ünïcode Tool ", "..dotted.name", "a/b\\c"] {
+        let app = dirs.with_app(name).unwrap();
+        assert_eq!(app.config(), dirs.config().join(app_name_normalize(name)));
+    }
+}
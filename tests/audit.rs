@@ -0,0 +1,50 @@
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+
+use user_dirs::{audit, test_util::EnvOverride, AuditCode, DirKind, Severity};
+
+#[test]
+fn audit_reports_loose_permissions() {
+    let root = std::env::temp_dir().join(format!("user_dirs-audit-{}", std::process::id()));
+    let (config, runtime) = (root.join("config"), root.join("runtime"));
+    for (dir, mode) in [(&config, 0o777), (&runtime, 0o750)] {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_RUNTIME_DIR", Some(runtime.as_os_str())),
+        ("XDG_DATA_HOME", Some(root.join("missing").as_os_str())),
+    ]);
+
+    let config_findings = audit(DirKind::Config);
+    let runtime_findings = audit(DirKind::Runtime);
+    let data_findings = audit(DirKind::Data);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let codes = |findings: &[user_dirs::AuditFinding]| {
+        findings
+            .iter()
+            .map(|finding| (finding.severity(), finding.code()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        codes(&config_findings),
+        [(Severity::Error, AuditCode::WorldWritable)]
+    );
+    assert_eq!(config_findings[0].path(), config);
+    assert_eq!(
+        codes(&runtime_findings),
+        [(Severity::Error, AuditCode::NotPrivate)]
+    );
+    assert_eq!(
+        codes(&data_findings),
+        [(Severity::Info, AuditCode::Missing)]
+    );
+    assert_eq!(
+        config_findings[0].to_string(),
+        format!("error: {}: world-writable", config.display())
+    );
+}
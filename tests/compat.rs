@@ -0,0 +1,441 @@
+use user_dirs::{
+    compat::app_dirs::{app_dir, get_app_dir, get_app_root, AppDataType, AppInfo},
+    test_util::EnvOverride,
+    AppDirs,
+};
+
+const APP_INFO: AppInfo = AppInfo {
+    name: "My App",
+    author: "Leah",
+};
+
+#[test]
+fn app_dirs_roots_are_app_dirs() {
+    let _env = EnvOverride::lock();
+    let dirs = AppDirs::new("My App").unwrap();
+
+    assert_eq!(
+        get_app_root(AppDataType::UserConfig, &APP_INFO).unwrap(),
+        dirs.config()
+    );
+    assert_eq!(
+        get_app_root(AppDataType::UserCache, &APP_INFO).unwrap(),
+        dirs.cache()
+    );
+    assert_eq!(
+        get_app_root(AppDataType::SharedConfig, &APP_INFO).unwrap(),
+        user_dirs::system::config_dir("my-app")
+    );
+}
+
+#[test]
+fn only_app_functions_create_directories() {
+    let root = std::env::temp_dir().join(format!("user_dirs-compat-{}", std::process::id()));
+    let _env = EnvOverride::set(&[("XDG_DATA_HOME", Some(root.as_os_str()))]);
+
+    let path = get_app_dir(AppDataType::UserData, &APP_INFO, "plugins").unwrap();
+    assert_eq!(path, root.join("my-app").join("plugins"));
+    assert!(!path.exists());
+
+    let created = app_dir(AppDataType::UserData, &APP_INFO, "plugins").unwrap();
+    let exists = created.is_dir();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(created, path);
+    assert!(exists);
+}
+
+mod electron {
+    use std::{collections::HashMap, path::Path};
+
+    use user_dirs::{
+        compat::electron::{path_for, ElectronPath},
+        env::TargetOs,
+        UserDirsError,
+    };
+
+    fn path(name: ElectronPath, os: TargetOs, home: &str, env: &[(&str, &str)]) -> String {
+        let env: HashMap<_, _> = env.iter().copied().collect();
+        let path = path_for(name, "My App", os, Path::new(home), &env).unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn linux_paths() {
+        let linux = |name| path(name, TargetOs::Linux, "/home/leah", &[]);
+        assert_eq!(linux(ElectronPath::Home), "/home/leah");
+        assert_eq!(linux(ElectronPath::AppData), "/home/leah/.config");
+        assert_eq!(linux(ElectronPath::UserData), "/home/leah/.config/My App");
+        assert_eq!(
+            linux(ElectronPath::SessionData),
+            "/home/leah/.config/My App"
+        );
+        assert_eq!(linux(ElectronPath::Logs), "/home/leah/.config/My App/logs");
+        assert_eq!(
+            linux(ElectronPath::CrashDumps),
+            "/home/leah/.config/My App/Crashpad"
+        );
+        assert_eq!(linux(ElectronPath::Temp), "/tmp");
+
+        // Chromium takes the variables as they are, even when relative or with a trailing slash.
+        let env = [("XDG_CONFIG_HOME", "dotfiles"), ("TMPDIR", "/var/tmp/")];
+        let linux = |name| path(name, TargetOs::Linux, "/home/leah", &env);
+        assert_eq!(linux(ElectronPath::UserData), "dotfiles/My App");
+        assert_eq!(linux(ElectronPath::Temp), "/var/tmp/");
+        let empty = [("XDG_CONFIG_HOME", "")];
+        assert_eq!(
+            path(ElectronPath::AppData, TargetOs::Linux, "/home/leah", &empty),
+            "/home/leah/.config"
+        );
+    }
+
+    #[test]
+    fn macos_paths() {
+        let env = [("XDG_CONFIG_HOME", "/ignored")];
+        let macos = |name| path(name, TargetOs::MacOs, "/Users/leah", &env);
+        assert_eq!(
+            macos(ElectronPath::AppData),
+            "/Users/leah/Library/Application Support"
+        );
+        assert_eq!(
+            macos(ElectronPath::UserData),
+            "/Users/leah/Library/Application Support/My App"
+        );
+        assert_eq!(macos(ElectronPath::Logs), "/Users/leah/Library/Logs/My App");
+        assert_eq!(
+            macos(ElectronPath::CrashDumps),
+            "/Users/leah/Library/Application Support/My App/Crashpad"
+        );
+    }
+
+    #[test]
+    fn windows_paths() {
+        let env = [
+            ("APPDATA", r"C:\Users\Leah\AppData\Roaming"),
+            ("TEMP", r"C:\Users\Leah\AppData\Local\Temp\"),
+        ];
+        let windows = |name| path(name, TargetOs::Windows, r"C:\Users\Leah", &env);
+        assert_eq!(
+            windows(ElectronPath::AppData),
+            r"C:\Users\Leah\AppData\Roaming"
+        );
+        assert_eq!(
+            windows(ElectronPath::UserData),
+            r"C:\Users\Leah\AppData\Roaming\My App"
+        );
+        assert_eq!(
+            windows(ElectronPath::Logs),
+            r"C:\Users\Leah\AppData\Roaming\My App\logs"
+        );
+        assert_eq!(
+            windows(ElectronPath::CrashDumps),
+            r"C:\Users\Leah\AppData\Roaming\My App\Crashpad"
+        );
+        assert_eq!(
+            windows(ElectronPath::Temp),
+            r"C:\Users\Leah\AppData\Local\Temp"
+        );
+
+        let windows = |name| path(name, TargetOs::Windows, r"C:\Users\Leah", &[]);
+        assert_eq!(
+            windows(ElectronPath::UserData),
+            r"C:\Users\Leah\AppData\Roaming\My App"
+        );
+        assert_eq!(windows(ElectronPath::Temp), r"C:\Windows");
+    }
+
+    #[test]
+    fn app_names_must_be_directory_names() {
+        let env = HashMap::<&str, &str>::new();
+        for name in ["", "..", "a/b", r"a\b"] {
+            assert!(matches!(
+                path_for(
+                    ElectronPath::UserData,
+                    name,
+                    TargetOs::Linux,
+                    Path::new("/home/leah"),
+                    &env
+                ),
+                Err(UserDirsError::InvalidAppName(_))
+            ));
+        }
+        assert_eq!(ElectronPath::CrashDumps.name(), "crashDumps");
+    }
+}
+
+mod platformdirs {
+    use std::{collections::HashMap, path::Path};
+
+    use user_dirs::{
+        compat::platformdirs::{AppAuthor, PlatformDir, PlatformDirs},
+        env::TargetOs,
+    };
+
+    const SUPER_APP: PlatformDirs = PlatformDirs {
+        appname: Some("SuperApp"),
+        appauthor: AppAuthor::Name("Acme"),
+        version: Some("1.0"),
+        roaming: false,
+    };
+
+    fn path(
+        dirs: PlatformDirs,
+        dir: PlatformDir,
+        os: TargetOs,
+        home: &str,
+        env: &[(&str, &str)],
+    ) -> String {
+        let env: HashMap<_, _> = env.iter().copied().collect();
+        let path = dirs.dir_for(dir, os, Path::new(home), &env);
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn linux_layout() {
+        let linux = |dir| path(SUPER_APP, dir, TargetOs::Linux, "/home/leah", &[]);
+        assert_eq!(
+            linux(PlatformDir::UserData),
+            "/home/leah/.local/share/SuperApp/1.0"
+        );
+        assert_eq!(
+            linux(PlatformDir::UserConfig),
+            "/home/leah/.config/SuperApp/1.0"
+        );
+        assert_eq!(
+            linux(PlatformDir::UserCache),
+            "/home/leah/.cache/SuperApp/1.0"
+        );
+        assert_eq!(
+            linux(PlatformDir::UserState),
+            "/home/leah/.local/state/SuperApp/1.0"
+        );
+        assert_eq!(
+            linux(PlatformDir::UserLog),
+            "/home/leah/.local/state/SuperApp/1.0/log"
+        );
+        assert_eq!(
+            linux(PlatformDir::SiteData),
+            "/usr/local/share/SuperApp/1.0"
+        );
+        assert_eq!(linux(PlatformDir::SiteConfig), "/etc/xdg/SuperApp/1.0");
+        assert_eq!(linux(PlatformDir::SiteCache), "/var/cache/SuperApp/1.0");
+
+        let env = [
+            ("XDG_CONFIG_HOME", "/srv/config"),
+            ("XDG_STATE_HOME", "relative"),
+            ("XDG_DATA_DIRS", "/opt/share:/usr/share"),
+        ];
+        let linux = |dir| path(SUPER_APP, dir, TargetOs::Linux, "/home/leah", &env);
+        assert_eq!(linux(PlatformDir::UserConfig), "/srv/config/SuperApp/1.0");
+        assert_eq!(
+            linux(PlatformDir::UserLog),
+            "/home/leah/.local/state/SuperApp/1.0/log"
+        );
+        assert_eq!(linux(PlatformDir::SiteData), "/opt/share/SuperApp/1.0");
+    }
+
+    #[test]
+    fn version_needs_an_app_name() {
+        let dirs = PlatformDirs {
+            appname: None,
+            version: Some("1.0"),
+            ..SUPER_APP
+        };
+        assert_eq!(
+            path(
+                dirs,
+                PlatformDir::UserConfig,
+                TargetOs::Linux,
+                "/home/leah",
+                &[]
+            ),
+            "/home/leah/.config"
+        );
+        assert_eq!(
+            path(
+                dirs,
+                PlatformDir::UserCache,
+                TargetOs::Windows,
+                r"C:\Users\Leah",
+                &[]
+            ),
+            r"C:\Users\Leah\AppData\Local"
+        );
+    }
+
+    #[test]
+    fn macos_layout() {
+        let macos = |dir| path(SUPER_APP, dir, TargetOs::MacOs, "/Users/leah", &[]);
+        assert_eq!(
+            macos(PlatformDir::UserConfig),
+            "/Users/leah/Library/Application Support/SuperApp/1.0"
+        );
+        assert_eq!(
+            macos(PlatformDir::UserState),
+            "/Users/leah/Library/Application Support/SuperApp/1.0"
+        );
+        assert_eq!(
+            macos(PlatformDir::UserCache),
+            "/Users/leah/Library/Caches/SuperApp/1.0"
+        );
+        assert_eq!(
+            macos(PlatformDir::UserLog),
+            "/Users/leah/Library/Logs/SuperApp/1.0"
+        );
+        assert_eq!(
+            macos(PlatformDir::SiteConfig),
+            "/Library/Application Support/SuperApp/1.0"
+        );
+        assert_eq!(
+            macos(PlatformDir::SiteCache),
+            "/Library/Caches/SuperApp/1.0"
+        );
+
+        let env = [("XDG_CACHE_HOME", "/Users/leah/.cache")];
+        assert_eq!(
+            path(
+                SUPER_APP,
+                PlatformDir::UserCache,
+                TargetOs::MacOs,
+                "/Users/leah",
+                &env
+            ),
+            "/Users/leah/.cache/SuperApp/1.0"
+        );
+    }
+
+    #[test]
+    fn windows_layout() {
+        let env = [
+            ("APPDATA", r"C:\Users\Leah\AppData\Roaming"),
+            ("LOCALAPPDATA", r"C:\Users\Leah\AppData\Local"),
+            ("PROGRAMDATA", r"C:\ProgramData"),
+        ];
+        let windows = |dirs, dir| path(dirs, dir, TargetOs::Windows, r"C:\Users\Leah", &env);
+        let roaming = PlatformDirs {
+            roaming: true,
+            ..SUPER_APP
+        };
+
+        assert_eq!(
+            windows(SUPER_APP, PlatformDir::UserData),
+            r"C:\Users\Leah\AppData\Local\Acme\SuperApp\1.0"
+        );
+        assert_eq!(
+            windows(roaming, PlatformDir::UserConfig),
+            r"C:\Users\Leah\AppData\Roaming\Acme\SuperApp\1.0"
+        );
+        assert_eq!(
+            windows(roaming, PlatformDir::UserCache),
+            r"C:\Users\Leah\AppData\Local\Acme\SuperApp\Cache\1.0"
+        );
+        assert_eq!(
+            windows(SUPER_APP, PlatformDir::UserLog),
+            r"C:\Users\Leah\AppData\Local\Acme\SuperApp\1.0\Logs"
+        );
+        assert_eq!(
+            windows(SUPER_APP, PlatformDir::SiteConfig),
+            r"C:\ProgramData\Acme\SuperApp\1.0"
+        );
+        assert_eq!(
+            windows(SUPER_APP, PlatformDir::SiteCache),
+            r"C:\ProgramData\Acme\SuperApp\Cache\1.0"
+        );
+
+        let default_author = PlatformDirs {
+            appauthor: AppAuthor::Default,
+            ..SUPER_APP
+        };
+        let no_author = PlatformDirs {
+            appauthor: AppAuthor::Omit,
+            ..SUPER_APP
+        };
+        assert_eq!(
+            windows(default_author, PlatformDir::UserData),
+            r"C:\Users\Leah\AppData\Local\SuperApp\SuperApp\1.0"
+        );
+        assert_eq!(
+            windows(no_author, PlatformDir::UserData),
+            r"C:\Users\Leah\AppData\Local\SuperApp\1.0"
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod qt {
+    use std::path::PathBuf;
+
+    use user_dirs::{
+        compat::qt::{standard_locations, writable_location, StandardLocation},
+        test_util::EnvOverride,
+    };
+
+    #[test]
+    fn linux_locations() {
+        let _env = EnvOverride::set(&[
+            ("HOME", Some("/home/leah")),
+            ("XDG_CONFIG_HOME", None),
+            ("XDG_DATA_HOME", Some("relative")),
+            ("XDG_CACHE_HOME", Some("/srv/cache")),
+            ("XDG_CONFIG_DIRS", None),
+            ("XDG_DATA_DIRS", Some("/opt/share:/usr/share")),
+        ]);
+        let writable = |location, org, app| writable_location(location, org, app).unwrap();
+        let standard = |location, org, app| standard_locations(location, org, app);
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            writable(StandardLocation::AppConfig, "Acme", "Editor"),
+            PathBuf::from("/home/leah/.config/Acme/Editor")
+        );
+        assert_eq!(
+            writable(StandardLocation::Cache, "", "Editor"),
+            PathBuf::from("/srv/cache/Editor")
+        );
+        assert_eq!(
+            writable(StandardLocation::GenericCache, "Acme", "Editor"),
+            PathBuf::from("/srv/cache")
+        );
+        assert_eq!(
+            standard(StandardLocation::Config, "Acme", "Editor"),
+            paths(&["/home/leah/.config", "/etc/xdg"])
+        );
+        assert_eq!(
+            standard(StandardLocation::AppConfig, "Acme", "Editor"),
+            paths(&["/home/leah/.config/Acme/Editor", "/etc/xdg/Acme/Editor"])
+        );
+        assert_eq!(
+            standard(StandardLocation::AppData, "Acme", "Editor"),
+            paths(&[
+                "/home/leah/.local/share/Acme/Editor",
+                "/opt/share/Acme/Editor",
+                "/usr/share/Acme/Editor",
+            ])
+        );
+        assert_eq!(
+            standard(StandardLocation::GenericData, "Acme", "Editor"),
+            paths(&["/home/leah/.local/share", "/opt/share", "/usr/share"])
+        );
+        assert_eq!(
+            standard(StandardLocation::Fonts, "", ""),
+            paths(&[
+                "/home/leah/.local/share/fonts",
+                "/home/leah/.fonts",
+                "/opt/share/fonts",
+                "/usr/share/fonts",
+            ])
+        );
+        assert_eq!(
+            standard(StandardLocation::Applications, "", ""),
+            paths(&[
+                "/home/leah/.local/share/applications",
+                "/opt/share/applications",
+                "/usr/share/applications",
+            ])
+        );
+        assert_eq!(
+            standard(StandardLocation::Cache, "Acme", "Editor"),
+            paths(&["/srv/cache/Acme/Editor"])
+        );
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(unix)]
+
+use std::path::PathBuf;
+
+use user_dirs::{completion_dir, test_util::EnvOverride, Shell};
+
+#[test]
+fn completion_dirs_follow_xdg_on_every_unix() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah")),
+        ("XDG_DATA_HOME", None),
+        ("XDG_CONFIG_HOME", Some("/home/leah/dotfiles")),
+        ("BASH_COMPLETION_USER_DIR", None),
+    ]);
+
+    assert_eq!(
+        completion_dir(Shell::Bash).unwrap(),
+        Some(PathBuf::from(
+            "/home/leah/.local/share/bash-completion/completions"
+        ))
+    );
+    assert_eq!(
+        completion_dir(Shell::Fish).unwrap(),
+        Some(PathBuf::from("/home/leah/dotfiles/fish/completions"))
+    );
+}
+
+#[test]
+fn bash_completion_user_dir_takes_precedence() {
+    let _env = EnvOverride::set(&[("BASH_COMPLETION_USER_DIR", Some("/opt/completion"))]);
+
+    assert_eq!(
+        completion_dir(Shell::Bash).unwrap(),
+        Some(PathBuf::from("/opt/completion/completions"))
+    );
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use user_dirs::{test_util::EnvOverride, UserDir};
+
+#[derive(UserDir)]
+#[user_dir(app_name = "myapp")]
+struct Paths {
+    #[user_dir(config)]
+    config: PathBuf,
+    #[user_dir(cache, app_name = "thumbnails")]
+    thumbnails: PathBuf,
+    #[user_dir(runtime)]
+    runtime: Option<PathBuf>,
+    #[user_dir(home)]
+    home: PathBuf,
+    unannotated: u32,
+}
+
+#[test]
+fn resolves_annotated_fields() {
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some("/xdg/config")),
+        ("XDG_CACHE_HOME", Some("/xdg/cache")),
+        ("XDG_RUNTIME_DIR", None),
+    ]);
+
+    let paths = Paths::resolve().unwrap();
+    assert_eq!(paths.config, PathBuf::from("/xdg/config/myapp"));
+    assert_eq!(paths.thumbnails, PathBuf::from("/xdg/cache/thumbnails"));
+    assert_eq!(paths.runtime, None);
+    assert_eq!(paths.home, user_dirs::home_dir().unwrap().join("myapp"));
+    assert_eq!(paths.unannotated, 0);
+}
@@ -0,0 +1,66 @@
+use std::{fs, path::PathBuf, process};
+
+use user_dirs::{
+    env::{clear_override, set_override},
+    test_util::EnvOverride,
+    DirKind,
+};
+
+fn overridden_dir(kind: DirKind, name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("user-dirs-{name}-{}", process::id()));
+    set_override(kind, root.join("nested").join("config"));
+    root
+}
+
+#[test]
+fn ensure_creates_the_directory() {
+    let root = overridden_dir(DirKind::Config, "ensure");
+    let dir = user_dirs::ensure_config_dir().unwrap();
+    clear_override(DirKind::Config);
+
+    assert_eq!(dir, root.join("nested").join("config"));
+    assert!(dir.is_dir());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn ensure_functions_create_the_resolved_xdg_dir() {
+    let root = std::env::temp_dir().join(format!("user_dirs-ensure-xdg-{}", std::process::id()));
+    let _env = EnvOverride::set(&[
+        ("XDG_DATA_HOME", Some(root.join("data").as_os_str())),
+        ("XDG_STATE_HOME", Some(root.join("state").as_os_str())),
+    ]);
+
+    assert_eq!(user_dirs::ensure_data_dir().unwrap(), root.join("data"));
+    assert_eq!(user_dirs::ensure_state_dir().unwrap(), root.join("state"));
+    assert!(root.join("data").is_dir());
+    assert!(root.join("state").is_dir());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(any(target_os = "macos", windows))]
+#[test]
+fn ensure_fails_without_a_state_dir() {
+    let _env = EnvOverride::set(&[("XDG_STATE_HOME", None::<&str>)]);
+
+    let err = user_dirs::ensure_state_dir().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn ensure_async_creates_the_directory() {
+    let root = overridden_dir(DirKind::Config, "ensure-async");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let dir = runtime
+        .block_on(user_dirs::ensure_config_dir_async())
+        .unwrap();
+    clear_override(DirKind::Config);
+
+    assert_eq!(dir, root.join("nested").join("config"));
+    assert!(dir.is_dir());
+    fs::remove_dir_all(root).unwrap();
+}
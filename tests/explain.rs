@@ -0,0 +1,104 @@
+use std::{ffi::OsString, path::Path};
+
+use user_dirs::{explain, test_util::EnvOverride, DirKind, Step, VarOutcome};
+
+#[cfg(unix)]
+const HOME: &str = "/home/leah";
+#[cfg(windows)]
+const HOME: &str = r"C:\Users\Leah";
+
+#[test]
+fn xdg_variable_is_reported_as_used() {
+    let config = Path::new(HOME).join("dotfiles");
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(config.as_os_str()))]);
+
+    let explanation = explain(DirKind::Config);
+    assert_eq!(
+        explanation.steps(),
+        [Step::Var {
+            name: "XDG_CONFIG_HOME".to_owned(),
+            value: Some(OsString::from(&config)),
+            outcome: VarOutcome::Used,
+        }]
+    );
+    assert_eq!(explanation.result().unwrap(), Some(config.as_path()));
+}
+
+#[test]
+fn default_is_reported_with_the_home_directory() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(HOME)),
+        ("USERPROFILE", Some(HOME)),
+        ("XDG_CACHE_HOME", None),
+        ("LOCALAPPDATA", None),
+    ]);
+
+    let explanation = explain(DirKind::Cache);
+    let cache = user_dirs::cache_dir().unwrap();
+    assert!(explanation.steps().contains(&Step::Home {
+        path: Some(HOME.into())
+    }));
+    assert!(explanation.steps().contains(&Step::Default {
+        path: cache.clone()
+    }));
+    assert_eq!(explanation.result().unwrap(), Some(cache.as_path()));
+
+    let report = format!("{explanation:#}");
+    assert!(report.starts_with("cache directory:\n  $XDG_CACHE_HOME is unset\n"));
+    assert!(!report.contains(HOME));
+}
+
+#[test]
+fn home_variable_is_traced() {
+    let name = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    let _env = EnvOverride::set(&[
+        (name, Some(HOME)),
+        ("USER_DIRS_HOME", None),
+        ("XDG_CACHE_HOME", None),
+        ("LOCALAPPDATA", None),
+    ]);
+
+    assert!(explain(DirKind::Cache).steps().contains(&Step::Var {
+        name: name.to_owned(),
+        value: Some(HOME.into()),
+        outcome: VarOutcome::Used,
+    }));
+}
+
+#[test]
+fn user_dirs_home_is_traced() {
+    let home = Path::new(HOME).join("service");
+    let _env = EnvOverride::set(&[
+        ("USER_DIRS_HOME", Some(home.as_os_str())),
+        ("XDG_DATA_HOME", None),
+        ("APPDATA", None),
+    ]);
+
+    let explanation = explain(DirKind::Data);
+    assert!(explanation.steps().contains(&Step::Var {
+        name: "USER_DIRS_HOME".to_owned(),
+        value: Some(home.clone().into()),
+        outcome: VarOutcome::Used,
+    }));
+    assert_eq!(
+        explanation.result().unwrap(),
+        Some(user_dirs::data_dir().unwrap().as_path())
+    );
+}
+
+#[test]
+fn thread_local_overrides_are_traced() {
+    let data = Path::new(HOME).join("overridden");
+    user_dirs::env::set_override(DirKind::Data, data.clone());
+    let explanation = explain(DirKind::Data);
+    user_dirs::env::clear_override(DirKind::Data);
+
+    assert_eq!(
+        explanation.steps(),
+        [Step::Override {
+            kind: DirKind::Data,
+            path: data.clone(),
+        }]
+    );
+    assert_eq!(explanation.result().unwrap(), Some(data.as_path()));
+}
@@ -0,0 +1,151 @@
+use user_dirs::{
+    cache_path, config_dir_relative, config_file, config_path, data_path, test_util::EnvOverride,
+    UserConfigFile, UserDirsError,
+};
+
+#[test]
+fn config_file_joins_a_single_component() {
+    let _env = EnvOverride::lock();
+
+    assert_eq!(
+        config_file("myapp.toml").unwrap(),
+        user_dirs::config_dir().unwrap().join("myapp.toml")
+    );
+}
+
+#[test]
+fn file_names_with_separators_are_rejected() {
+    let _env = EnvOverride::lock();
+
+    for name in ["", "..", "myapp/config.toml", r"myapp\config.toml"] {
+        assert!(matches!(
+            config_file(name),
+            Err(UserDirsError::InvalidFileName(_))
+        ));
+    }
+}
+
+#[test]
+fn user_config_file_prefers_the_user_directory() {
+    let root = std::env::temp_dir().join(format!("user_dirs-config-file-{}", std::process::id()));
+    let (user, system) = (root.join("user"), root.join("system"));
+    for dir in [&user, &system] {
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+    }
+    std::fs::write(system.join("my-app/config.toml"), "").unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(user.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some(system.as_os_str())),
+    ]);
+    let file = UserConfigFile::new("My App", "config.toml").unwrap();
+
+    let system_only = file.path();
+    std::fs::write(user.join("my-app/config.toml"), "").unwrap();
+    let both = file.paths();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(system_only, Some(system.join("my-app/config.toml")));
+    assert_eq!(
+        both,
+        [
+            user.join("my-app/config.toml"),
+            system.join("my-app/config.toml")
+        ]
+    );
+    assert!(matches!(
+        UserConfigFile::new("My App", "../config.toml"),
+        Err(UserDirsError::InvalidFileName(_))
+    ));
+}
+
+#[test]
+fn config_dir_relative_stays_in_the_config_directory() {
+    let _env = EnvOverride::lock();
+
+    assert_eq!(
+        config_dir_relative("myapp/config.toml").unwrap(),
+        user_dirs::config_dir().unwrap().join("myapp/config.toml")
+    );
+    for path in ["/etc/passwd", "../secrets", "myapp/../../secrets"] {
+        assert!(matches!(
+            config_dir_relative(path),
+            Err(UserDirsError::InvalidRelativePath(_))
+        ));
+    }
+}
+
+#[test]
+fn safe_join_helpers_stay_in_their_base_directory() {
+    let _env = EnvOverride::lock();
+
+    assert_eq!(
+        data_path("myapp/plugins/foo.db").unwrap(),
+        user_dirs::data_dir().unwrap().join("myapp/plugins/foo.db")
+    );
+    assert_eq!(
+        cache_path("./thumbnails").unwrap(),
+        user_dirs::cache_dir().unwrap().join("./thumbnails")
+    );
+    assert_eq!(
+        config_path("myapp..d/config.toml").unwrap(),
+        user_dirs::config_dir()
+            .unwrap()
+            .join("myapp..d/config.toml")
+    );
+
+    for path in [
+        "",
+        "..",
+        r"..\",
+        "foo/../../bar",
+        r"foo\..\bar",
+        "/etc/passwd",
+        r"\Windows",
+        r"C:\Windows",
+        "C:relative",
+        r"\\server\share",
+        r"\\?\C:\Windows",
+        "foo\0bar",
+    ] {
+        assert!(
+            matches!(
+                config_path(path),
+                Err(UserDirsError::InvalidRelativePath(_))
+            ),
+            "{path:?} was accepted"
+        );
+    }
+}
+
+#[cfg(feature = "figment")]
+#[test]
+fn user_config_file_is_a_figment_provider() {
+    use figment::{providers::Serialized, Figment, Source};
+
+    let root = std::env::temp_dir().join(format!("user_dirs-figment-{}", std::process::id()));
+    let (user, system) = (root.join("user"), root.join("system"));
+    std::fs::create_dir_all(system.join("my-app")).unwrap();
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(user.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some(system.as_os_str())),
+    ]);
+    let file = UserConfigFile::new("My App", "config.toml").unwrap();
+    let figment = || Figment::from(Serialized::default("theme", "light")).merge(file.clone());
+
+    let missing = figment().extract_inner::<String>("theme");
+    std::fs::write(system.join("my-app/config.toml"), "theme = \"dark\"\n").unwrap();
+    let found = figment().extract_inner::<String>("theme");
+    let source = figment().find_metadata("theme").unwrap().source.clone();
+    std::fs::write(system.join("my-app/config.toml"), "theme = 1\n").unwrap();
+    let invalid = figment().extract_inner::<String>("theme").unwrap_err();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(missing.unwrap(), "light");
+    assert_eq!(found.unwrap(), "dark");
+    assert_eq!(
+        source,
+        Some(Source::File(system.join("my-app/config.toml")))
+    );
+    assert!(invalid.to_string().contains("config.toml"), "{invalid}");
+}
@@ -0,0 +1,86 @@
+//! Home directory behavior that must hold both with the `home` crate and with the in-crate
+//! fallback (`--no-default-features`).
+
+use std::path::PathBuf;
+
+use user_dirs::test_util::EnvOverride;
+
+#[cfg(unix)]
+const HOME_VAR: &str = "HOME";
+#[cfg(windows)]
+const HOME_VAR: &str = "USERPROFILE";
+
+#[cfg(unix)]
+const HOME: &str = "/home/leah";
+#[cfg(windows)]
+const HOME: &str = r"C:\Users\Leah";
+
+#[test]
+fn home_dir_follows_environment() {
+    let _env = EnvOverride::set(&[(HOME_VAR, Some(HOME))]);
+
+    assert_eq!(user_dirs::home_dir().unwrap(), PathBuf::from(HOME));
+}
+
+#[test]
+fn os_dirs_are_rooted_in_home() {
+    let _env = EnvOverride::set(&[
+        (HOME_VAR, Some(HOME)),
+        ("APPDATA", None),
+        ("LOCALAPPDATA", None),
+    ]);
+
+    assert!(user_dirs::os::config_dir().unwrap().starts_with(HOME));
+    assert!(user_dirs::os::cache_dir().unwrap().starts_with(HOME));
+    assert!(user_dirs::os::data_dir().unwrap().starts_with(HOME));
+}
+
+#[cfg(unix)]
+#[test]
+fn user_dirs_home_moves_defaults_but_not_xdg_dirs() {
+    let _env = EnvOverride::set(&[
+        (HOME_VAR, Some(HOME)),
+        ("USER_DIRS_HOME", Some("/srv/service")),
+        ("XDG_CONFIG_HOME", Some("/etc/service")),
+        ("XDG_CACHE_HOME", None),
+    ]);
+
+    assert_eq!(
+        user_dirs::home_dir().unwrap(),
+        PathBuf::from("/srv/service")
+    );
+    assert!(user_dirs::cache_dir().unwrap().starts_with("/srv/service"));
+    assert_eq!(
+        user_dirs::config_dir().unwrap(),
+        PathBuf::from("/etc/service")
+    );
+
+    std::env::set_var("USER_DIRS_HOME", "relative");
+    assert_eq!(user_dirs::home_dir().unwrap(), PathBuf::from(HOME));
+    std::env::set_var("USER_DIRS_HOME", "");
+    assert_eq!(user_dirs::home_dir().unwrap(), PathBuf::from(HOME));
+}
+
+#[test]
+fn only_platforms_without_a_home_report_unsupported_platform() {
+    let err = user_dirs::UserDirsError::from(user_dirs::HomeDirError);
+
+    if cfg!(any(
+        target_os = "unknown",
+        target_os = "none",
+        target_os = "fuchsia"
+    )) {
+        assert!(matches!(
+            err,
+            user_dirs::UserDirsError::UnsupportedPlatform { os } if os == std::env::consts::OS
+        ));
+        assert!(err
+            .to_string()
+            .ends_with(&format!("({})", std::env::consts::OS)));
+    } else {
+        assert!(matches!(
+            err,
+            user_dirs::UserDirsError::HomeDir(user_dirs::HomeDirError)
+        ));
+    }
+}
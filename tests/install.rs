@@ -0,0 +1,137 @@
+#![cfg(unix)]
+
+use std::path::PathBuf;
+
+use user_dirs::{
+    executable_dir, install_executable, is_executable_dir_on_path, man_dir, man_section_dir,
+    test_util::EnvOverride, InstallError,
+};
+
+#[test]
+fn man_pages_go_in_the_xdg_data_dir() {
+    let _env = EnvOverride::set(&[("HOME", Some("/home/leah")), ("XDG_DATA_HOME", None)]);
+
+    assert_eq!(
+        man_section_dir(1).unwrap(),
+        Some(PathBuf::from("/home/leah/.local/share/man/man1"))
+    );
+}
+
+#[test]
+fn man_dir_follows_xdg_data_home() {
+    let _env = EnvOverride::set(&[("XDG_DATA_HOME", Some("/data"))]);
+
+    assert_eq!(man_dir().unwrap(), Some(PathBuf::from("/data/man")));
+    assert_eq!(
+        man_section_dir(8).unwrap(),
+        Some(PathBuf::from("/data/man/man8"))
+    );
+}
+
+#[test]
+fn executables_are_installed_into_the_bin_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = std::env::temp_dir().join(format!("user_dirs-install-{}", std::process::id()));
+    let bin = root.join("bin");
+    let _env = EnvOverride::set(&[
+        ("XDG_BIN_HOME", Some(bin.as_os_str())),
+        ("PATH", Some("/usr/bin:/bin".as_ref())),
+    ]);
+    assert_eq!(executable_dir().unwrap(), bin);
+    assert!(!is_executable_dir_on_path());
+
+    std::fs::create_dir_all(&root).unwrap();
+    let src = root.join("build-output");
+    std::fs::write(&src, "v1").unwrap();
+    let installed = install_executable(&src, "mytool").unwrap();
+    assert_eq!(installed, bin.join("mytool"));
+    std::fs::write(&src, "v2").unwrap();
+    install_executable(&src, "mytool").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&installed).unwrap(), "v2");
+    let mode = std::fs::metadata(&installed).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+    assert_eq!(std::fs::read_dir(&bin).unwrap().count(), 1);
+    assert!(matches!(
+        install_executable(&src, "../escape"),
+        Err(InstallError::InvalidName(_))
+    ));
+
+    std::env::set_var("PATH", format!("/usr/bin:{}/", bin.display()));
+    assert!(is_executable_dir_on_path());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn executable_dir_defaults_to_local_bin() {
+    let _env = EnvOverride::set(&[("HOME", Some("/home/leah")), ("XDG_BIN_HOME", Some("bin"))]);
+
+    assert_eq!(
+        executable_dir().unwrap(),
+        PathBuf::from("/home/leah/.local/bin")
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn desktop_entries_are_written_to_the_applications_dir() {
+    let root = std::env::temp_dir().join(format!("user_dirs-desktop-{}", std::process::id()));
+    let _env = EnvOverride::set(&[("XDG_DATA_HOME", Some(root.as_os_str()))]);
+    let applications = root.join("applications");
+    assert_eq!(
+        user_dirs::application_dir().unwrap(),
+        Some(applications.clone())
+    );
+
+    let entry = "[Desktop Entry]\nType=Application\nName=My App\nExec=myapp\n";
+    let path = user_dirs::install_desktop_entry("org.example.MyApp", entry).unwrap();
+    assert_eq!(path, applications.join("org.example.MyApp.desktop"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), entry);
+    assert_eq!(std::fs::read_dir(&applications).unwrap().count(), 1);
+
+    for stem in ["", "my app", "a/b", "org..app", "org.3d", ".hidden"] {
+        assert!(
+            matches!(
+                user_dirs::install_desktop_entry(stem, entry),
+                Err(user_dirs::DesktopEntryError::InvalidName(_))
+            ),
+            "{stem:?} was accepted"
+        );
+    }
+
+    let removed = user_dirs::DesktopEntryOptions::new()
+        .touch_dir(true)
+        .uninstall("org.example.MyApp")
+        .unwrap();
+    assert!(removed);
+    assert!(!user_dirs::uninstall_desktop_entry("org.example.MyApp").unwrap());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_install_matches_the_sync_version() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = std::env::temp_dir().join(format!("user_dirs-install-async-{}", std::process::id()));
+    let _env = EnvOverride::set(&[("XDG_BIN_HOME", Some(root.join("bin").as_os_str()))]);
+    std::fs::create_dir_all(&root).unwrap();
+    let src = root.join("build-output");
+    std::fs::write(&src, "binary").unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let installed = runtime
+        .block_on(user_dirs::install_executable_async(&src, "mytool"))
+        .unwrap();
+    assert_eq!(installed, root.join("bin").join("mytool"));
+    let mode = std::fs::metadata(&installed).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+    assert!(matches!(
+        runtime.block_on(user_dirs::install_executable_async(&src, "")),
+        Err(InstallError::InvalidName(_))
+    ));
+    std::fs::remove_dir_all(&root).unwrap();
+}
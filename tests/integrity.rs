@@ -0,0 +1,102 @@
+#![cfg(unix)]
+
+use std::os::unix::fs::{symlink, PermissionsExt};
+
+use user_dirs::{integrity_check, test_util::EnvOverride, DirKind, UserDirsError};
+
+#[test]
+fn every_problem_is_reported() {
+    let root = std::env::temp_dir().join(format!("user_dirs-integrity-{}", std::process::id()));
+    let (cache, data, runtime) = (root.join("cache"), root.join("data"), root.join("runtime"));
+    std::fs::create_dir_all(&runtime).unwrap();
+    std::fs::set_permissions(&runtime, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(&cache, "").unwrap();
+    symlink(root.join("missing"), &data).unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.as_os_str())),
+        ("XDG_CACHE_HOME", Some(cache.as_os_str())),
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(data.as_os_str())),
+        ("XDG_RUNTIME_DIR", Some(runtime.as_os_str())),
+        ("XDG_STATE_HOME", None),
+    ]);
+
+    let errors = integrity_check().unwrap_err();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(
+        matches!(&errors[0], UserDirsError::NotADirectory(DirKind::Cache, path) if *path == cache)
+    );
+    assert!(
+        matches!(&errors[1], UserDirsError::Inaccessible(DirKind::Data, path, _) if *path == data)
+    );
+    assert!(
+        matches!(&errors[2], UserDirsError::InsecurePermissions(DirKind::Runtime, path) if *path == runtime)
+    );
+}
+
+#[test]
+fn missing_directories_pass() {
+    let root = std::env::temp_dir().join(format!("user_dirs-integrity-ok-{}", std::process::id()));
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.as_os_str())),
+        ("XDG_CACHE_HOME", None),
+        ("XDG_CONFIG_HOME", None),
+        ("XDG_DATA_HOME", None),
+        ("XDG_RUNTIME_DIR", None),
+        ("XDG_STATE_HOME", None),
+    ]);
+
+    assert!(integrity_check().is_ok());
+}
+
+#[test]
+fn checked_variants_tell_missing_from_misconfigured() {
+    let root = std::env::temp_dir().join(format!("user_dirs-checked-{}", std::process::id()));
+    let (config, cache, data) = (root.join("config"), root.join("cache"), root.join("data"));
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(&cache, "").unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_CACHE_HOME", Some(cache.as_os_str())),
+        ("XDG_DATA_HOME", Some(data.as_os_str())),
+    ]);
+
+    let results = (
+        user_dirs::config_dir_checked(),
+        user_dirs::cache_dir_checked(),
+        user_dirs::data_dir_checked(),
+    );
+    let data_created = data.exists();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(results.0.unwrap(), Some(config));
+    assert!(
+        matches!(results.1, Err(UserDirsError::NotADirectory(DirKind::Cache, path)) if path == cache)
+    );
+    assert_eq!(results.2.unwrap(), None);
+    assert!(!data_created);
+}
+
+#[test]
+fn all_dirs_exist_requires_every_base_dir() {
+    let root = std::env::temp_dir().join(format!("user_dirs-all-exist-{}", std::process::id()));
+    for kind in [
+        DirKind::Home,
+        DirKind::Data,
+        DirKind::Config,
+        DirKind::Cache,
+    ] {
+        let dir = root.join(kind.to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        user_dirs::env::set_override(kind, dir);
+    }
+
+    assert!(user_dirs::all_dirs_exist());
+    std::fs::remove_dir(root.join("cache")).unwrap();
+    assert!(!user_dirs::all_dirs_exist());
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf, process};
+
+use user_dirs::{
+    env::{clear_override, set_override},
+    DirKind, InstanceLock, InstanceLockError,
+};
+
+fn runtime_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("user-dirs-{name}-{}", process::id()));
+    set_override(DirKind::Runtime, dir.clone());
+    dir
+}
+
+#[test]
+fn second_acquire_reports_the_running_instance() {
+    let runtime = runtime_dir("lock-twice");
+    let lock = InstanceLock::acquire("my-app").unwrap();
+    assert_eq!(lock.path(), runtime.join("my-app").join("instance.lock"));
+
+    let expected_pid = if cfg!(windows) {
+        None
+    } else {
+        Some(process::id())
+    };
+    assert!(matches!(
+        InstanceLock::acquire("my-app"),
+        Err(InstanceLockError::AlreadyRunning { pid }) if pid == expected_pid
+    ));
+
+    drop(lock);
+    clear_override(DirKind::Runtime);
+    fs::remove_dir_all(runtime).unwrap();
+}
+
+#[test]
+fn stale_lock_files_are_taken_over_and_removed_on_drop() {
+    let runtime = runtime_dir("lock-stale");
+    let path = runtime.join("my-app").join("instance.lock");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "4294967295").unwrap();
+
+    let lock = InstanceLock::acquire("my-app").unwrap();
+    #[cfg(not(windows))]
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        process::id().to_string()
+    );
+
+    drop(lock);
+    assert!(!path.exists());
+    assert!(InstanceLock::acquire("my-app").is_ok());
+
+    clear_override(DirKind::Runtime);
+    fs::remove_dir_all(runtime).unwrap();
+}
@@ -0,0 +1,77 @@
+#![cfg(target_os = "macos")]
+
+use std::{fs, path::PathBuf, process};
+
+use user_dirs::{
+    env::{clear_override, set_override},
+    os::macos,
+    DirKind,
+};
+
+fn temp_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("user_dirs-{name}-{}", process::id()));
+    fs::create_dir_all(&home).unwrap();
+    set_override(DirKind::Home, home.clone());
+    home
+}
+
+#[test]
+fn icloud_dirs_are_only_returned_if_they_exist() {
+    let home = temp_home("icloud");
+    let mobile = home.join("Library").join("Mobile Documents");
+
+    assert_eq!(macos::icloud_drive_dir(), None);
+    assert_eq!(macos::icloud_container_dir("iCloud.com.example.app"), None);
+
+    fs::create_dir_all(mobile.join("com~apple~CloudDocs")).unwrap();
+    fs::create_dir_all(mobile.join("iCloud~com~example~app")).unwrap();
+    let drive = macos::icloud_drive_dir();
+    let container = macos::icloud_container_dir("iCloud.com.example.app");
+    let unprefixed = macos::icloud_container_dir("com.example.app");
+    let invalid = macos::icloud_container_dir("com/example");
+    clear_override(DirKind::Home);
+    fs::remove_dir_all(&home).unwrap();
+
+    assert_eq!(drive, Some(mobile.join("com~apple~CloudDocs")));
+    assert_eq!(container, Some(mobile.join("iCloud~com~example~app")));
+    assert_eq!(unprefixed, container);
+    assert_eq!(invalid, None);
+}
+
+#[cfg(not(feature = "native"))]
+#[test]
+fn group_containers_live_under_the_home_library() {
+    let home = temp_home("group");
+    let container = macos::group_container_dir("group.com.example.shared");
+    let invalid: Vec<_> = ["", ".", "..", "a/b", "a:b"]
+        .into_iter()
+        .map(macos::group_container_dir)
+        .collect();
+    clear_override(DirKind::Home);
+    fs::remove_dir_all(&home).unwrap();
+
+    assert_eq!(
+        container,
+        Some(
+            home.join("Library")
+                .join("Group Containers")
+                .join("group.com.example.shared")
+        )
+    );
+    assert!(invalid.iter().all(Option::is_none), "{invalid:?}");
+}
+
+#[test]
+fn darwin_user_dirs_are_complete_existing_paths() {
+    let cache = macos::darwin_user_cache_dir().unwrap();
+    let temp = macos::darwin_user_temp_dir().unwrap();
+
+    for (dir, leaf) in [(&cache, "C"), (&temp, "T")] {
+        let text = dir.to_str().unwrap();
+        assert!(dir.is_absolute(), "{text}");
+        assert!(dir.is_dir(), "{text}");
+        assert!(!text.ends_with('/') && !text.contains('\0'), "{text:?}");
+        assert_eq!(dir.file_name().unwrap(), leaf);
+    }
+    assert_eq!(cache.parent(), temp.parent());
+}
@@ -0,0 +1,147 @@
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+
+use user_dirs::{download_dir, music_dir, test_util::EnvOverride, UserDirKind};
+
+#[test]
+fn user_dirs_defaults_provide_translated_names() {
+    let root = std::env::temp_dir().join(format!("user_dirs-defaults-{}", std::process::id()));
+    let (config, system) = (root.join("config"), root.join("xdg"));
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::create_dir_all(&system).unwrap();
+    std::fs::write(
+        system.join("user-dirs.defaults"),
+        "# Default settings for user directories\nDESKTOP=Bureau\nDOWNLOAD=Téléchargements\n",
+    )
+    .unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah".as_ref())),
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some(system.as_os_str())),
+    ]);
+
+    let download = download_dir();
+    let music = music_dir();
+    std::fs::write(
+        config.join("user-dirs.dirs"),
+        "XDG_DOWNLOAD_DIR=\"$HOME/Downloads\"\n",
+    )
+    .unwrap();
+    let configured = download_dir();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(
+        download.unwrap(),
+        Some(PathBuf::from("/home/leah/Téléchargements"))
+    );
+    assert_eq!(music.unwrap(), Some(PathBuf::from("/home/leah/Music")));
+    assert_eq!(
+        configured.unwrap(),
+        Some(PathBuf::from("/home/leah/Downloads"))
+    );
+}
+
+#[test]
+fn ensure_user_dirs_creates_missing_enabled_dirs_in_home() {
+    let home = std::env::temp_dir().join(format!("user_dirs-ensure-{}", std::process::id()));
+    let config = home.join(".config");
+    std::fs::create_dir_all(home.join("Music")).unwrap();
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("user-dirs.dirs"),
+        "XDG_DESKTOP_DIR=\"$HOME\"\nXDG_PUBLICSHARE_DIR=\"/srv/public\"\n",
+    )
+    .unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(home.as_os_str())),
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some("/nonexistent".as_ref())),
+    ]);
+
+    let planned = user_dirs::ensure_user_dirs(true).unwrap();
+    let downloads_after_dry_run = home.join("Downloads").exists();
+    let created = user_dirs::ensure_user_dirs(false).unwrap();
+    let downloads_created = home.join("Downloads").is_dir();
+    let again = user_dirs::ensure_user_dirs(false).unwrap();
+    std::fs::remove_dir_all(&home).unwrap();
+
+    let kinds: Vec<_> = created.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(planned, created);
+    assert!(!downloads_after_dry_run);
+    assert!(downloads_created);
+    assert!(again.is_empty());
+    assert_eq!(
+        kinds,
+        [
+            UserDirKind::Documents,
+            UserDirKind::Download,
+            UserDirKind::Pictures,
+            UserDirKind::Templates,
+            UserDirKind::Videos
+        ]
+    );
+}
+
+#[test]
+fn only_home_itself_is_expanded_in_user_dirs() {
+    let config = std::env::temp_dir().join(format!("user_dirs-home-prefix-{}", std::process::id()));
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("user-dirs.dirs"),
+        "XDG_DOWNLOAD_DIR=\"$HOMEFOO/Downloads\"\nXDG_MUSIC_DIR=\"$HOME/\"\n",
+    )
+    .unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah".as_ref())),
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some(config.as_os_str())),
+    ]);
+    let (download, music) = (download_dir(), music_dir());
+    std::fs::remove_dir_all(&config).unwrap();
+
+    assert_eq!(
+        download.unwrap(),
+        Some(PathBuf::from("/home/leah/Downloads"))
+    );
+    assert_eq!(music.unwrap(), None);
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_user_dirs_reports_edits_to_user_dirs_file() {
+    use user_dirs::{
+        env::{clear_override, set_override},
+        DirKind,
+    };
+
+    let home = std::env::temp_dir().join(format!("user_dirs-watch-media-{}", std::process::id()));
+    let config = home.join(".config");
+    std::fs::create_dir_all(&config).unwrap();
+    set_override(DirKind::Home, home.clone());
+    set_override(DirKind::Config, config.clone());
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let watcher =
+        user_dirs::watch_user_dirs(move |changes| sender.send(changes.to_vec()).unwrap()).unwrap();
+    clear_override(DirKind::Home);
+    clear_override(DirKind::Config);
+
+    std::fs::write(
+        config.join("user-dirs.dirs"),
+        "XDG_MUSIC_DIR=\"$HOME/Tunes\"\n",
+    )
+    .unwrap();
+    let changes = receiver
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .unwrap();
+    drop(watcher);
+    std::fs::remove_dir_all(&home).unwrap();
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, UserDirKind::Music);
+    assert_eq!(changes[0].new, Some(home.join("Tunes")));
+}
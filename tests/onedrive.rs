@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use user_dirs::{is_cloud_redirected, test_util::EnvOverride};
+
+#[cfg(unix)]
+const ONEDRIVE: &str = "/home/leah/OneDrive";
+#[cfg(windows)]
+const ONEDRIVE: &str = r"C:\Users\Leah\OneDrive";
+
+#[test]
+fn detects_paths_inside_onedrive() {
+    let _env = EnvOverride::set(&[
+        ("OneDrive", Some(ONEDRIVE)),
+        ("OneDriveConsumer", None),
+        ("OneDriveCommercial", None),
+    ]);
+
+    assert!(is_cloud_redirected(&Path::new(ONEDRIVE).join("Documents")));
+    assert!(is_cloud_redirected(Path::new(&ONEDRIVE.to_uppercase())));
+    assert!(!is_cloud_redirected(
+        &Path::new(ONEDRIVE).with_file_name("Documents")
+    ));
+    assert!(!is_cloud_redirected(
+        &Path::new(ONEDRIVE).with_file_name("OneDriveBackup")
+    ));
+}
+
+#[test]
+fn nothing_is_redirected_without_onedrive() {
+    let _env = EnvOverride::set(&[
+        ("OneDrive", None::<&str>),
+        ("OneDriveConsumer", None),
+        ("OneDriveCommercial", None),
+    ]);
+
+    assert!(!is_cloud_redirected(&Path::new(ONEDRIVE).join("Documents")));
+}
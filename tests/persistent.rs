@@ -0,0 +1,32 @@
+#![cfg(unix)]
+
+use user_dirs::{persistent_dirs, test_util::EnvOverride, DirKind, PersistentDirKind};
+
+#[test]
+fn persistent_dirs_skip_cache_and_runtime_and_aliases() {
+    let root = std::env::temp_dir().join(format!("user_dirs-persistent-{}", std::process::id()));
+    let (config, data) = (root.join("config"), root.join("data"));
+    std::fs::create_dir_all(&config).unwrap();
+    std::os::unix::fs::symlink(&config, &data).unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.as_os_str())),
+        ("XDG_CONFIG_HOME", Some(config.as_os_str())),
+        ("XDG_DATA_HOME", Some(data.as_os_str())),
+        ("XDG_STATE_HOME", Some(config.as_os_str())),
+        ("XDG_CACHE_HOME", None),
+        ("XDG_RUNTIME_DIR", Some(root.as_os_str())),
+    ]);
+
+    let base = persistent_dirs(false).unwrap();
+    let with_user = persistent_dirs(true).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(base.len(), 1);
+    assert_eq!(base[0].kind(), PersistentDirKind::Base(DirKind::Config));
+    assert_eq!(base[0].path(), config);
+    assert!(with_user.len() > 1);
+    assert!(with_user
+        .iter()
+        .all(|dir| dir.path() != root && !dir.path().starts_with(root.join(".cache"))));
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use user_dirs::{test_util::EnvOverride, AppDirs, PortableDirs, PortableOptions};
+
+#[test]
+fn portable_dirs_are_next_to_the_executable() {
+    let dirs = PortableDirs::new().unwrap();
+    let exe = std::env::current_exe().unwrap();
+
+    assert_eq!(Some(dirs.base()), exe.parent());
+    assert_eq!(dirs.config(), dirs.base().join("config"));
+}
+
+#[test]
+fn portable_dirs_can_use_any_base() {
+    let dirs = PortableDirs::with_base("/media/usb/myapp");
+
+    assert_eq!(dirs.data(), Path::new("/media/usb/myapp").join("data"));
+    assert_eq!(dirs.cache(), Path::new("/media/usb/myapp").join("cache"));
+}
+
+#[test]
+fn portable_mode_needs_an_explicit_rule() {
+    let _env = EnvOverride::set(&[("MY_APP_PORTABLE", Some("yes"))]);
+    let dirs = AppDirs::new("My App").unwrap();
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+
+    let mut options = PortableOptions::new();
+    options.marker_file(Some(&format!(
+        "user_dirs-portable-{}.txt",
+        std::process::id()
+    )));
+    let unchanged = dirs.clone().with_portable_mode(&options).unwrap();
+    assert_eq!(unchanged, dirs);
+    assert!(unchanged.portable().is_none());
+
+    std::env::set_var("MY_APP_PORTABLE", "1");
+    let portable = dirs.clone().with_portable_mode(&options).unwrap();
+    assert_eq!(portable.config(), exe_dir.join("config"));
+    assert_eq!(portable.state(), Some(exe_dir.join("state").as_path()));
+    assert_eq!(portable.runtime(), dirs.runtime());
+    assert_eq!(
+        portable.with_profile("work").unwrap().data(),
+        exe_dir.join("data").join("profiles").join("work")
+    );
+
+    options.env_var(false);
+    assert_eq!(dirs.clone().with_portable_mode(&options).unwrap(), dirs);
+}
+
+#[test]
+fn portable_mode_follows_a_marker_file() {
+    let _env = EnvOverride::set(&[("MARKED_APP_PORTABLE", None::<&str>)]);
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let marker = format!("user_dirs-marker-{}.txt", std::process::id());
+    let mut options = PortableOptions::new();
+    options.marker_file(Some(&marker)).resolve_symlinks(true);
+
+    std::fs::create_dir_all(exe_dir.join(&marker)).unwrap();
+    let dirs = AppDirs::new("Marked App").unwrap();
+    // A directory is not a marker file.
+    assert!(dirs
+        .clone()
+        .with_portable_mode(&options)
+        .unwrap()
+        .portable()
+        .is_none());
+    std::fs::remove_dir(exe_dir.join(&marker)).unwrap();
+
+    std::fs::write(exe_dir.join(&marker), "").unwrap();
+    let portable = dirs.with_portable_mode(&options);
+    std::fs::remove_file(exe_dir.join(&marker)).unwrap();
+    assert_eq!(
+        portable.unwrap().portable().map(PortableDirs::base),
+        Some(exe_dir.canonicalize().unwrap().as_path())
+    );
+}
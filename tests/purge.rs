@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+use std::os::unix::fs::symlink;
+
+use user_dirs::{test_util::EnvOverride, AppDirs, DirKind, PurgeError, PurgeOptions, PurgedDir};
+
+#[test]
+fn purge_removes_selected_dirs_without_following_symlinks() {
+    let root = std::env::temp_dir().join(format!("user_dirs-purge-{}", std::process::id()));
+    let outside = root.join("outside");
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(outside.join("keep.txt"), "keep").unwrap();
+
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.join("home").as_os_str())),
+        ("XDG_CACHE_HOME", Some(root.join("cache").as_os_str())),
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(root.join("data").as_os_str())),
+        ("XDG_STATE_HOME", None),
+        ("XDG_RUNTIME_DIR", None),
+    ]);
+    let app = AppDirs::new("My App").unwrap();
+    for dir in [app.cache(), app.config(), app.data()] {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("file"), "12345").unwrap();
+    }
+    symlink(&outside, app.data().join("link")).unwrap();
+
+    let mut options = PurgeOptions::new();
+    options.exclude(DirKind::Config).dry_run(true);
+    let report = app.purge(&options).unwrap();
+    assert!(report.is_dry_run());
+    assert_eq!(
+        report
+            .dirs()
+            .iter()
+            .map(PurgedDir::kind)
+            .collect::<Vec<_>>(),
+        [DirKind::Cache, DirKind::Data]
+    );
+    assert!(report.total_size() >= 10);
+    assert!(app.cache().exists());
+
+    let report = app.purge(options.dry_run(false)).unwrap();
+    assert_eq!(report.dirs().len(), 2);
+    assert!(!app.cache().exists() && !app.data().exists());
+    assert!(app.config().join("file").exists());
+    assert!(outside.join("keep.txt").exists());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn purge_refuses_dirs_that_could_be_shared() {
+    let root = std::env::temp_dir().join(format!("user_dirs-purge-unsafe-{}", std::process::id()));
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.as_os_str())),
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(root.as_os_str())),
+    ]);
+    let app = AppDirs::new("My App").unwrap();
+    std::fs::create_dir_all(app.config()).unwrap();
+
+    assert!(matches!(
+        app.purge(&PurgeOptions::new()),
+        Err(PurgeError::Unsafe(DirKind::Data, _))
+    ));
+    assert!(app.config().exists());
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,32 @@
+#![cfg(target_os = "linux")]
+
+use std::path::Path;
+
+use user_dirs::{recently_used_path, test_util::EnvOverride};
+
+#[test]
+fn recently_used_path_respects_xdg_data_home() {
+    let _env = EnvOverride::set(&[("XDG_DATA_HOME", Some("/tmp/xdg-data"))]);
+
+    assert_eq!(
+        recently_used_path().as_deref(),
+        Some(Path::new("/tmp/xdg-data/recently-used.xbel"))
+    );
+}
+
+#[test]
+fn calendar_dir_follows_xdg_data_home_but_os_ignores_it() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah")),
+        ("XDG_DATA_HOME", Some("/tmp/xdg-data")),
+    ]);
+
+    assert_eq!(
+        user_dirs::calendar_dir().unwrap().as_deref(),
+        Some(Path::new("/tmp/xdg-data/evolution/calendar"))
+    );
+    assert_eq!(
+        user_dirs::os::calendar_dir().unwrap().as_deref(),
+        Some(Path::new("/home/leah/.local/share/evolution/calendar"))
+    );
+}
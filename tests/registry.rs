@@ -0,0 +1,24 @@
+#![cfg(all(windows, feature = "registry", not(feature = "native")))]
+
+use user_dirs::test_util::EnvOverride;
+
+#[test]
+fn unset_appdata_is_read_from_the_registry() {
+    let appdata = {
+        let _env = EnvOverride::lock();
+        std::env::var_os("APPDATA").unwrap()
+    };
+    let _env = EnvOverride::set(&[("APPDATA", None::<&str>), ("XDG_CONFIG_HOME", None)]);
+
+    assert_eq!(user_dirs::os::config_dir().unwrap().as_os_str(), appdata);
+}
+
+#[test]
+fn user_dirs_are_read_from_the_registry_expanded() {
+    let config = std::env::temp_dir().join(format!("user_dirs-registry-{}", std::process::id()));
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(config.as_os_str()))]);
+
+    let documents = user_dirs::documents_dir().unwrap().unwrap();
+    assert!(documents.is_absolute(), "{}", documents.display());
+    assert!(!documents.to_string_lossy().contains('%'));
+}
@@ -0,0 +1,422 @@
+use std::{collections::HashMap, path::Path};
+
+use user_dirs::{
+    env::{candidates_for, dir_for, TargetOs},
+    msys::MsysMounts,
+    CandidateSource, DirKind,
+};
+
+fn resolve(kind: DirKind, os: TargetOs, home: &str, env: &[(&str, &str)]) -> Option<String> {
+    let env: HashMap<_, _> = env.iter().copied().collect();
+    dir_for(kind, os, Path::new(home), &env).map(|dir| dir.to_str().unwrap().to_owned())
+}
+
+#[test]
+fn linux_follows_xdg_defaults() {
+    let home = "/home/leah";
+
+    assert_eq!(
+        resolve(DirKind::Home, TargetOs::Linux, home, &[]).as_deref(),
+        Some(home)
+    );
+    assert_eq!(
+        resolve(DirKind::Data, TargetOs::Linux, home, &[]).as_deref(),
+        Some("/home/leah/.local/share")
+    );
+    assert_eq!(
+        resolve(DirKind::Config, TargetOs::Linux, home, &[]).as_deref(),
+        Some("/home/leah/.config")
+    );
+    assert_eq!(
+        resolve(DirKind::Cache, TargetOs::Linux, home, &[]).as_deref(),
+        Some("/home/leah/.cache")
+    );
+    assert_eq!(
+        resolve(DirKind::State, TargetOs::Linux, home, &[]).as_deref(),
+        Some("/home/leah/.local/state")
+    );
+    assert_eq!(resolve(DirKind::Runtime, TargetOs::Linux, home, &[]), None);
+}
+
+#[test]
+fn macos_uses_library() {
+    let home = "/Users/Leah";
+
+    assert_eq!(
+        resolve(DirKind::Data, TargetOs::MacOs, home, &[]).as_deref(),
+        Some("/Users/Leah/Library/Application Support")
+    );
+    assert_eq!(
+        resolve(DirKind::Cache, TargetOs::MacOs, home, &[]).as_deref(),
+        Some("/Users/Leah/Library/Caches")
+    );
+    assert_eq!(resolve(DirKind::State, TargetOs::MacOs, home, &[]), None);
+}
+
+#[test]
+fn windows_uses_app_data_with_backslashes() {
+    let home = r"C:\Users\Leah";
+
+    assert_eq!(
+        resolve(DirKind::Config, TargetOs::Windows, home, &[]).as_deref(),
+        Some(r"C:\Users\Leah\AppData\Roaming")
+    );
+    assert_eq!(
+        resolve(
+            DirKind::Cache,
+            TargetOs::Windows,
+            home,
+            &[("LOCALAPPDATA", r"D:\Local")]
+        )
+        .as_deref(),
+        Some(r"D:\Local")
+    );
+    assert_eq!(resolve(DirKind::State, TargetOs::Windows, home, &[]), None);
+}
+
+#[test]
+fn fuchsia_dirs_are_in_the_component_namespace() {
+    let fuchsia = |kind| resolve(kind, TargetOs::Fuchsia, "", &[]);
+
+    assert_eq!(fuchsia(DirKind::Data).as_deref(), Some("/data"));
+    assert_eq!(fuchsia(DirKind::Config).as_deref(), Some("/data/config"));
+    assert_eq!(fuchsia(DirKind::State).as_deref(), Some("/data/state"));
+    assert_eq!(fuchsia(DirKind::Cache).as_deref(), Some("/cache"));
+    assert_eq!(fuchsia(DirKind::Runtime), None);
+    assert_eq!(
+        candidates_for(
+            DirKind::Cache,
+            TargetOs::Fuchsia,
+            Path::new(""),
+            &HashMap::<&str, &str>::new()
+        )
+        .iter()
+        .map(|candidate| candidate.path().to_str().unwrap())
+        .collect::<Vec<_>>(),
+        ["/cache"]
+    );
+}
+
+#[test]
+fn xdg_variables_take_precedence_on_every_os() {
+    for (os, home, state) in [
+        (TargetOs::Linux, "/home/leah", "/srv/state"),
+        (TargetOs::MacOs, "/Users/Leah", "/srv/state"),
+        (TargetOs::Windows, r"C:\Users\Leah", r"D:\state"),
+        (TargetOs::Other, "/home/leah", "/srv/state"),
+        (TargetOs::Fuchsia, "", "/srv/state"),
+    ] {
+        assert_eq!(
+            resolve(DirKind::State, os, home, &[("XDG_STATE_HOME", state)]).as_deref(),
+            Some(state)
+        );
+    }
+}
+
+#[test]
+fn relative_xdg_variables_are_ignored() {
+    assert_eq!(
+        resolve(
+            DirKind::Config,
+            TargetOs::Linux,
+            "/home/leah",
+            &[("XDG_CONFIG_HOME", "relative")]
+        )
+        .as_deref(),
+        Some("/home/leah/.config")
+    );
+    assert_eq!(
+        resolve(
+            DirKind::Config,
+            TargetOs::Windows,
+            r"C:\Users\Leah",
+            &[("XDG_CONFIG_HOME", "/config")]
+        )
+        .as_deref(),
+        Some(r"C:\Users\Leah\AppData\Roaming")
+    );
+    assert_eq!(
+        resolve(
+            DirKind::Config,
+            TargetOs::Windows,
+            r"C:\Users\Leah",
+            &[("XDG_CONFIG_HOME", r"D:\config")]
+        )
+        .as_deref(),
+        Some(r"D:\config")
+    );
+    assert_eq!(
+        resolve(
+            DirKind::Runtime,
+            TargetOs::Linux,
+            "/home/leah",
+            &[("XDG_RUNTIME_DIR", "")]
+        ),
+        None
+    );
+}
+
+#[test]
+fn candidates_put_the_preferred_directory_first() {
+    let candidates = |os, home: &str, env: &[(&str, &str)]| {
+        let env: HashMap<_, _> = env.iter().copied().collect();
+        candidates_for(DirKind::Config, os, Path::new(home), &env)
+            .into_iter()
+            .map(|candidate| {
+                (
+                    candidate.path().to_str().unwrap().to_owned(),
+                    candidate.source(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let owned = |list: &[(&str, CandidateSource)]| {
+        list.iter()
+            .map(|&(path, source)| (path.to_owned(), source))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        candidates(
+            TargetOs::MacOs,
+            "/Users/Leah",
+            &[("XDG_CONFIG_HOME", "/Users/Leah/.config")]
+        ),
+        owned(&[
+            ("/Users/Leah/.config", CandidateSource::Xdg),
+            (
+                "/Users/Leah/Library/Preferences",
+                CandidateSource::PlatformDefault
+            ),
+            (
+                "/Users/Leah/Library/Application Support",
+                CandidateSource::Alternative
+            ),
+        ])
+    );
+    assert_eq!(
+        candidates(
+            TargetOs::Windows,
+            r"C:\Users\Leah",
+            &[("APPDATA", r"D:\Roaming"), ("LOCALAPPDATA", r"D:\Local")]
+        ),
+        owned(&[
+            (r"D:\Roaming", CandidateSource::PlatformDefault),
+            (r"C:\Users\Leah\.config", CandidateSource::XdgDefault),
+            (r"D:\Local", CandidateSource::EnvVar("LOCALAPPDATA")),
+            (
+                r"C:\Users\Leah\AppData\Roaming",
+                CandidateSource::Alternative
+            ),
+        ])
+    );
+    assert_eq!(
+        candidates(TargetOs::Linux, "/home/leah", &[]),
+        owned(&[("/home/leah/.config", CandidateSource::PlatformDefault)])
+    );
+}
+
+#[test]
+fn dir_kinds_sort_alphabetically() {
+    let mut kinds = DirKind::ALL;
+    kinds.sort();
+
+    assert_eq!(
+        kinds,
+        [
+            DirKind::Cache,
+            DirKind::Config,
+            DirKind::Data,
+            DirKind::Home,
+            DirKind::Runtime,
+            DirKind::State
+        ]
+    );
+}
+
+#[test]
+fn windows_values_have_variables_expanded() {
+    let env = [
+        ("USERPROFILE", r"C:\Users\Leah"),
+        ("APPDATA", r"%USERPROFILE%\AppData\Roaming"),
+        ("LOCALAPPDATA", r"%UNSET%\Local"),
+        ("XDG_DATA_HOME", r"%USERPROFILE%\.local\share"),
+    ];
+
+    assert_eq!(
+        resolve(DirKind::Config, TargetOs::Windows, r"C:\Users\Leah", &env).as_deref(),
+        Some(r"C:\Users\Leah\AppData\Roaming")
+    );
+    assert_eq!(
+        resolve(DirKind::Data, TargetOs::Windows, r"C:\Users\Leah", &env).as_deref(),
+        Some(r"C:\Users\Leah\.local\share")
+    );
+    assert_eq!(
+        resolve(DirKind::Cache, TargetOs::Windows, r"C:\Users\Leah", &env).as_deref(),
+        Some(r"%UNSET%\Local")
+    );
+    // Other platforms don't expand Windows-style references.
+    assert_eq!(
+        resolve(
+            DirKind::Data,
+            TargetOs::Linux,
+            "/home/leah",
+            &[("XDG_DATA_HOME", "%HOME%/data"), ("HOME", "/home/leah")]
+        )
+        .as_deref(),
+        Some("/home/leah/.local/share")
+    );
+}
+
+#[test]
+fn msys_paths_take_the_form_of_the_target() {
+    let env = [
+        ("MSYSTEM", "MINGW64"),
+        ("WD", r"C:\msys64\usr\bin\"),
+        ("HOME", "/home/leah"),
+        ("APPDATA", r"C:\Users\Leah\AppData\Roaming"),
+        ("XDG_DATA_HOME", "/home/leah/.local/share"),
+        ("XDG_CACHE_HOME", "/d/cache"),
+    ];
+    let home = r"C:\Users\Leah";
+
+    assert_eq!(
+        resolve(DirKind::Config, TargetOs::Windows, home, &env).as_deref(),
+        Some(r"C:\Users\Leah\AppData\Roaming")
+    );
+    assert_eq!(
+        resolve(DirKind::Data, TargetOs::Windows, home, &env).as_deref(),
+        Some(r"C:\msys64\home\leah\.local\share")
+    );
+    assert_eq!(
+        resolve(DirKind::Cache, TargetOs::Windows, home, &env).as_deref(),
+        Some(r"D:\cache")
+    );
+    assert_eq!(
+        resolve(
+            DirKind::Config,
+            TargetOs::Other,
+            "/home/leah",
+            &[("OSTYPE", "cygwin"), ("XDG_CONFIG_HOME", r"C:\config")]
+        )
+        .as_deref(),
+        Some("/cygdrive/c/config")
+    );
+}
+
+#[test]
+fn msys_mounts_round_trip() {
+    let mounts = MsysMounts::msys2(Some(r"C:\msys64"));
+
+    for (posix, windows) in [
+        ("/", r"C:\msys64"),
+        ("/home/leah", r"C:\msys64\home\leah"),
+        ("/usr/bin/bash", r"C:\msys64\usr\bin\bash"),
+        ("/c/Users/Leah", r"C:\Users\Leah"),
+        ("/d", r"D:\"),
+    ] {
+        assert_eq!(mounts.to_windows_form(posix).as_deref(), Some(windows));
+        assert_eq!(mounts.to_posix_form(windows).as_deref(), Some(posix));
+    }
+    assert_eq!(
+        mounts.to_windows_form("/bin/ls").as_deref(),
+        Some(r"C:\msys64\usr\bin\ls")
+    );
+    assert_eq!(mounts.to_windows_form("relative"), None);
+    assert_eq!(mounts.to_posix_form("/already/posix"), None);
+    assert_eq!(
+        MsysMounts::cygwin(None)
+            .to_windows_form("/cygdrive/c/x")
+            .as_deref(),
+        Some(r"C:\x")
+    );
+    assert_eq!(MsysMounts::cygwin(None).to_windows_form("/home/leah"), None);
+}
+
+#[test]
+fn overrides_only_apply_to_the_current_thread() {
+    #[cfg(unix)]
+    let config = Path::new("/srv/test-config");
+    #[cfg(windows)]
+    let config = Path::new(r"D:\test-config");
+
+    user_dirs::env::set_override(DirKind::Config, config.to_path_buf());
+    assert_eq!(user_dirs::config_dir().unwrap(), config);
+    let dirs = user_dirs::UserDirs::new().unwrap();
+    assert_eq!(dirs.config(), config);
+    assert_eq!(
+        dirs.source(DirKind::Config),
+        Some(user_dirs::DirSource::Override)
+    );
+
+    let elsewhere = std::thread::spawn(|| user_dirs::config_dir().unwrap())
+        .join()
+        .unwrap();
+    assert_ne!(elsewhere, config);
+
+    user_dirs::env::clear_override(DirKind::Config);
+    assert_eq!(user_dirs::config_dir().unwrap(), elsewhere);
+}
+
+#[test]
+fn every_kind_is_in_the_resolved_map() {
+    #[cfg(unix)]
+    let data = Path::new("/srv/test-data");
+    #[cfg(windows)]
+    let data = Path::new(r"D:\test-data");
+    user_dirs::env::set_override(DirKind::Data, data.to_path_buf());
+
+    let map = user_dirs::resolve_all_to_map().unwrap();
+    user_dirs::env::clear_override(DirKind::Data);
+
+    assert_eq!(map.len(), DirKind::ALL.len());
+    assert_eq!(map[&DirKind::Data].as_deref(), Some(data));
+    assert_eq!(map[&DirKind::Home], Some(user_dirs::home_dir().unwrap()));
+}
+
+#[test]
+fn or_default_functions_return_the_resolved_directory() {
+    use user_dirs::DirFallback;
+
+    #[cfg(unix)]
+    let cache = Path::new("/srv/test-cache");
+    #[cfg(windows)]
+    let cache = Path::new(r"D:\test-cache");
+
+    user_dirs::env::set_override(DirKind::Cache, cache.to_path_buf());
+    assert_eq!(
+        user_dirs::cache_dir_or_default(),
+        (cache.to_path_buf(), DirFallback::Resolved)
+    );
+    user_dirs::env::clear_override(DirKind::Cache);
+
+    let _env = user_dirs::test_util::EnvOverride::lock();
+    let (state, fallback) = user_dirs::state_dir_or_default();
+    assert_eq!(fallback, DirFallback::Resolved);
+    let expected = user_dirs::state_dir().unwrap();
+    assert_eq!(
+        state,
+        expected.unwrap_or_else(|| user_dirs::data_dir().unwrap())
+    );
+
+    assert!(DirFallback::Resolved < DirFallback::TempDir);
+    assert!(DirFallback::TempDir < DirFallback::CurrentDir);
+}
+
+#[test]
+fn overridden_config_dir_is_the_first_candidate() {
+    let dir = std::env::temp_dir().join("overridden-config");
+    user_dirs::env::set_override(DirKind::Config, dir.clone());
+    let candidates = user_dirs::config_dir_candidates().unwrap();
+    user_dirs::env::clear_override(DirKind::Config);
+
+    assert_eq!(candidates[0].path(), dir);
+    assert_eq!(candidates[0].source(), CandidateSource::Override);
+    assert_eq!(
+        candidates
+            .iter()
+            .filter(|candidate| candidate.path() == dir)
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,73 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use user_dirs::{
+    env::{clear_override, set_override},
+    runtime_scratch_file,
+    test_util::EnvOverride,
+    DirKind, ScratchFileError, ScratchFileOptions,
+};
+
+fn runtime_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("user-dirs-{name}-{}", std::process::id()));
+    set_override(DirKind::Runtime, dir.clone());
+    dir
+}
+
+#[test]
+fn scratch_files_are_created_privately() {
+    let runtime = runtime_dir("scratch-private");
+    let scratch = runtime_scratch_file("my-app").unwrap();
+    clear_override(DirKind::Runtime);
+
+    assert_eq!(scratch.path().parent(), Some(&*runtime.join("my-app")));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(scratch.path()), 0o600);
+        assert_eq!(mode(&runtime.join("my-app")), 0o700);
+    }
+
+    drop(scratch);
+    fs::remove_dir_all(runtime).unwrap();
+}
+
+#[test]
+fn scratch_files_are_removed_on_drop_unless_kept() {
+    let runtime = runtime_dir("scratch-drop");
+    let mut dropped = runtime_scratch_file("my-app").unwrap();
+    let kept = runtime_scratch_file("my-app").unwrap();
+    clear_override(DirKind::Runtime);
+    assert_ne!(dropped.path(), kept.path());
+
+    dropped.file_mut().write_all(b"scratch").unwrap();
+    let dropped_path = dropped.path().to_owned();
+    drop(dropped);
+    assert!(!dropped_path.exists());
+
+    let (file, kept_path) = kept.keep();
+    drop(file);
+    assert!(kept_path.is_file());
+
+    fs::remove_dir_all(runtime).unwrap();
+}
+
+#[test]
+fn scratch_files_need_a_runtime_dir_unless_falling_back() {
+    let _env = EnvOverride::set(&[("XDG_RUNTIME_DIR", None::<&str>)]);
+
+    assert!(matches!(
+        runtime_scratch_file("user-dirs-scratch-fallback"),
+        Err(ScratchFileError::NoRuntimeDir)
+    ));
+
+    let scratch = ScratchFileOptions::new()
+        .allow_tmp_fallback(true)
+        .create("user-dirs-scratch-fallback")
+        .unwrap();
+    let dir = scratch.path().parent().unwrap().to_owned();
+    assert!(dir.starts_with(std::env::temp_dir()) || cfg!(target_os = "macos"));
+    drop(scratch);
+    fs::remove_dir(dir).unwrap();
+}
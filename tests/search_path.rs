@@ -0,0 +1,273 @@
+#![cfg(unix)]
+
+use std::path::PathBuf;
+
+use user_dirs::{
+    cfg_dirs_for, config_dirs, find_project_config, system_config_dirs, system_data_dirs,
+    test_util::EnvOverride, ProjectConfigOptions,
+};
+
+#[test]
+fn system_dirs_default_to_the_xdg_specification() {
+    let _env = EnvOverride::set(&[
+        ("XDG_DATA_DIRS", None::<&str>),
+        ("XDG_CONFIG_DIRS", Some("")),
+    ]);
+
+    assert_eq!(
+        system_data_dirs().collect::<Vec<_>>(),
+        [
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share")
+        ]
+    );
+    assert_eq!(
+        system_config_dirs().collect::<Vec<_>>(),
+        [PathBuf::from("/etc/xdg")]
+    );
+}
+
+#[test]
+fn relative_entries_are_ignored() {
+    let _env = EnvOverride::set(&[(
+        "XDG_DATA_DIRS",
+        Some("/opt/share:relative/share::/usr/share"),
+    )]);
+
+    assert_eq!(
+        system_data_dirs().collect::<Vec<_>>(),
+        [PathBuf::from("/opt/share"), PathBuf::from("/usr/share")]
+    );
+}
+
+#[test]
+fn user_dir_comes_first() {
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some("/home/leah/.config")),
+        ("XDG_CONFIG_DIRS", Some("/etc/xdg")),
+    ]);
+
+    assert_eq!(
+        config_dirs().collect::<Vec<_>>(),
+        [
+            PathBuf::from("/home/leah/.config"),
+            PathBuf::from("/etc/xdg")
+        ]
+    );
+}
+
+#[test]
+fn config_files_are_found_in_precedence_order_without_duplicates() {
+    let root = std::env::temp_dir().join(format!("user_dirs-config-files-{}", std::process::id()));
+    let (user, system) = (root.join("user"), root.join("system"));
+    for dir in [&user, &system, &root.join("empty")] {
+        std::fs::create_dir_all(dir.join("app")).unwrap();
+    }
+    std::fs::write(user.join("app/config.toml"), "").unwrap();
+    std::fs::write(system.join("app/config.toml"), "").unwrap();
+
+    let dirs = std::env::join_paths([&system, &root.join("empty"), &system]).unwrap();
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some(user.as_os_str())),
+        ("XDG_CONFIG_DIRS", Some(dirs.as_os_str())),
+    ]);
+
+    let files = user_dirs::find_all_config_files("app/config.toml");
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(
+        files,
+        [user.join("app/config.toml"), system.join("app/config.toml")]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn font_search_paths_put_user_dirs_first() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah")),
+        ("XDG_DATA_HOME", None),
+        ("XDG_DATA_DIRS", Some("/usr/share:/usr/share")),
+    ]);
+
+    assert_eq!(
+        user_dirs::font_search_paths(false),
+        [
+            PathBuf::from("/home/leah/.local/share/fonts"),
+            PathBuf::from("/home/leah/.fonts"),
+            PathBuf::from("/usr/share/fonts"),
+        ]
+    );
+}
+
+#[test]
+fn extra_data_dirs_come_first() {
+    let _env = EnvOverride::set(&[
+        ("MYAPP_DATA_DIRS", Some("/opt/myapp/share:relative")),
+        ("XDG_DATA_HOME", Some("/home/leah/.local/share")),
+        ("XDG_DATA_DIRS", Some("/usr/share")),
+    ]);
+
+    assert_eq!(
+        user_dirs::data_dirs_with_extra("MYAPP_DATA_DIRS").collect::<Vec<_>>(),
+        [
+            PathBuf::from("/opt/myapp/share"),
+            PathBuf::from("/home/leah/.local/share"),
+            PathBuf::from("/usr/share"),
+        ]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn themes_search_paths_include_the_legacy_dir() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah")),
+        ("XDG_DATA_HOME", None),
+        ("XDG_DATA_DIRS", Some("/usr/share")),
+    ]);
+
+    assert_eq!(
+        user_dirs::themes_dir().unwrap(),
+        Some(PathBuf::from("/home/leah/.local/share/themes"))
+    );
+    assert_eq!(
+        user_dirs::themes_search_paths(false),
+        [
+            PathBuf::from("/home/leah/.local/share/themes"),
+            PathBuf::from("/home/leah/.themes"),
+            PathBuf::from("/usr/share/themes"),
+        ]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn sounds_dirs_follow_data_dirs() {
+    let _env = EnvOverride::set(&[
+        ("XDG_DATA_HOME", Some("/home/leah/.local/share")),
+        ("XDG_DATA_DIRS", Some("/usr/local/share:/usr/share")),
+    ]);
+
+    assert_eq!(
+        user_dirs::sounds_dir().unwrap(),
+        Some(PathBuf::from("/home/leah/.local/share/sounds"))
+    );
+    assert_eq!(
+        user_dirs::sounds_search_paths(false),
+        [
+            PathBuf::from("/home/leah/.local/share/sounds"),
+            PathBuf::from("/usr/local/share/sounds"),
+            PathBuf::from("/usr/share/sounds"),
+        ]
+    );
+}
+
+#[test]
+fn dir_iterators_are_snapshots() {
+    let _env = EnvOverride::set(&[("XDG_DATA_DIRS", Some("/a:/b"))]);
+    let dirs = user_dirs::system_data_dirs();
+    std::env::set_var("XDG_DATA_DIRS", "/c");
+
+    let copy: user_dirs::XdgDataDirsIter = dirs.clone();
+    assert_eq!(dirs.len(), 2);
+    assert_eq!(
+        dirs.collect::<Vec<_>>(),
+        [PathBuf::from("/a"), PathBuf::from("/b")]
+    );
+    assert_eq!(copy.count(), 2);
+}
+
+#[test]
+fn dir_iterators_know_their_length() {
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some("/home/leah/.config")),
+        ("XDG_CONFIG_DIRS", Some("/etc/xdg:relative:/opt/xdg")),
+    ]);
+    let mut dirs = config_dirs();
+
+    assert_eq!(dirs.len(), 3);
+    dirs.next();
+    assert_eq!(dirs.len(), 2);
+}
+
+#[test]
+fn project_configs_are_found_in_the_nearest_parent() {
+    let root = std::env::temp_dir()
+        .canonicalize()
+        .unwrap()
+        .join(format!("user_dirs-project-{}", std::process::id()));
+    let repo = root.join("repo");
+    let deep = repo.join("src").join("deep");
+    std::fs::create_dir_all(&deep).unwrap();
+    std::fs::create_dir_all(repo.join(".git")).unwrap();
+    std::fs::write(root.join(".myapp.toml"), "").unwrap();
+    std::fs::create_dir(repo.join("src").join(".myapp")).unwrap();
+    let names = [".myapp.toml", ".myapp"];
+
+    assert_eq!(
+        find_project_config(&names, &deep),
+        Some(repo.join("src").join(".myapp"))
+    );
+    assert_eq!(
+        find_project_config(&names, &repo),
+        Some(root.join(".myapp.toml"))
+    );
+    assert_eq!(find_project_config(&[".nothing-here"], &deep), None);
+
+    let mut options = ProjectConfigOptions::new();
+    assert_eq!(options.stop_at_git(true).find(&names, &repo), None);
+    assert_eq!(
+        options
+            .stop_at_git(false)
+            .boundary(Some(&root))
+            .find(&names, &repo),
+        None
+    );
+
+    // The parents of a symbolic link are those of its target.
+    let link = root.join("link");
+    std::os::unix::fs::symlink(&deep, &link).unwrap();
+    assert_eq!(
+        find_project_config(&names, &link.join("..").join("deep")),
+        Some(repo.join("src").join(".myapp"))
+    );
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn cfg_dirs_for_ends_with_etc() {
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some("/home/user/.config")),
+        ("XDG_CONFIG_DIRS", Some("/etc/xdg:/etc/xdg:/opt/etc")),
+    ]);
+
+    assert_eq!(
+        cfg_dirs_for("My App").collect::<Vec<_>>(),
+        [
+            PathBuf::from("/home/user/.config/my-app"),
+            PathBuf::from("/etc/xdg/my-app"),
+            PathBuf::from("/opt/etc/my-app"),
+            PathBuf::from("/etc/my-app"),
+        ]
+    );
+    assert_eq!(cfg_dirs_for("!!!").count(), 0);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn sounds_search_paths_can_skip_missing_dirs() {
+    let root = std::env::temp_dir().join(format!("user_dirs-sounds-{}", std::process::id()));
+    let (user, system) = (root.join("user"), root.join("system"));
+    std::fs::create_dir_all(system.join("sounds")).unwrap();
+    let _env = EnvOverride::set(&[
+        ("XDG_DATA_HOME", Some(user.as_os_str())),
+        ("XDG_DATA_DIRS", Some(system.as_os_str())),
+    ]);
+
+    let paths = user_dirs::sounds_search_paths(true);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(paths, [system.join("sounds")]);
+}
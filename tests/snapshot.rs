@@ -0,0 +1,335 @@
+use std::path::Path;
+
+use user_dirs::{test_util::EnvOverride, DirKind, DirSource, UserDirs};
+
+#[cfg(unix)]
+const HOME: &str = "/home/leah";
+#[cfg(windows)]
+const HOME: &str = r"C:\Users\Leah";
+
+#[test]
+fn tilde_is_expanded_when_enabled() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(HOME)),
+        ("USERPROFILE", Some(HOME)),
+        ("XDG_CONFIG_HOME", Some("~/.config")),
+        ("XDG_STATE_HOME", Some("~")),
+    ]);
+
+    let dirs = UserDirs::builder()
+        .expand_tilde_in_xdg(true)
+        .build()
+        .unwrap();
+    assert_eq!(dirs.config(), Path::new(HOME).join(".config"));
+    assert_eq!(dirs.state(), Some(Path::new(HOME)));
+}
+
+#[test]
+fn tilde_is_not_expanded_by_default() {
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some("~/.config"))]);
+
+    // Taken literally, the value is a relative path, which is ignored.
+    assert_eq!(
+        UserDirs::new().unwrap().config(),
+        user_dirs::os::config_dir().unwrap()
+    );
+}
+
+#[test]
+fn reload_keeps_builder_options() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(HOME)),
+        ("USERPROFILE", Some(HOME)),
+        ("XDG_CONFIG_HOME", Some("~/.config")),
+    ]);
+    let mut dirs = UserDirs::builder()
+        .expand_tilde_in_xdg(true)
+        .build()
+        .unwrap();
+
+    // The override is still held, so this is restored along with the other variables.
+    std::env::set_var("XDG_CONFIG_HOME", "~/.config-new");
+    dirs.reload().unwrap();
+    assert_eq!(dirs.config(), Path::new(HOME).join(".config-new"));
+}
+
+#[test]
+fn has_changed_since_compares_paths() {
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some("~/dotfiles"))]);
+    let literal = UserDirs::new().unwrap();
+    let expanded = UserDirs::builder()
+        .expand_tilde_in_xdg(true)
+        .build()
+        .unwrap();
+
+    assert!(!literal.has_changed_since(&literal.clone()));
+    assert!(expanded.has_changed_since(&literal));
+}
+
+#[test]
+fn debug_string_marks_the_source_of_each_dir() {
+    #[cfg(unix)]
+    let cache = "/srv/cache";
+    #[cfg(windows)]
+    let cache = r"D:\cache";
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(HOME)),
+        ("USERPROFILE", Some(HOME)),
+        ("XDG_CACHE_HOME", Some(cache)),
+        ("XDG_CONFIG_HOME", None),
+    ]);
+    let dirs = UserDirs::new().unwrap();
+
+    assert_eq!(dirs.source(DirKind::Cache), Some(DirSource::Xdg));
+    assert_eq!(dirs.source(DirKind::Config), Some(DirSource::Default));
+
+    let debug = dirs.to_debug_string();
+    let mut lines = debug.lines();
+    assert_eq!(lines.next(), Some(format!("HOME     = {HOME}").as_str()));
+    assert_eq!(
+        lines.next(),
+        Some(format!("CACHE    = {cache} [XDG]").as_str())
+    );
+    assert!(lines.next().unwrap().ends_with(" [DEFAULT]"));
+}
+
+#[test]
+#[cfg(unix)]
+fn sensitive_dirs_hide_the_home_directory() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/alice")),
+        ("XDG_CONFIG_HOME", None),
+        ("XDG_CACHE_HOME", Some("/var/cache/alice")),
+    ]);
+    let dirs = user_dirs::SensitiveDirs::new(UserDirs::new().unwrap());
+
+    let debug = format!("{dirs:?}");
+    assert!(debug.starts_with(
+        r#"SensitiveDirs { home: "<HOME>", cache: "/var/cache/alice", config: "<HOME>/"#
+    ));
+    assert_eq!(dirs.inner().home(), Path::new("/home/alice"));
+}
+
+#[test]
+fn env_vars_describe_every_xdg_dir() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+
+    let vars = dirs.to_env_vars();
+    assert!(vars.contains(&("XDG_CONFIG_HOME", dirs.config())));
+    assert!(vars.contains(&("XDG_DATA_HOME", dirs.data())));
+    assert!(vars.contains(&("XDG_CACHE_HOME", dirs.cache())));
+    assert!(vars.iter().all(|(name, _)| name.starts_with("XDG_")));
+
+    let mut cmd = std::process::Command::new("true");
+    dirs.apply_to_command(&mut cmd);
+    assert!(
+        cmd.get_envs()
+            .any(|(name, value)| name == "XDG_CONFIG_HOME"
+                && value == Some(dirs.config().as_os_str()))
+    );
+}
+
+#[test]
+fn setters_require_absolute_paths() {
+    let _env = EnvOverride::lock();
+    let mut dirs = UserDirs::new().unwrap();
+    let data = Path::new(HOME).join("project-data");
+
+    dirs.set_data(data.clone()).unwrap();
+    assert_eq!(dirs.data(), data);
+    assert_eq!(dirs.source(DirKind::Data), Some(DirSource::Override));
+
+    let config = dirs.config().to_path_buf();
+    assert!(matches!(
+        dirs.set_config("relative".into()),
+        Err(user_dirs::UserDirsError::NotAbsolute(DirKind::Config, _))
+    ));
+    assert_eq!(dirs.config(), config);
+
+    dirs.reload().unwrap();
+    assert_ne!(dirs.data(), data);
+}
+
+#[test]
+fn frozen_snapshots_are_shared_across_threads() {
+    let _env = EnvOverride::lock();
+    let dirs = UserDirs::new().unwrap();
+    let frozen = dirs.clone().freeze();
+
+    let shared = std::sync::Arc::clone(&frozen);
+    let config = std::thread::spawn(move || shared.config().to_path_buf())
+        .join()
+        .unwrap();
+    assert_eq!(config, dirs.config());
+    assert_eq!(*frozen, dirs);
+}
+
+#[test]
+#[cfg(all(unix, feature = "unicode-path"))]
+fn non_unicode_paths_are_rejected() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let config = OsStr::from_bytes(b"/home/leah/\xFF");
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(config))]);
+
+    assert!(matches!(
+        UserDirs::new(),
+        Err(user_dirs::UserDirsError::NonUtf8Path(path)) if path == Path::new(config)
+    ));
+    assert!(user_dirs::config_dir().is_ok());
+}
+
+#[test]
+fn is_default_ignores_the_runtime_dir() {
+    #[cfg(unix)]
+    let (runtime, state) = ("/run/user/1000", "/srv/state");
+    #[cfg(windows)]
+    let (runtime, state) = (r"C:\run", r"D:\state");
+    let _env = EnvOverride::set(&[
+        ("XDG_CACHE_HOME", None),
+        ("XDG_CONFIG_HOME", Some("relative")),
+        ("XDG_DATA_HOME", None),
+        ("XDG_STATE_HOME", None),
+        ("XDG_RUNTIME_DIR", Some(runtime)),
+    ]);
+    assert!(UserDirs::new().unwrap().is_default());
+
+    std::env::set_var("XDG_STATE_HOME", state);
+    let mut dirs = UserDirs::new().unwrap();
+    assert!(!dirs.is_default());
+
+    std::env::remove_var("XDG_STATE_HOME");
+    dirs.reload().unwrap();
+    dirs.set_cache(Path::new(state).join("cache")).unwrap();
+    assert!(!dirs.is_default());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn snap_strategy_rebases_default_dirs() {
+    let _env = EnvOverride::set(&[
+        ("HOME", Some("/home/leah/snap/editor/42")),
+        ("SNAP", Some("/snap/editor/42")),
+        ("SNAP_USER_DATA", Some("/home/leah/snap/editor/42")),
+        ("SNAP_USER_COMMON", Some("/home/leah/snap/editor/common")),
+        ("XDG_CONFIG_HOME", None),
+        ("XDG_CACHE_HOME", Some("/srv/cache")),
+    ]);
+
+    let dirs = UserDirs::builder()
+        .snap_strategy(user_dirs::SnapStrategy::UserCommon)
+        .build()
+        .unwrap();
+    assert!(user_dirs::is_snap());
+    assert_eq!(dirs.home(), Path::new("/home/leah/snap/editor/42"));
+    assert_eq!(
+        dirs.config(),
+        Path::new("/home/leah/snap/editor/common/.config")
+    );
+    assert_eq!(dirs.cache(), Path::new("/srv/cache"));
+
+    std::env::remove_var("SNAP");
+    let dirs = UserDirs::builder()
+        .snap_strategy(user_dirs::SnapStrategy::UserCommon)
+        .build()
+        .unwrap();
+    assert_eq!(
+        dirs.config(),
+        Path::new("/home/leah/snap/editor/42/.config")
+    );
+}
+
+#[test]
+fn try_all_exist_reports_each_missing_dir() {
+    let root = std::env::temp_dir().join(format!("user_dirs-all-exist-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("config")).unwrap();
+    std::fs::write(root.join("data"), "").unwrap();
+    let _env = EnvOverride::set(&[
+        ("HOME", Some(root.as_os_str())),
+        ("USERPROFILE", Some(root.as_os_str())),
+        ("XDG_CACHE_HOME", Some(root.join("cache").as_os_str())),
+        ("XDG_CONFIG_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_DATA_HOME", Some(root.join("data").as_os_str())),
+        ("XDG_STATE_HOME", Some(root.join("config").as_os_str())),
+        ("XDG_RUNTIME_DIR", None),
+    ]);
+
+    let errors = UserDirs::new().unwrap().try_all_exist().unwrap_err();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let kinds: Vec<_> = errors.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(kinds, [DirKind::Cache, DirKind::Data]);
+    assert_eq!(errors[0].1.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(errors[1].1.kind(), std::io::ErrorKind::NotADirectory);
+}
+
+#[test]
+fn compare_lists_changed_dirs() {
+    let _env = EnvOverride::lock();
+    let old = UserDirs::new().unwrap();
+    let mut new = old.clone();
+    assert!(!user_dirs::compare(&old, &new).has_changes());
+
+    let data = Path::new(HOME).join("elsewhere");
+    new.set_data(data.clone()).unwrap();
+    let diff = user_dirs::compare(&old, &new);
+
+    assert!(diff.has_changes());
+    assert_eq!(
+        diff.changed().collect::<Vec<_>>(),
+        [(DirKind::Data, &old.data().to_path_buf(), &data)]
+    );
+    assert_eq!(
+        diff.to_string(),
+        format!(
+            "-data = {}\n+data = {}\n",
+            old.data().display(),
+            data.display()
+        )
+    );
+}
+
+#[test]
+fn copy_to_creates_every_dir_under_the_destination() {
+    let dest = std::env::temp_dir().join(format!("user_dirs-copy-{}", std::process::id()));
+    let home = Path::new("/home/leah");
+    let defaults = UserDirs::default_for_home(home);
+    assert_eq!(defaults.runtime(), None);
+    assert!(defaults.config().starts_with(home));
+
+    let mut outside = defaults.clone();
+    outside
+        .set_runtime(dest.join("elsewhere").join("runtime"))
+        .unwrap();
+    let copy = outside.copy_to(&dest).unwrap();
+
+    assert_eq!(copy.home(), dest);
+    assert_eq!(
+        copy.config(),
+        dest.join(defaults.config().strip_prefix(home).unwrap())
+    );
+    assert!(copy.runtime().unwrap().ends_with("elsewhere/runtime"));
+    assert!(copy.runtime().unwrap().starts_with(&dest));
+    for kind in DirKind::ALL {
+        if let Some(dir) = copy.get(kind) {
+            assert!(dir.is_dir(), "{kind} directory {dir:?} was not created");
+        }
+    }
+    assert_eq!(copy.source(DirKind::Runtime), Some(DirSource::Override));
+
+    std::fs::remove_dir_all(&dest).unwrap();
+}
+
+#[test]
+fn config_or_home_uses_the_snapshot() {
+    let mut dirs = UserDirs::default_for_home(Path::new(HOME));
+    assert_eq!(dirs.config_or_home(), dirs.config());
+
+    let config = std::env::temp_dir().join("custom-config");
+    dirs.set_config(config.clone()).unwrap();
+    assert_eq!(dirs.config_or_home(), config);
+
+    let relative = UserDirs::default_for_home(Path::new("relative"));
+    assert_eq!(relative.config_or_home(), Path::new("."));
+}
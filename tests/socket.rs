@@ -0,0 +1,75 @@
+use std::{fs, path::PathBuf, process};
+
+use user_dirs::{
+    env::{clear_override, set_override},
+    socket_path,
+    test_util::EnvOverride,
+    DirKind, SocketPathError,
+};
+
+fn runtime_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("user-dirs-{name}-{}", process::id()));
+    set_override(DirKind::Runtime, dir.clone());
+    dir
+}
+
+#[test]
+fn sockets_live_in_the_runtime_dir() {
+    let runtime = runtime_dir("socket");
+    let path = socket_path("my-app", "control").unwrap();
+    clear_override(DirKind::Runtime);
+
+    assert_eq!(path, runtime.join("my-app").join("control.sock"));
+    assert!(path.parent().unwrap().is_dir());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path.parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    fs::remove_dir_all(runtime).unwrap();
+}
+
+#[test]
+fn long_socket_paths_are_rejected_before_creating_anything() {
+    let runtime = runtime_dir("socket-long");
+    let name = "s".repeat(100);
+    let result = socket_path("my-app", &name);
+    clear_override(DirKind::Runtime);
+
+    let Err(SocketPathError::TooLong { path, len, max }) = result else {
+        panic!("expected TooLong, got {result:?}");
+    };
+    assert_eq!(path, runtime.join("my-app").join(format!("{name}.sock")));
+    assert_eq!(len, path.as_os_str().len());
+    assert!(len > max);
+    assert!(!runtime.exists());
+}
+
+#[test]
+fn sockets_fall_back_to_the_temp_dir() {
+    let _env = EnvOverride::set(&[("XDG_RUNTIME_DIR", None::<&str>)]);
+
+    let path = socket_path("user-dirs-socket-fallback", "control").unwrap();
+    let dir = path.parent().unwrap();
+    assert!(dir.starts_with(std::env::temp_dir()) || cfg!(target_os = "macos"));
+    assert!(dir
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("user-dirs-socket-fallback"));
+    fs::remove_dir(dir).unwrap();
+}
+
+#[test]
+fn socket_names_must_be_single_components() {
+    assert!(matches!(
+        socket_path("my-app", "../control"),
+        Err(SocketPathError::InvalidName(name)) if name == "../control"
+    ));
+}
@@ -0,0 +1,57 @@
+use user_dirs::test_util::EnvOverride;
+
+#[test]
+fn static_dirs_are_resolved_once() {
+    let first = {
+        let _env = EnvOverride::lock();
+        assert_eq!(
+            user_dirs::config_dir_static(),
+            user_dirs::config_dir().unwrap()
+        );
+        user_dirs::config_dir_static()
+    };
+
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some("/elsewhere"))]);
+    assert!(std::ptr::eq(first, user_dirs::config_dir_static()));
+}
+
+#[test]
+fn home_path_is_shared_with_home_dir_static() {
+    let _env = EnvOverride::lock();
+    let home = user_dirs::home_path().unwrap();
+
+    assert_eq!(home, user_dirs::home_dir().unwrap());
+    assert!(std::ptr::eq(home, user_dirs::home_dir_static()));
+}
+
+#[cfg(unix)]
+#[test]
+fn cache_and_data_dirs_keep_their_first_value() {
+    {
+        let _env = EnvOverride::set(&[
+            ("XDG_CACHE_HOME", Some("/first/cache")),
+            ("XDG_DATA_HOME", Some("/first/data")),
+        ]);
+        assert_eq!(
+            user_dirs::cache_dir_static(),
+            std::path::Path::new("/first/cache")
+        );
+        assert_eq!(
+            user_dirs::data_dir_static(),
+            std::path::Path::new("/first/data")
+        );
+    }
+
+    let _env = EnvOverride::set(&[
+        ("XDG_CACHE_HOME", Some("/second/cache")),
+        ("XDG_DATA_HOME", Some("/second/data")),
+    ]);
+    assert_eq!(
+        user_dirs::cache_dir_static(),
+        std::path::Path::new("/first/cache")
+    );
+    assert_eq!(
+        user_dirs::data_dir_static(),
+        std::path::Path::new("/first/data")
+    );
+}
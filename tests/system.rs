@@ -0,0 +1,27 @@
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+
+use user_dirs::{system, test_util::EnvOverride};
+
+#[test]
+fn system_dirs_default_to_the_fhs() {
+    let _env = EnvOverride::set(&[("STATE_DIRECTORY", None::<&str>), ("LOGS_DIRECTORY", None)]);
+
+    assert_eq!(system::state_dir("app"), PathBuf::from("/var/lib/app"));
+    assert_eq!(system::log_dir("app"), PathBuf::from("/var/log/app"));
+}
+
+#[test]
+fn systemd_directories_take_precedence() {
+    let _env = EnvOverride::set(&[(
+        "STATE_DIRECTORY",
+        Some("/var/lib/private/app:/var/lib/other"),
+    )]);
+
+    assert_eq!(
+        system::state_dir("app"),
+        PathBuf::from("/var/lib/private/app")
+    );
+    assert!(system::is_system_context());
+}
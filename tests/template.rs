@@ -0,0 +1,78 @@
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+
+use user_dirs::{contract, expand_template, test_util::EnvOverride, DirKind, UserDirsError};
+
+fn leah() -> EnvOverride {
+    EnvOverride::set(&[
+        ("HOME", Some("/home/leah")),
+        ("XDG_CACHE_HOME", None),
+        ("XDG_CONFIG_HOME", Some("/srv/config")),
+        ("XDG_DATA_HOME", None),
+        ("XDG_RUNTIME_DIR", None),
+        ("XDG_STATE_HOME", None),
+    ])
+}
+
+#[test]
+fn templates_expand_through_the_resolved_directories() {
+    let _env = leah();
+    let expand = |template| expand_template(template, false).unwrap();
+
+    assert_eq!(expand("~"), PathBuf::from("/home/leah"));
+    assert_eq!(expand("~/notes"), PathBuf::from("/home/leah/notes"));
+    assert_eq!(expand("$HOME/notes"), PathBuf::from("/home/leah/notes"));
+    assert_eq!(
+        expand("${XDG_DATA_HOME}/myapp/db.sqlite"),
+        PathBuf::from("/home/leah/.local/share/myapp/db.sqlite")
+    );
+    assert_eq!(
+        expand("$XDG_CONFIG_HOME/myapp"),
+        PathBuf::from("/srv/config/myapp")
+    );
+    assert_eq!(expand("/tmp/~/a$"), PathBuf::from("/tmp/~/a$"));
+}
+
+#[test]
+fn unknown_variables_are_kept_or_rejected() {
+    let _env = leah();
+
+    assert_eq!(
+        expand_template("${FOO}/$BAR", false).unwrap(),
+        PathBuf::from("${FOO}/$BAR")
+    );
+    assert!(matches!(
+        expand_template("${FOO}/x", true),
+        Err(UserDirsError::UnknownVariable(name)) if name == "FOO"
+    ));
+    assert!(matches!(
+        expand_template("${XDG_DATA_HOME/x", false),
+        Err(UserDirsError::InvalidTemplate(_))
+    ));
+    assert!(matches!(
+        expand_template("$XDG_RUNTIME_DIR/app.sock", false),
+        Err(UserDirsError::Unavailable(DirKind::Runtime))
+    ));
+}
+
+#[test]
+fn contraction_uses_the_longest_base_and_round_trips() {
+    let _env = leah();
+
+    for (path, contracted) in [
+        ("/home/leah/notes", "~/notes"),
+        ("/home/leah", "~"),
+        (
+            "/home/leah/.local/share/myapp/db.sqlite",
+            "${XDG_DATA_HOME}/myapp/db.sqlite",
+        ),
+        ("/home/leah/.local/state/myapp", "${XDG_STATE_HOME}/myapp"),
+        ("/home/leah/.local/bin", "~/.local/bin"),
+        ("/srv/config", "${XDG_CONFIG_HOME}"),
+        ("/opt/myapp", "/opt/myapp"),
+    ] {
+        assert_eq!(contract(Path::new(path)), contracted);
+        assert_eq!(expand_template(contracted, true).unwrap(), Path::new(path));
+    }
+}
@@ -0,0 +1,13 @@
+#![cfg(target_os = "linux")]
+
+use std::path::Path;
+
+#[test]
+fn all_users_includes_root() {
+    let users = user_dirs::all_users().unwrap();
+    let root = users.iter().find(|user| user.uid() == Some(0)).unwrap();
+
+    assert_eq!(root.name(), "root");
+    assert_eq!(root.home(), Path::new("/root"));
+    assert!(root.is_system());
+}
@@ -0,0 +1,49 @@
+#![cfg(all(target_os = "linux", feature = "watch"))]
+
+use std::{fs, path::PathBuf, sync::mpsc, time::Duration};
+
+use user_dirs::{env::set_override, DirKind, UserDirs};
+
+// This test changes the process environment directly, since the watcher's thread can't observe an `EnvOverride`, so
+// it is the only test in this binary.
+#[test]
+fn watch_reports_changed_dirs_when_they_are_touched() {
+    let home = std::env::temp_dir().join(format!("user_dirs-watch-{}", std::process::id()));
+    fs::create_dir_all(home.join(".config")).unwrap();
+    fs::create_dir_all(home.join(".local")).unwrap();
+    set_override(DirKind::Home, home.clone());
+    for var in [
+        "XDG_CACHE_HOME",
+        "XDG_CONFIG_HOME",
+        "XDG_DATA_HOME",
+        "XDG_STATE_HOME",
+        "XDG_RUNTIME_DIR",
+    ] {
+        std::env::remove_var(var);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = UserDirs::new()
+        .unwrap()
+        .watch(move |diff| sender.send(diff).unwrap())
+        .unwrap();
+    std::env::set_var("XDG_CACHE_HOME", "/srv/cache-after");
+
+    // Unrelated files in the home directory don't cause the directories to be resolved again.
+    fs::write(home.join(".bash_history"), "ls\n").unwrap();
+    assert!(receiver.recv_timeout(Duration::from_secs(2)).is_err());
+
+    fs::create_dir(home.join(".cache")).unwrap();
+    let diff = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(
+        diff.changed().collect::<Vec<_>>(),
+        [(
+            DirKind::Cache,
+            &home.join(".cache"),
+            &PathBuf::from("/srv/cache-after")
+        )]
+    );
+
+    drop(watcher);
+    fs::remove_dir_all(&home).unwrap();
+}
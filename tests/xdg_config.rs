@@ -0,0 +1,118 @@
+use user_dirs::{explain, test_util::EnvOverride, DirKind, Step, VarOutcome};
+
+#[test]
+fn relative_xdg_config_home_falls_back_to_the_default() {
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some("relative"))]);
+
+    assert_eq!(
+        user_dirs::config_dir().unwrap(),
+        user_dirs::os::config_dir().unwrap()
+    );
+    #[cfg(target_os = "linux")]
+    assert_eq!(
+        user_dirs::config_dir().unwrap(),
+        user_dirs::home_dir().unwrap().join(".config")
+    );
+}
+
+#[test]
+fn relative_xdg_config_home_is_explained() {
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some("relative"))]);
+
+    assert_eq!(
+        explain(DirKind::Config).steps()[0],
+        Step::Var {
+            name: "XDG_CONFIG_HOME".to_owned(),
+            value: Some("relative".into()),
+            outcome: VarOutcome::Relative,
+        }
+    );
+}
+
+#[test]
+fn well_known_xdg_dirs_cover_every_resolved_variable() {
+    let names: Vec<_> = user_dirs::well_known_xdg_dirs()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+
+    for name in [
+        "XDG_DATA_HOME",
+        "XDG_CONFIG_HOME",
+        "XDG_STATE_HOME",
+        "XDG_DATA_DIRS",
+        "XDG_CONFIG_DIRS",
+        "XDG_CACHE_HOME",
+        "XDG_RUNTIME_DIR",
+    ] {
+        assert!(names.contains(&name), "{name} is missing");
+    }
+}
+
+#[cfg(all(unix, feature = "cap-std"))]
+#[test]
+fn config_dir_opens_as_a_capability_handle() {
+    let root = std::env::temp_dir().join(format!("user_dirs-cap-{}", std::process::id()));
+    let _env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(root.join("config").as_os_str()))]);
+
+    assert!(matches!(
+        user_dirs::cap::open_config_dir(false),
+        Err(user_dirs::cap::OpenDirError::Io(_))
+    ));
+    let config = user_dirs::cap::open_config_dir(true).unwrap();
+    config.write("settings.toml", "key = 1").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(root.join("config").join("settings.toml")).unwrap(),
+        "key = 1"
+    );
+    assert!(config.open("../escape").is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn other_threads_do_not_observe_an_override() {
+    let config = std::env::temp_dir().join("overridden-config");
+    let env = EnvOverride::set(&[("XDG_CONFIG_HOME", Some(&config))]);
+
+    let reader = std::thread::spawn(user_dirs::config_dir);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(!reader.is_finished());
+
+    drop(env);
+    assert_ne!(reader.join().unwrap().unwrap(), config);
+}
+
+#[test]
+fn config_or_home_falls_back_to_the_current_dir() {
+    let _env = EnvOverride::set(&[
+        ("HOME", None::<&str>),
+        ("USERPROFILE", None),
+        ("HOMEDRIVE", None),
+        ("XDG_CONFIG_HOME", Some("relative")),
+    ]);
+
+    // With the `home` crate, the home directory is still found through the system's user database.
+    #[cfg(feature = "home-crate")]
+    assert_eq!(
+        user_dirs::config_or_home(),
+        user_dirs::os::config_dir().unwrap()
+    );
+    #[cfg(not(feature = "home-crate"))]
+    assert_eq!(user_dirs::config_or_home(), std::path::Path::new("."));
+}
+
+#[test]
+fn raw_xdg_values_are_returned_unprocessed() {
+    let _env = EnvOverride::set(&[
+        ("XDG_CONFIG_HOME", Some("relative")),
+        ("XDG_DATA_HOME", Some("")),
+        ("XDG_CACHE_HOME", None),
+    ]);
+
+    assert_eq!(
+        user_dirs::xdg_config_home_raw().as_deref(),
+        Some("relative")
+    );
+    assert_eq!(user_dirs::xdg_data_home_raw().as_deref(), Some(""));
+    assert_eq!(user_dirs::xdg_cache_home_raw(), None);
+}
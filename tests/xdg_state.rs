@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use user_dirs::test_util::EnvOverride;
+
+#[cfg(unix)]
+const STATE: &str = "/srv/state";
+#[cfg(windows)]
+const STATE: &str = r"D:\state";
+
+#[test]
+fn xdg_state_home_is_respected_on_every_platform() {
+    let _env = EnvOverride::set(&[("XDG_STATE_HOME", Some(STATE))]);
+
+    assert_eq!(user_dirs::state_dir().unwrap(), Some(PathBuf::from(STATE)));
+}
+
+#[cfg(unix)]
+#[test]
+fn per_host_dirs_are_named_after_the_sanitized_host_id() {
+    let _env = EnvOverride::set(&[
+        ("XDG_STATE_HOME", Some("/srv/state")),
+        ("XDG_CACHE_HOME", Some("/srv/cache")),
+        ("USER_DIRS_HOST_ID", Some(".Build Box #3.")),
+    ]);
+
+    assert_eq!(user_dirs::host_id().as_deref(), Some("build_box__3"));
+    assert_eq!(
+        user_dirs::state_dir_per_host().unwrap(),
+        Some(PathBuf::from("/srv/state/hosts/build_box__3"))
+    );
+    assert_eq!(
+        user_dirs::cache_dir_per_host().unwrap(),
+        PathBuf::from("/srv/cache/hosts/build_box__3")
+    );
+
+    std::env::set_var("USER_DIRS_HOST_ID", "");
+    let host = user_dirs::host_id().unwrap_or_else(|| "unknown".to_owned());
+    assert!(!host.starts_with('.'));
+    assert_eq!(
+        user_dirs::cache_dir_per_host().unwrap(),
+        PathBuf::from("/srv/cache/hosts").join(host)
+    );
+}